@@ -9,14 +9,15 @@
 
 use std::sync::Arc;
 
+use accesskit::{DefaultActionVerb, Role};
 use masonry::app_driver::{AppDriver, DriverCtx};
 use masonry::event_loop_runner::EventLoopRunner;
 use masonry::testing::TestHarness;
 use masonry::widget::{Align, CrossAxisAlignment, Flex, Label, SizedBox, WidgetRef};
 use masonry::{
-    assert_render_snapshot, Action, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
-    WidgetPod,
+    assert_render_snapshot, AccessCtx, AccessEvent, Action, BoxConstraints, Color, EventCtx,
+    LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point, PointerEvent, Size, StatusChange,
+    TextEvent, Widget, WidgetId, WidgetPod,
 };
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, Span};
@@ -174,6 +175,14 @@ impl Widget for CalcButton {
         self.inner.on_text_event(ctx, event);
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id() && event.action == accesskit::Action::Default {
+            ctx.submit_action(Action::Other(Arc::new(self.action)));
+            ctx.set_handled();
+        }
+        self.inner.on_access_event(ctx, event);
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         match event {
             StatusChange::HotChanged(true) => {
@@ -204,6 +213,22 @@ impl Widget for CalcButton {
         self.inner.paint(ctx, scene);
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let name = match self.action {
+            CalcAction::Digit(digit) => digit.to_string(),
+            CalcAction::Op(op) => op.to_string(),
+        };
+        let node = ctx.current_node();
+        node.set_name(name);
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.inner.accessibility(ctx);
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         smallvec![self.inner.as_dyn()]
     }
@@ -348,6 +373,7 @@ pub fn main() {
         .with_title("Simple Calculator")
         .with_resizable(true)
         .with_min_inner_size(window_size)
+        .with_visible(false)
         .build(&event_loop)
         .unwrap();
     let calc_state = CalcState {
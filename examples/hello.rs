@@ -39,6 +39,7 @@ pub fn main() {
         .with_title("Hello World!")
         .with_resizable(true)
         .with_min_inner_size(window_size)
+        .with_visible(false)
         .build(&event_loop)
         .unwrap();
 
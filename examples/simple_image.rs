@@ -37,6 +37,7 @@ pub fn main() {
         .with_title("Simple image example")
         .with_min_inner_size(window_size)
         .with_max_inner_size(window_size)
+        .with_visible(false)
         .build(&event_loop)
         .unwrap();
 
@@ -5,6 +5,9 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use crate::widget::SortOrder;
+use crate::ArcStr;
+
 // TODO - Refactor - See issue #1
 
 // TODO - TextCursor changed, ImeChanged, EnterKey, MouseEnter
@@ -18,8 +21,18 @@ pub enum Action {
     TextChanged(String),
     TextEntered(String),
     CheckboxChecked(bool),
+    SliderValueChanged(f64),
+    RadioGroupSelected(usize),
+    TableSortRequested(usize, SortOrder),
+    TableRowSelected(usize),
+    TabSelected(usize),
+    TabCloseRequested(usize),
+    DropdownSelected(usize),
+    MenuItemActivated(ArcStr),
+    DialogClosed(usize),
+    CollapsibleExpanded(bool),
     // FIXME - This is a huge hack
-    Other(Arc<dyn Any>),
+    Other(Arc<dyn Any + Send + Sync>),
 }
 
 impl PartialEq for Action {
@@ -29,6 +42,18 @@ impl PartialEq for Action {
             (Self::TextChanged(l0), Self::TextChanged(r0)) => l0 == r0,
             (Self::TextEntered(l0), Self::TextEntered(r0)) => l0 == r0,
             (Self::CheckboxChecked(l0), Self::CheckboxChecked(r0)) => l0 == r0,
+            (Self::SliderValueChanged(l0), Self::SliderValueChanged(r0)) => l0 == r0,
+            (Self::RadioGroupSelected(l0), Self::RadioGroupSelected(r0)) => l0 == r0,
+            (Self::TableSortRequested(l0, l1), Self::TableSortRequested(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
+            (Self::TableRowSelected(l0), Self::TableRowSelected(r0)) => l0 == r0,
+            (Self::TabSelected(l0), Self::TabSelected(r0)) => l0 == r0,
+            (Self::TabCloseRequested(l0), Self::TabCloseRequested(r0)) => l0 == r0,
+            (Self::DropdownSelected(l0), Self::DropdownSelected(r0)) => l0 == r0,
+            (Self::MenuItemActivated(l0), Self::MenuItemActivated(r0)) => l0 == r0,
+            (Self::DialogClosed(l0), Self::DialogClosed(r0)) => l0 == r0,
+            (Self::CollapsibleExpanded(l0), Self::CollapsibleExpanded(r0)) => l0 == r0,
             #[allow(ambiguous_wide_pointer_comparisons)]
             // FIXME
             (Self::Other(val_l), Self::Other(val_r)) => Arc::ptr_eq(val_l, val_r),
@@ -44,6 +69,27 @@ impl std::fmt::Debug for Action {
             Self::TextChanged(text) => f.debug_tuple("TextChanged").field(text).finish(),
             Self::TextEntered(text) => f.debug_tuple("TextEntered").field(text).finish(),
             Self::CheckboxChecked(b) => f.debug_tuple("CheckboxChecked").field(b).finish(),
+            Self::SliderValueChanged(value) => {
+                f.debug_tuple("SliderValueChanged").field(value).finish()
+            }
+            Self::RadioGroupSelected(idx) => {
+                f.debug_tuple("RadioGroupSelected").field(idx).finish()
+            }
+            Self::TableSortRequested(column, order) => f
+                .debug_tuple("TableSortRequested")
+                .field(column)
+                .field(order)
+                .finish(),
+            Self::TableRowSelected(row) => f.debug_tuple("TableRowSelected").field(row).finish(),
+            Self::TabSelected(idx) => f.debug_tuple("TabSelected").field(idx).finish(),
+            Self::TabCloseRequested(idx) => f.debug_tuple("TabCloseRequested").field(idx).finish(),
+            Self::DropdownSelected(idx) => f.debug_tuple("DropdownSelected").field(idx).finish(),
+            Self::MenuItemActivated(id) => f.debug_tuple("MenuItemActivated").field(id).finish(),
+            Self::DialogClosed(idx) => f.debug_tuple("DialogClosed").field(idx).finish(),
+            Self::CollapsibleExpanded(expanded) => f
+                .debug_tuple("CollapsibleExpanded")
+                .field(expanded)
+                .finish(),
             Self::Other(_) => write!(f, "Other(...)"),
         }
     }
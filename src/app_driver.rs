@@ -1,20 +1,173 @@
-use crate::widget::{StoreInWidgetMut, WidgetMut};
-use crate::{Action, Widget, WidgetId};
+use std::any::Any;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use winit::window::WindowBuilder;
+
+use crate::render_root::{Layer, LayerRequest};
+use crate::widget::{Dialog, StoreInWidgetMut, WidgetMut};
+use crate::{Action, Widget, WidgetId, WidgetPod};
 
 // xilem::App will implement AppDriver
 
+/// A unique identifier for a window opened by the app.
+///
+/// Unlike winit's `WindowId`, this is known as soon as the window is requested,
+/// before the platform window is created.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct WindowId(NonZeroU64);
+
 pub struct DriverCtx<'a> {
-    // TODO
+    pub(crate) window_id: WindowId,
     pub(crate) main_root_widget: WidgetMut<'a, Box<dyn Widget>>,
+    pub(crate) window_requests: &'a mut Vec<WindowRequest>,
+}
+
+/// A change to the set of open windows, requested by the [`AppDriver`].
+///
+/// Requests are applied by the event loop once the driver method returns.
+pub(crate) enum WindowRequest {
+    Open {
+        window_id: WindowId,
+        window_builder: WindowBuilder,
+        root_widget: Box<dyn Widget>,
+    },
+    Close(WindowId),
 }
 
 pub trait AppDriver {
+    /// Called when a widget submits an [`Action`].
+    ///
+    /// [`DriverCtx::window_id`] is the window the widget is in.
     fn on_action(&mut self, ctx: &mut DriverCtx<'_>, widget_id: WidgetId, action: Action);
+
+    /// Called with values submitted through [`ExtEventSink::submit_payload`](crate::ExtEventSink::submit_payload).
+    ///
+    /// The default implementation ignores them.
+    #[allow(unused_variables)]
+    fn on_ext_payload(&mut self, ctx: &mut DriverCtx<'_>, payload: Box<dyn Any + Send>) {}
+}
+
+impl WindowId {
+    /// Allocate a new, unique `WindowId`.
+    pub fn next() -> WindowId {
+        static WINDOW_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = WINDOW_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        WindowId(id.try_into().unwrap())
+    }
 }
 
 impl<'a> DriverCtx<'a> {
-    /// Return a [`WidgetMut`] to the root widget.
+    /// The window the current event comes from.
+    pub fn window_id(&self) -> WindowId {
+        self.window_id
+    }
+
+    /// Return a [`WidgetMut`] to the root widget of the current window.
     pub fn get_root<W: Widget + StoreInWidgetMut>(&mut self) -> WidgetMut<'_, W> {
         self.main_root_widget.downcast().expect("wrong widget type")
     }
+
+    /// Open a new window displaying `root_widget`.
+    ///
+    /// The window is created once the current driver method returns.
+    pub fn open_window(
+        &mut self,
+        window_builder: WindowBuilder,
+        root_widget: impl Widget,
+    ) -> WindowId {
+        let window_id = WindowId::next();
+        self.window_requests.push(WindowRequest::Open {
+            window_id,
+            window_builder,
+            root_widget: Box::new(root_widget),
+        });
+        window_id
+    }
+
+    /// Close the window `window_id`, once the current driver method returns.
+    ///
+    /// The app exits once its last window is closed.
+    pub fn close_window(&mut self, window_id: WindowId) {
+        self.window_requests.push(WindowRequest::Close(window_id));
+    }
+
+    /// Open a modal dialog above the root widget of the current window, and return
+    /// the dialog's id.
+    ///
+    /// When the user presses one of its buttons, the dialog closes and
+    /// [`on_action`](AppDriver::on_action) receives [`Action::DialogClosed`] with the
+    /// dialog's id.
+    pub fn open_dialog(&mut self, dialog: Dialog) -> WidgetId {
+        let widget = WidgetPod::new(dialog).boxed();
+        let dialog_id = widget.id();
+        Box::<dyn Widget>::get_ctx(&mut self.main_root_widget.inner)
+            .global_state
+            .layer_requests
+            .push(LayerRequest::Open(Layer::dialog(widget, None)));
+        dialog_id
+    }
+
+    /// Close a dialog of the current window without emitting an action.
+    pub fn close_dialog(&mut self, dialog_id: WidgetId) {
+        Box::<dyn Widget>::get_ctx(&mut self.main_root_widget.inner)
+            .global_state
+            .layer_requests
+            .push(LayerRequest::Close(dialog_id, None));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Label;
+
+    /// The window and root widget text of each request, or `None` for closed windows.
+    fn requested_windows(requests: &[WindowRequest]) -> Vec<(WindowId, Option<String>)> {
+        requests
+            .iter()
+            .map(|request| match request {
+                WindowRequest::Open {
+                    window_id,
+                    root_widget,
+                    ..
+                } => (*window_id, root_widget.get_debug_text()),
+                WindowRequest::Close(window_id) => (*window_id, None),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn open_and_close_windows() {
+        let main_window_id = WindowId::next();
+        let mut harness = TestHarness::create(Label::new("Main window"));
+        let mut window_requests = Vec::new();
+
+        let (window_1, window_2) = harness.edit_root_widget(|root| {
+            let mut ctx = DriverCtx {
+                window_id: main_window_id,
+                main_root_widget: root,
+                window_requests: &mut window_requests,
+            };
+            assert_eq!(ctx.window_id(), main_window_id);
+
+            let window_1 = ctx.open_window(WindowBuilder::new(), Label::new("Window 1"));
+            let window_2 = ctx.open_window(WindowBuilder::new(), Label::new("Window 2"));
+            ctx.close_window(window_1);
+            (window_1, window_2)
+        });
+
+        assert_ne!(window_1, main_window_id);
+        assert_ne!(window_1, window_2);
+        // Requests are applied in order, so window 1 is opened before being closed.
+        assert_eq!(
+            requested_windows(&window_requests),
+            vec![
+                (window_1, Some("Window 1".to_string())),
+                (window_2, Some("Window 2".to_string())),
+                (window_1, None),
+            ]
+        );
+    }
 }
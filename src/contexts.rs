@@ -7,17 +7,20 @@
 use std::any::Any;
 use std::time::Duration;
 
+use accesskit::{NodeBuilder, NodeId, TreeUpdate};
 use parley::FontContext;
 use tracing::{trace, warn};
-use winit::dpi::PhysicalPosition;
+use winit::dpi::LogicalPosition;
 use winit::window::CursorIcon;
 
 use crate::action::Action;
-use crate::promise::PromiseToken;
-use crate::render_root::{RenderRootSignal, RenderRootState};
+use crate::ext_event::ExtEventSink;
+use crate::promise::{PromiseResult, PromiseToken};
+use crate::render_root::{Layer, LayerRequest, RenderRootSignal, RenderRootState};
 use crate::text_helpers::{ImeChangeSignal, TextFieldRegistration};
-use crate::widget::{CursorChange, FocusChange, StoreInWidgetMut, WidgetMut, WidgetState};
-use crate::{Insets, Point, Rect, Size, Widget, WidgetId, WidgetPod};
+use crate::timers::TimerToken;
+use crate::widget::{CursorChange, Dialog, FocusChange, StoreInWidgetMut, WidgetMut, WidgetState};
+use crate::{Insets, Point, PopupResult, Rect, Size, Widget, WidgetId, WidgetPod};
 
 /// A macro for implementing methods on multiple contexts.
 ///
@@ -55,6 +58,10 @@ pub struct EventCtx<'a> {
     pub(crate) widget_state: &'a mut WidgetState,
     pub(crate) is_handled: bool,
     pub(crate) request_pan_to_child: Option<Rect>,
+    /// The child that is topmost under the pointer, if any.
+    ///
+    /// Other children are considered to be occluded by it, even if they contain the pointer.
+    pub(crate) hot_child: Option<WidgetId>,
 }
 
 /// A context provided to the [`lifecycle`] method on widgets.
@@ -86,10 +93,22 @@ pub struct PaintCtx<'a> {
     pub(crate) debug_widget: bool,
 }
 
-pub struct WorkerCtx<'a> {
-    // TODO
-    #[allow(dead_code)]
+/// A context passed to the [`accessibility`](crate::Widget::accessibility) method of widgets.
+pub struct AccessCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
+    pub(crate) widget_state: &'a WidgetState,
+    pub(crate) tree_update: &'a mut TreeUpdate,
+    pub(crate) current_node: NodeBuilder,
+    pub(crate) rebuild_all: bool,
+    pub(crate) scale_factor: f64,
+}
+
+/// A context given to functions running on a background thread.
+///
+/// See [`EventCtx::run_in_background`] and [`EventCtx::compute_in_background`].
+#[derive(Clone)]
+pub struct WorkerCtx {
+    pub(crate) ext_event_sink: ExtEventSink,
 }
 
 pub struct WorkerFn(pub Box<dyn FnOnce(WorkerCtx) + Send + 'static>);
@@ -99,6 +118,7 @@ impl_context_method!(
     EventCtx<'_>,
     LifeCycleCtx<'_>,
     PaintCtx<'_>,
+    AccessCtx<'_>,
     LayoutCtx<'_>,
     {
         /// get the `WidgetId` of the current widget.
@@ -126,6 +146,7 @@ impl_context_method!(
     EventCtx<'_>,
     LifeCycleCtx<'_>,
     PaintCtx<'_>,
+    AccessCtx<'_>,
     {
         /// The layout size.
         ///
@@ -348,6 +369,19 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.widget_state.needs_paint = true;
     }
 
+    /// Request an [`accessibility`] pass.
+    ///
+    /// Widgets should call this whenever a change in their state affects the node
+    /// they expose to assistive technologies, eg a checkbox being checked.
+    /// The pass always runs after layout, so changes in size or position don't need
+    /// to be signaled.
+    ///
+    /// [`accessibility`]: crate::Widget::accessibility
+    pub fn request_accessibility_update(&mut self) {
+        trace!("request_accessibility_update");
+        self.widget_state.needs_accessibility = true;
+    }
+
     /// Request a layout pass.
     ///
     /// A Widget's [`layout`] method is always called when the widget tree
@@ -379,6 +413,94 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.request_layout();
     }
 
+    /// Open a popup layer above the root widget, and return the popup's id.
+    ///
+    /// `anchor` is a rect in window coordinates, usually this widget's own rect.
+    /// The popup is placed below it, or above it if there isn't enough room below,
+    /// and is kept inside the window.
+    ///
+    /// Popups get pointer and text events before the root widget. A popup is closed
+    /// when the user clicks outside of it or presses Escape, when this widget is removed
+    /// or stashed, or with [`close_popup`](Self::close_popup); this widget then receives
+    /// [`LifeCycle::PopupClosed`](crate::LifeCycle::PopupClosed).
+    pub fn open_popup(&mut self, widget: impl Widget, anchor: Rect) -> WidgetId {
+        let widget = WidgetPod::new(widget).boxed();
+        let popup_id = widget.id();
+        trace!("open_popup {:?} anchor={:?}", popup_id, anchor);
+        self.global_state
+            .layer_requests
+            .push(LayerRequest::Open(Layer::popup(
+                widget,
+                Some(self.widget_state.id),
+                anchor,
+            )));
+        popup_id
+    }
+
+    /// Open a modal dialog above the root widget, and return the dialog's id.
+    ///
+    /// The dialog is centered in the window, and the content below it is dimmed.
+    /// Until it's closed, the widgets below it don't get pointer events and can't
+    /// get focus. The dialog closes itself when one of its buttons is pressed; this
+    /// widget then receives [`LifeCycle::PopupClosed`](crate::LifeCycle::PopupClosed).
+    pub fn open_dialog(&mut self, dialog: Dialog) -> WidgetId {
+        let widget = WidgetPod::new(dialog).boxed();
+        let dialog_id = widget.id();
+        trace!("open_dialog {:?}", dialog_id);
+        self.global_state
+            .layer_requests
+            .push(LayerRequest::Open(Layer::dialog(
+                widget,
+                Some(self.widget_state.id),
+            )));
+        dialog_id
+    }
+
+    /// Open a tooltip above the root widget, and return its id.
+    ///
+    /// The tooltip is placed next to `anchor` like a popup, but the pointer goes through
+    /// it, and clicks and Escape don't close it. Close it with [`close_popup`](Self::close_popup).
+    /// It's also closed when this widget is removed or stashed; this widget then receives
+    /// [`LifeCycle::PopupClosed`](crate::LifeCycle::PopupClosed).
+    pub fn open_tooltip(&mut self, widget: impl Widget, anchor: Rect) -> WidgetId {
+        let widget = WidgetPod::new(widget).boxed();
+        let tooltip_id = widget.id();
+        trace!("open_tooltip {:?} anchor={:?}", tooltip_id, anchor);
+        self.global_state
+            .layer_requests
+            .push(LayerRequest::Open(Layer::tooltip(
+                widget,
+                self.widget_state.id,
+                anchor,
+            )));
+        tooltip_id
+    }
+
+    /// Close a popup opened with [`open_popup`](Self::open_popup), along with the
+    /// popups opened above it.
+    ///
+    /// Widgets inside the popup can close it too, given its id.
+    pub fn close_popup(&mut self, popup_id: WidgetId) {
+        trace!("close_popup {:?}", popup_id);
+        self.global_state
+            .layer_requests
+            .push(LayerRequest::Close(popup_id, None));
+    }
+
+    /// Close a popup like [`close_popup`](Self::close_popup), passing `result` to the
+    /// widget which opened it.
+    ///
+    /// The widget gets the result in [`LifeCycle::PopupClosed`](crate::LifeCycle::PopupClosed),
+    /// and can read it with [`PopupResult::get`](crate::PopupResult::get). This is how a
+    /// popup tells its owner what the user picked.
+    pub fn close_popup_with_result(&mut self, popup_id: WidgetId, result: impl Any + Send + Sync) {
+        trace!("close_popup_with_result {:?}", popup_id);
+        self.global_state.layer_requests.push(LayerRequest::Close(
+            popup_id,
+            Some(PopupResult::new(result)),
+        ));
+    }
+
     /// Set the disabled state for this widget.
     ///
     /// Setting this to `false` does not mean a widget is not still disabled; for instance it may
@@ -402,14 +524,29 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.children_changed();
     }
 
-    #[allow(unused)]
     /// Indicate that text input state has changed.
     ///
     /// A widget that accepts text input should call this anytime input state
     /// (such as the text or the selection) changes as a result of a non text-input
     /// event.
+    ///
+    /// This has no effect if the widget isn't focused.
     pub fn invalidate_text_input(&mut self, event: ImeChangeSignal) {
-        todo!("invalidate_text_input");
+        trace!("invalidate_text_input {:?}", event);
+        if !self.is_focused() {
+            return;
+        }
+        if event == ImeChangeSignal::Reset {
+            // The platform can't reset an IME session, so we start a new one.
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::EndIme);
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::StartIme);
+        }
+        // Send the IME area again, even if it didn't move.
+        self.global_state.ime_area = None;
     }
 });
 
@@ -434,12 +571,19 @@ impl_context_method!(
         ///
         /// The function takes a [`WorkerCtx`] which it can use to
         /// communicate with the main thread.
+        ///
+        /// If the function panics, the panic is logged. The app waits for background
+        /// tasks to return before it exits.
         pub fn run_in_background(
             &mut self,
-            _background_task: impl FnOnce(WorkerCtx) + Send + 'static,
+            background_task: impl FnOnce(WorkerCtx) + Send + 'static,
         ) {
-            // TODO - Use RenderRootSignal::SpawnWorker
-            todo!("run_in_background");
+            trace!("run_in_background");
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::SpawnWorker(WorkerFn(Box::new(
+                    background_task,
+                ))));
         }
 
         /// Run the provided function in the background, and send its result once it's done.
@@ -447,33 +591,91 @@ impl_context_method!(
         /// The function takes a [`WorkerCtx`] which it can use to
         /// communicate with the main thread.
         ///
-        /// Once the function returns, an [`Event::PromiseResult`](crate::Event::PromiseResult)
-        /// is emitted with the return value.
+        /// Once the function returns, this widget receives a
+        /// [`LifeCycle::PromiseResult`](crate::LifeCycle::PromiseResult) event
+        /// with the return value, which can be retrieved with the returned token.
+        /// If the function panics, the widget gets no event.
         pub fn compute_in_background<T: Any + Send>(
             &mut self,
-            _background_task: impl FnOnce(WorkerCtx) -> T + Send + 'static,
+            background_task: impl FnOnce(WorkerCtx) -> T + Send + 'static,
         ) -> PromiseToken<T> {
-            // TODO - Use RenderRootSignal::SpawnWorker
-            todo!("compute_in_background");
+            trace!("compute_in_background");
+            let token = PromiseToken::<T>::new();
+            let widget_id = self.widget_state.id;
+            self.run_in_background(move |ctx| {
+                let main_thread = ctx.clone();
+                let payload = background_task(ctx);
+                main_thread.resolve_promise(token.make_result(payload), widget_id);
+            });
+            token
+        }
+
+        /// Set the area the IME candidate window should avoid, in local coordinates.
+        ///
+        /// Text fields should set this to the rect of their caret or selection, and
+        /// update it when it moves. It defaults to the widget's layout rect.
+        ///
+        /// This only has an effect on widgets registered with
+        /// [`register_as_text_input`](LifeCycleCtx::register_as_text_input).
+        pub fn set_ime_area(&mut self, area: Rect) {
+            trace!("set_ime_area {:?}", area);
+            self.widget_state.ime_area = Some(area);
         }
 
         /// Request a timer event.
         ///
-        /// The return value is a token, which can be used to associate the
+        /// Once `deadline` has elapsed, this widget will receive a [`LifeCycle::Timer`]
+        /// event carrying the returned token, which can be used to associate the
         /// request with the event.
-        pub fn request_timer(&mut self, _deadline: Duration) -> TimerToken {
-            todo!("request_timer");
+        ///
+        /// [`LifeCycle::Timer`]: crate::LifeCycle::Timer
+        pub fn request_timer(&mut self, deadline: Duration) -> TimerToken {
+            trace!("request_timer deadline={:?}", deadline);
+            self.global_state.timers.add(deadline, self.widget_state.id)
         }
     }
 );
 
-// FIXME - Remove
-pub struct TimerToken;
+impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, {
+    /// Request keyboard focus.
+    ///
+    /// Because only one widget can be focused at a time, multiple focus requests
+    /// from different widgets during a single event cycle means that the last
+    /// widget that requests focus will override the previous requests.
+    ///
+    /// See [`is_focused`](Self::is_focused) for more information about focus.
+    pub fn request_focus(&mut self) {
+        trace!("request_focus");
+        // We need to send the request even if we're currently focused,
+        // because we may have a sibling widget that already requested focus
+        // and we have no way of knowing that yet. We need to override that
+        // to deliver on the "last focus request wins" promise.
+        let id = self.widget_id();
+        self.widget_state.request_focus = Some(FocusChange::Focus(id));
+    }
+
+    /// Transfer focus to the widget with the given `WidgetId`.
+    ///
+    /// See [`is_focused`](Self::is_focused) for more information about focus.
+    pub fn set_focus(&mut self, target: WidgetId) {
+        trace!("set_focus target={:?}", target);
+        self.widget_state.request_focus = Some(FocusChange::Focus(target));
+    }
+});
 
 impl EventCtx<'_> {
     /// Send a signal to parent widgets to scroll this widget into view.
     pub fn request_pan_to_this(&mut self) {
-        self.request_pan_to_child = Some(self.widget_state.layout_rect());
+        self.request_pan_to_rect(self.widget_state.size.to_rect());
+    }
+
+    /// Send a signal to parent widgets to scroll part of this widget into view.
+    ///
+    /// `rect` is in this widget's local coordinates. This is useful for large
+    /// widgets, eg to keep a text editor's caret visible.
+    pub fn request_pan_to_rect(&mut self, rect: Rect) {
+        trace!("request_pan_to_rect({:?})", rect);
+        self.request_pan_to_child = Some(rect);
     }
 
     /// Set the "active" state of the widget.
@@ -497,31 +699,6 @@ impl EventCtx<'_> {
         self.is_handled
     }
 
-    /// Request keyboard focus.
-    ///
-    /// Because only one widget can be focused at a time, multiple focus requests
-    /// from different widgets during a single event cycle means that the last
-    /// widget that requests focus will override the previous requests.
-    ///
-    /// See [`is_focused`](Self::is_focused) for more information about focus.
-    pub fn request_focus(&mut self) {
-        trace!("request_focus");
-        // We need to send the request even if we're currently focused,
-        // because we may have a sibling widget that already requested focus
-        // and we have no way of knowing that yet. We need to override that
-        // to deliver on the "last focus request wins" promise.
-        let id = self.widget_id();
-        self.widget_state.request_focus = Some(FocusChange::Focus(id));
-    }
-
-    /// Transfer focus to the widget with the given `WidgetId`.
-    ///
-    /// See [`is_focused`](Self::is_focused) for more information about focus.
-    pub fn set_focus(&mut self, target: WidgetId) {
-        trace!("set_focus target={:?}", target);
-        self.widget_state.request_focus = Some(FocusChange::Focus(target));
-    }
-
     /// Transfer focus to the next focusable widget.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -601,7 +778,13 @@ impl LifeCycleCtx<'_> {
     }
 
     /// Register this widget as accepting text input.
+    ///
+    /// When a registered widget is focused, the platform's input method editor (IME)
+    /// is enabled, and the widget receives [`TextEvent::Ime`](crate::TextEvent::Ime) events.
+    ///
+    /// This should only be called in response to a [`LifeCycle::WidgetAdded`](crate::LifeCycle::WidgetAdded) event.
     pub fn register_as_text_input(&mut self) {
+        trace!("register_as_text_input");
         let registration = TextFieldRegistration {
             widget_id: self.widget_id(),
         };
@@ -649,6 +832,27 @@ impl LayoutCtx<'_> {
         self.widget_state.baseline_offset = baseline
     }
 
+    /// Request a [`LifeCycle::PostLayout`](crate::LifeCycle::PostLayout) event once
+    /// this layout pass is done.
+    ///
+    /// Children can't be added during layout. Widgets which find out during layout
+    /// that they need new children, eg a list scrolled to rows which don't exist yet,
+    /// can add them in that event, before the next paint.
+    pub fn request_post_layout(&mut self) {
+        trace!("request_post_layout");
+        self.global_state
+            .post_layout_requests
+            .push(self.widget_state.id);
+    }
+
+    /// The size of the window, in logical pixels.
+    ///
+    /// With [`WindowSizePolicy::Content`](crate::render_root::WindowSizePolicy::Content), this is
+    /// the size computed by the previous layout pass.
+    pub fn window_size(&self) -> Size {
+        self.global_state.window_size
+    }
+
     /// Set the position of a child widget, in the paren't coordinate space. This
     /// will also implicitly change "hot" status and affect the parent's display rect.
     ///
@@ -661,9 +865,7 @@ impl LayoutCtx<'_> {
         self.widget_state.local_paint_rect =
             self.widget_state.local_paint_rect.union(child.paint_rect());
 
-        let mouse_pos = self
-            .mouse_pos
-            .map(|pos| PhysicalPosition::new(pos.x, pos.y));
+        let mouse_pos = self.mouse_pos.map(|pos| LogicalPosition::new(pos.x, pos.y));
         // if the widget has moved, it may have moved under the mouse, in which
         // case we need to handle that.
         if WidgetPod::update_hot_state(
@@ -677,7 +879,7 @@ impl LayoutCtx<'_> {
     }
 }
 
-impl_context_method!(LayoutCtx<'_>, PaintCtx<'_>, {
+impl_context_method!(EventCtx<'_>, LayoutCtx<'_>, PaintCtx<'_>, {
     pub fn font_ctx(&mut self) -> &mut FontContext {
         &mut self.global_state.font_context
     }
@@ -696,3 +898,57 @@ impl PaintCtx<'_> {
         self.depth
     }
 }
+
+impl AccessCtx<'_> {
+    /// The accessibility node of the current widget.
+    ///
+    /// Its role, bounds, children and disabled state are already filled in. Widgets
+    /// can add other properties, such as a name or the actions they support.
+    pub fn current_node(&mut self) -> &mut NodeBuilder {
+        &mut self.current_node
+    }
+
+    /// The number of physical pixels per logical pixel.
+    ///
+    /// Node bounds are expressed in physical pixels.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Add the node of a child that [`Widget::children`] doesn't return, eg a
+    /// scrollbar, to the current node.
+    ///
+    /// Nodes of the children it returns are added automatically.
+    pub fn add_extra_child(&mut self, child: &mut WidgetPod<impl Widget>) {
+        self.current_node.push_child(child.id().into());
+        child.accessibility(self);
+    }
+
+    /// Add a node which doesn't belong to a widget, eg a table row grouping some
+    /// of the current widget's children.
+    ///
+    /// The widget must make it a child of its own node, or of another node added
+    /// this way, and push it again every time its own node is rebuilt. `id` must not
+    /// clash with a widget's; take it from [`WidgetId::next`].
+    pub fn push_node(&mut self, id: NodeId, node: NodeBuilder) {
+        let node = node.build(&mut self.global_state.access_node_classes);
+        self.tree_update.nodes.push((id, node));
+    }
+}
+
+impl WorkerCtx {
+    /// Send the result of a computation to a widget.
+    ///
+    /// The widget will receive it as a [`LifeCycle::PromiseResult`](crate::LifeCycle::PromiseResult)
+    /// event.
+    pub fn resolve_promise(&self, result: PromiseResult, target: WidgetId) {
+        // This only fails if the app has shut down, in which case nobody
+        // is waiting for the result anyway.
+        let _ = self.ext_event_sink.resolve_promise(result, target);
+    }
+
+    /// Return a handle which can be used to submit other events to the main thread.
+    pub fn ext_event_sink(&self) -> ExtEventSink {
+        self.ext_event_sink.clone()
+    }
+}
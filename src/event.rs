@@ -5,12 +5,15 @@
 //! Events.
 
 use crate::kurbo::Rect;
+use crate::promise::PromiseResult;
 // TODO - See issue #14
-use crate::WidgetId;
+use crate::{TimerToken, WidgetId};
 
+use std::any::Any;
+use std::sync::Arc;
 use std::{collections::HashSet, path::PathBuf};
 
-use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::dpi::{LogicalPosition, PhysicalSize};
 use winit::event::{Ime, KeyEvent, Modifiers, MouseButton};
 use winit::keyboard::ModifiersState;
 
@@ -40,7 +43,7 @@ pub enum PointerEvent {
     PointerMove(PointerState),
     PointerEnter(PointerState),
     PointerLeave(PointerState),
-    MouseWheel(PhysicalPosition<f64>, PointerState),
+    MouseWheel(LogicalPosition<f64>, PointerState),
     HoverFile(PathBuf, PointerState),
     DropFile(PathBuf, PointerState),
     HoverFileCancel(PointerState),
@@ -57,11 +60,26 @@ pub enum TextEvent {
     FocusChange(bool),
 }
 
+/// A request from an assistive technology, such as a screen reader.
+///
+/// Access events are routed to their target widget, through its ancestors.
+#[derive(Debug, Clone)]
+pub struct AccessEvent {
+    /// The widget the action is aimed at.
+    pub target: WidgetId,
+    /// The action requested.
+    pub action: accesskit::Action,
+    /// Extra parameters for some actions, eg the offset for [`SetScrollOffset`](accesskit::Action::SetScrollOffset).
+    ///
+    /// Positions are in logical pixels.
+    pub data: Option<accesskit::ActionData>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PointerState {
     // TODO
     // pub device_id: DeviceId,
-    pub position: PhysicalPosition<f64>,
+    pub position: LogicalPosition<f64>,
     pub buttons: HashSet<MouseButton>,
     pub mods: Modifiers,
     pub count: u8,
@@ -136,10 +154,50 @@ pub enum LifeCycle {
     /// [`focus_prev`]: crate::EventCtx::focus_prev
     BuildFocusChain,
 
-    /// Called when a child widgets uses
-    /// [`EventCtx::request_pan_to_this`](crate::EventCtx::request_pan_to_this).
+    /// Called when this widget or one of its descendants uses
+    /// [`EventCtx::request_pan_to_this`](crate::EventCtx::request_pan_to_this)
+    /// or [`EventCtx::request_pan_to_rect`](crate::EventCtx::request_pan_to_rect).
+    ///
+    /// The rect is in this widget's coordinate space.
     RequestPanToChild(Rect),
 
+    /// Called when a timer requested with [`request_timer`] expires.
+    ///
+    /// This event is only sent to the widget which requested the timer; the token
+    /// matches the one returned by [`request_timer`].
+    ///
+    /// [`request_timer`]: crate::EventCtx::request_timer
+    Timer(TimerToken),
+
+    /// Called when a background task started with [`compute_in_background`] completes.
+    ///
+    /// This event is only sent to the widget which started the task. Use the
+    /// [`PromiseToken`](crate::promise::PromiseToken) returned by
+    /// [`compute_in_background`] to retrieve the value.
+    ///
+    /// [`compute_in_background`]: crate::EventCtx::compute_in_background
+    PromiseResult(PromiseResult),
+
+    /// Called when a popup opened with [`open_popup`] is closed.
+    ///
+    /// This event is only sent to the widget which opened the popup, whether the
+    /// popup was closed with [`close_popup`] or dismissed by the user. It carries
+    /// the id returned by [`open_popup`], and the result the popup was closed with,
+    /// if it was closed with [`close_popup_with_result`].
+    ///
+    /// [`open_popup`]: crate::EventCtx::open_popup
+    /// [`close_popup`]: crate::EventCtx::close_popup
+    /// [`close_popup_with_result`]: crate::EventCtx::close_popup_with_result
+    PopupClosed(WidgetId, Option<PopupResult>),
+
+    /// Called after a layout pass in which the widget used [`request_post_layout`].
+    ///
+    /// The event is sent before the next paint, so the widget can add children it
+    /// found missing during layout; they are laid out in another pass before painting.
+    ///
+    /// [`request_post_layout`]: crate::LayoutCtx::request_post_layout
+    PostLayout,
+
     /// Internal Masonry lifecycle event.
     ///
     /// This should always be passed down to descendant [`WidgetPod`]s.
@@ -173,6 +231,38 @@ pub enum InternalLifeCycle {
 
     /// The parents widget origin in window coordinate space has changed.
     ParentWindowOrigin,
+
+    /// Used to route the `Timer` event to the widget which requested it.
+    RouteTimer {
+        /// the token returned by `request_timer`
+        token: TimerToken,
+        /// the widget that requested the timer
+        widget_id: WidgetId,
+    },
+
+    /// Used to route the `PromiseResult` event to the widget which started the task.
+    RoutePromiseResult {
+        /// the value computed by the background task
+        result: PromiseResult,
+        /// the widget that started the task
+        widget_id: WidgetId,
+    },
+
+    /// Used to route the `PopupClosed` event to the widget which opened the popup.
+    RoutePopupClosed {
+        /// the id returned by `open_popup`
+        popup_id: WidgetId,
+        /// the result the popup was closed with
+        result: Option<PopupResult>,
+        /// the widget that opened the popup
+        widget_id: WidgetId,
+    },
+
+    /// Used to route the `PostLayout` event to the widget which requested it.
+    RoutePostLayout {
+        /// the widget that requested it
+        widget_id: WidgetId,
+    },
 }
 
 /// Event indicating status changes within the widget hierarchy.
@@ -201,6 +291,30 @@ pub enum StatusChange {
     FocusChanged(bool),
 }
 
+/// The value a popup was closed with, passed to the widget which opened it in
+/// [`LifeCycle::PopupClosed`].
+///
+/// See [`EventCtx::close_popup_with_result`](crate::EventCtx::close_popup_with_result).
+#[derive(Clone)]
+pub struct PopupResult(Arc<dyn Any + Send + Sync>);
+
+impl PopupResult {
+    pub(crate) fn new(value: impl Any + Send + Sync) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// The value the popup was closed with, if it has type `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for PopupResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PopupResult").finish_non_exhaustive()
+    }
+}
+
 impl PointerEvent {
     pub fn pointer_state(&self) -> &PointerState {
         match self {
@@ -242,6 +356,24 @@ impl TextEvent {
     }
 }
 
+impl AccessEvent {
+    pub fn short_name(&self) -> &'static str {
+        match self.action {
+            accesskit::Action::Default => "Default",
+            accesskit::Action::Focus => "Focus",
+            accesskit::Action::Blur => "Blur",
+            accesskit::Action::Increment => "Increment",
+            accesskit::Action::Decrement => "Decrement",
+            accesskit::Action::ScrollUp => "ScrollUp",
+            accesskit::Action::ScrollDown => "ScrollDown",
+            accesskit::Action::ScrollIntoView => "ScrollIntoView",
+            accesskit::Action::SetScrollOffset => "SetScrollOffset",
+            accesskit::Action::SetValue => "SetValue",
+            _ => "Other",
+        }
+    }
+}
+
 impl PointerState {
     pub fn empty() -> Self {
         #[cfg(FALSE)]
@@ -254,7 +386,7 @@ impl PointerState {
         let device_id = unsafe { DeviceId::dummy() };
 
         PointerState {
-            position: PhysicalPosition::new(0.0, 0.0),
+            position: LogicalPosition::new(0.0, 0.0),
             buttons: Default::default(),
             mods: Default::default(),
             count: 0,
@@ -281,6 +413,10 @@ impl LifeCycle {
             LifeCycle::DisabledChanged(_) => true,
             LifeCycle::BuildFocusChain => false,
             LifeCycle::RequestPanToChild(_) => false,
+            LifeCycle::Timer(_) => true,
+            LifeCycle::PromiseResult(_) => true,
+            LifeCycle::PopupClosed(..) => true,
+            LifeCycle::PostLayout => true,
         }
     }
 
@@ -294,12 +430,20 @@ impl LifeCycle {
                 InternalLifeCycle::RouteFocusChanged { .. } => "RouteFocusChanged",
                 InternalLifeCycle::RouteDisabledChanged => "RouteDisabledChanged",
                 InternalLifeCycle::ParentWindowOrigin => "ParentWindowOrigin",
+                InternalLifeCycle::RouteTimer { .. } => "RouteTimer",
+                InternalLifeCycle::RoutePromiseResult { .. } => "RoutePromiseResult",
+                InternalLifeCycle::RoutePopupClosed { .. } => "RoutePopupClosed",
+                InternalLifeCycle::RoutePostLayout { .. } => "RoutePostLayout",
             },
             LifeCycle::WidgetAdded => "WidgetAdded",
             LifeCycle::AnimFrame(_) => "AnimFrame",
             LifeCycle::DisabledChanged(_) => "DisabledChanged",
             LifeCycle::BuildFocusChain => "BuildFocusChain",
             LifeCycle::RequestPanToChild(_) => "RequestPanToChild",
+            LifeCycle::Timer(_) => "Timer",
+            LifeCycle::PromiseResult(_) => "PromiseResult",
+            LifeCycle::PopupClosed(..) => "PopupClosed",
+            LifeCycle::PostLayout => "PostLayout",
         }
     }
 }
@@ -317,7 +461,11 @@ impl InternalLifeCycle {
         match self {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
-            | InternalLifeCycle::RouteDisabledChanged => true,
+            | InternalLifeCycle::RouteDisabledChanged
+            | InternalLifeCycle::RouteTimer { .. }
+            | InternalLifeCycle::RoutePromiseResult { .. }
+            | InternalLifeCycle::RoutePopupClosed { .. }
+            | InternalLifeCycle::RoutePostLayout { .. } => true,
             InternalLifeCycle::ParentWindowOrigin => false,
         }
     }
@@ -1,147 +1,438 @@
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
+use accesskit::{ActionHandler, ActionRequest};
+use accesskit_winit::Adapter;
+use kurbo::Affine;
 use tracing::warn;
 use vello::util::{RenderContext, RenderSurface};
 use vello::{peniko::Color, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
 use wgpu::PresentMode;
-use winit::dpi::PhysicalPosition;
+use winit::dpi::LogicalPosition;
 use winit::error::EventLoopError;
-use winit::event::WindowEvent as WinitWindowEvent;
-use winit::event_loop::EventLoop;
-use winit::window::Window;
+use winit::event::{Event as WinitEvent, WindowEvent as WinitWindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget};
+use winit::window::{Window, WindowId as WinitWindowId};
 
-use crate::app_driver::{AppDriver, DriverCtx};
+use crate::app_driver::{AppDriver, DriverCtx, WindowId, WindowRequest};
 use crate::event::{PointerState, WindowEvent};
+use crate::ext_event::ExtEventSink;
 use crate::render_root::{self, RenderRoot, WindowSizePolicy};
+use crate::worker_pool::WorkerPool;
 use crate::{PointerEvent, TextEvent, Widget};
 
 pub struct EventLoopRunner {
     window: Arc<Window>,
+    window_id: WindowId,
     event_loop: EventLoop<()>,
     render_root: RenderRoot,
     app_driver: Box<dyn AppDriver>,
 }
 
-struct MainState<'a> {
-    window: Arc<Window>,
+struct MainState {
     render_cx: RenderContext,
-    surface: RenderSurface<'a>,
+    windows: HashMap<WindowId, WindowState>,
+    winit_window_ids: HashMap<WinitWindowId, WindowId>,
+    window_requests: Vec<WindowRequest>,
+    app_driver: Box<dyn AppDriver>,
+    worker_pool: WorkerPool,
+    event_loop_proxy: EventLoopProxy<()>,
+}
+
+/// Everything related to a single open window.
+struct WindowState {
+    window: Arc<Window>,
+    surface: RenderSurface<'static>,
     renderer: Option<Renderer>,
+    render_root: RenderRoot,
     pointer_state: PointerState,
-    app_driver: Box<dyn AppDriver>,
+    accesskit_adapter: Adapter,
+}
+
+/// Forwards requests from assistive technologies to a window's [`RenderRoot`].
+///
+/// Requests arrive on another thread, so they go through the [`ExtEventSink`].
+struct AccessActionHandler(ExtEventSink);
+
+impl ActionHandler for AccessActionHandler {
+    fn do_action(&mut self, request: ActionRequest) {
+        if self.0.submit_access_action(request).is_err() {
+            warn!("Cannot forward accessibility action: window is closed.");
+        }
+    }
 }
 
 impl EventLoopRunner {
+    /// Create a runner displaying `root_widget` in `window`.
+    ///
+    /// The window must have been created invisible, with [`WindowBuilder::with_visible(false)`];
+    /// it is made visible once its accessibility adapter is set up.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if the window is already visible. Platforms that can't
+    /// tell whether a window is visible aren't checked.
+    ///
+    /// [`WindowBuilder::with_visible(false)`]: winit::window::WindowBuilder::with_visible
     pub fn new(
         root_widget: impl Widget,
         window: Window,
         event_loop: EventLoop<()>,
         app_driver: impl AppDriver + 'static,
     ) -> Self {
+        if window.is_visible() == Some(true) {
+            debug_panic!(
+                "EventLoopRunner::new: the window must be created with `with_visible(false)`, \
+                so that its accessibility adapter is set up before it is shown"
+            );
+        }
+        let mut render_root = RenderRoot::new(root_widget, WindowSizePolicy::User);
+        render_root
+            .state
+            .ext_event_queue
+            .set_waker(event_loop.create_proxy());
         Self {
             window: Arc::new(window),
+            window_id: WindowId::next(),
             event_loop,
-            render_root: RenderRoot::new(root_widget, WindowSizePolicy::User),
+            render_root,
             app_driver: Box::new(app_driver),
         }
     }
 
+    /// The id of the window passed to [`new`](Self::new).
+    pub fn main_window_id(&self) -> WindowId {
+        self.window_id
+    }
+
+    /// Return a handle other threads can use to send events to the main window.
+    ///
+    /// Once the event loop has exited, submitting events through the handle returns an error.
+    pub fn ext_event_sink(&self) -> ExtEventSink {
+        self.render_root.ext_event_sink()
+    }
+
     pub fn run(self) -> Result<(), EventLoopError> {
-        let mut render_cx = RenderContext::new().unwrap();
-        let size = self.window.inner_size();
-        let surface = pollster::block_on(render_cx.create_surface(
-            self.window.clone(),
-            size.width,
-            size.height,
-            PresentMode::AutoVsync,
-        ))
-        .unwrap();
-        let mut render_root = self.render_root;
         let mut main_state = MainState {
-            window: self.window,
-            render_cx,
-            surface,
-            renderer: None,
-            pointer_state: PointerState::empty(),
+            render_cx: RenderContext::new().unwrap(),
+            windows: HashMap::new(),
+            winit_window_ids: HashMap::new(),
+            window_requests: Vec::new(),
             app_driver: self.app_driver,
+            worker_pool: WorkerPool::new(),
+            event_loop_proxy: self.event_loop.create_proxy(),
         };
+        main_state.add_window(self.window_id, self.window, self.render_root);
 
         self.event_loop.run(move |event, window_target| {
-            if let winit::event::Event::WindowEvent { event: e, .. } = event {
-                match e {
-                    WinitWindowEvent::RedrawRequested => {
-                        let scene = render_root.redraw();
-                        main_state.render(scene);
-                    }
-                    WinitWindowEvent::CloseRequested => window_target.exit(),
-                    WinitWindowEvent::Resized(size) => {
-                        render_root.handle_window_event(WindowEvent::Resize(size));
+            match event {
+                WinitEvent::WindowEvent {
+                    window_id: winit_window_id,
+                    event: e,
+                } => {
+                    let Some(&window_id) = main_state.winit_window_ids.get(&winit_window_id) else {
+                        return;
+                    };
+                    main_state.handle_window_event(window_id, e);
+                    main_state.process_signals(window_id);
+                }
+                // Sent by ExtEventSink, including the ones used by background workers.
+                WinitEvent::UserEvent(()) => {
+                    for window_id in main_state.window_ids() {
+                        let window = main_state.windows.get_mut(&window_id).unwrap();
+                        window.render_root.handle_ext_events();
+                        main_state.process_signals(window_id);
                     }
-                    WinitWindowEvent::ModifiersChanged(modifiers) => {
-                        render_root.handle_text_event(TextEvent::ModifierChange(modifiers.state()));
+                }
+                WinitEvent::AboutToWait => {
+                    for window_id in main_state.window_ids() {
+                        let window = main_state.windows.get_mut(&window_id).unwrap();
+                        window.render_root.handle_timers();
+                        main_state.process_signals(window_id);
                     }
-                    WinitWindowEvent::CursorMoved { position, .. } => {
-                        main_state.pointer_state.position = position;
-                        render_root.handle_pointer_event(PointerEvent::PointerMove(
-                            main_state.pointer_state.clone(),
-                        ));
+
+                    // Sleep until the next event or the next timer, whichever comes first.
+                    let next_deadline = main_state
+                        .windows
+                        .values()
+                        .filter_map(|window| window.render_root.next_timer_deadline())
+                        .min();
+                    match next_deadline {
+                        Some(deadline) => {
+                            window_target.set_control_flow(ControlFlow::WaitUntil(deadline))
+                        }
+                        None => window_target.set_control_flow(ControlFlow::Wait),
                     }
-                    WinitWindowEvent::CursorLeft { .. } => {
-                        render_root.handle_pointer_event(PointerEvent::PointerLeave(
-                            main_state.pointer_state.clone(),
-                        ));
+                }
+                _ => (),
+            }
+
+            main_state.process_window_requests(window_target);
+            if main_state.windows.is_empty() {
+                window_target.exit();
+            }
+        })
+    }
+}
+
+impl MainState {
+    fn window_ids(&self) -> Vec<WindowId> {
+        self.windows.keys().copied().collect()
+    }
+
+    fn add_window(
+        &mut self,
+        window_id: WindowId,
+        window: Arc<Window>,
+        mut render_root: RenderRoot,
+    ) {
+        let size = window.inner_size();
+        render_root.handle_window_event(WindowEvent::Rescale(window.scale_factor()));
+        render_root.handle_window_event(WindowEvent::Resize(size));
+
+        // AccessKit asks for the tree from another thread once an assistive technology
+        // connects. We answer with a placeholder, and send the full tree on the next redraw.
+        let placeholder_tree = render_root.placeholder_access_tree();
+        let ext_event_sink = render_root.ext_event_sink();
+        let accesskit_adapter = Adapter::with_action_handler(
+            &window,
+            move || {
+                let _ = ext_event_sink.request_access_tree();
+                placeholder_tree
+            },
+            Box::new(AccessActionHandler(render_root.ext_event_sink())),
+        );
+        // The adapter must be created before the window is shown.
+        window.set_visible(true);
+
+        let surface = pollster::block_on(self.render_cx.create_surface(
+            window.clone(),
+            size.width,
+            size.height,
+            PresentMode::AutoVsync,
+        ))
+        .unwrap();
+        self.winit_window_ids.insert(window.id(), window_id);
+        self.windows.insert(
+            window_id,
+            WindowState {
+                window,
+                surface,
+                renderer: None,
+                render_root,
+                pointer_state: PointerState::empty(),
+                accesskit_adapter,
+            },
+        );
+    }
+
+    fn remove_window(&mut self, window_id: WindowId) {
+        let Some(window) = self.windows.remove(&window_id) else {
+            warn!("Cannot close window {window_id:?}: window not found.");
+            return;
+        };
+        self.winit_window_ids.remove(&window.window.id());
+    }
+
+    fn handle_window_event(&mut self, window_id: WindowId, event: WinitWindowEvent) {
+        let window = self.windows.get_mut(&window_id).unwrap();
+        window
+            .accesskit_adapter
+            .process_event(&window.window, &event);
+        let render_root = &mut window.render_root;
+        match event {
+            WinitWindowEvent::RedrawRequested => {
+                let (scene, tree_update) = render_root.redraw();
+                let scale_factor = render_root.scale_factor();
+                window.accesskit_adapter.update_if_active(|| tree_update);
+                window.render(&mut self.render_cx, scene, scale_factor);
+            }
+            WinitWindowEvent::CloseRequested => {
+                self.window_requests.push(WindowRequest::Close(window_id));
+            }
+            WinitWindowEvent::Resized(size) => {
+                render_root.handle_window_event(WindowEvent::Resize(size));
+            }
+            WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                render_root.handle_window_event(WindowEvent::Rescale(scale_factor));
+            }
+            WinitWindowEvent::ModifiersChanged(modifiers) => {
+                window.pointer_state.mods = modifiers;
+                render_root.handle_text_event(TextEvent::ModifierChange(modifiers.state()));
+            }
+            // Synthetic events are sent for keys held while the window gains or loses focus;
+            // they aren't actual key presses.
+            WinitWindowEvent::KeyboardInput {
+                event,
+                is_synthetic: false,
+                ..
+            } => {
+                let modifiers = window.pointer_state.mods.state();
+                render_root.handle_text_event(TextEvent::KeyboardKey(event, modifiers));
+            }
+            WinitWindowEvent::Ime(ime) => {
+                render_root.handle_text_event(TextEvent::Ime(ime));
+            }
+            WinitWindowEvent::CursorMoved { position, .. } => {
+                window.pointer_state.position = position.to_logical(render_root.scale_factor());
+                render_root
+                    .handle_pointer_event(PointerEvent::PointerMove(window.pointer_state.clone()));
+            }
+            WinitWindowEvent::CursorLeft { .. } => {
+                render_root
+                    .handle_pointer_event(PointerEvent::PointerLeave(window.pointer_state.clone()));
+            }
+            WinitWindowEvent::MouseInput { state, button, .. } => match state {
+                winit::event::ElementState::Pressed => {
+                    render_root.handle_pointer_event(PointerEvent::PointerDown(
+                        button,
+                        window.pointer_state.clone(),
+                    ));
+                }
+                winit::event::ElementState::Released => {
+                    render_root.handle_pointer_event(PointerEvent::PointerUp(
+                        button,
+                        window.pointer_state.clone(),
+                    ));
+                }
+            },
+            WinitWindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => (x as f64, y as f64),
+                    winit::event::MouseScrollDelta::PixelDelta(delta) => {
+                        let delta = delta.to_logical(render_root.scale_factor());
+                        (delta.x, delta.y)
                     }
-                    WinitWindowEvent::MouseInput { state, button, .. } => match state {
-                        winit::event::ElementState::Pressed => {
-                            render_root.handle_pointer_event(PointerEvent::PointerDown(
-                                button,
-                                main_state.pointer_state.clone(),
-                            ));
-                        }
-                        winit::event::ElementState::Released => {
-                            render_root.handle_pointer_event(PointerEvent::PointerUp(
-                                button,
-                                main_state.pointer_state.clone(),
-                            ));
-                        }
-                    },
-                    WinitWindowEvent::MouseWheel { delta, .. } => {
-                        let delta = match delta {
-                            winit::event::MouseScrollDelta::LineDelta(x, y) => (x as f64, y as f64),
-                            winit::event::MouseScrollDelta::PixelDelta(delta) => (delta.x, delta.y),
+                };
+                let delta = LogicalPosition::new(delta.0, delta.1);
+                render_root.handle_pointer_event(PointerEvent::MouseWheel(
+                    delta,
+                    window.pointer_state.clone(),
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    /// Open and close the windows requested by the [`AppDriver`].
+    fn process_window_requests(&mut self, window_target: &EventLoopWindowTarget<()>) {
+        while !self.window_requests.is_empty() {
+            for request in std::mem::take(&mut self.window_requests) {
+                match request {
+                    WindowRequest::Open {
+                        window_id,
+                        window_builder,
+                        root_widget,
+                    } => {
+                        // The window is shown once its accessibility adapter is set up.
+                        let window_builder = window_builder.with_visible(false);
+                        let window = match window_builder.build(window_target) {
+                            Ok(window) => window,
+                            Err(err) => {
+                                warn!("Cannot open window {window_id:?}: {err}");
+                                continue;
+                            }
                         };
-                        let delta = PhysicalPosition::new(delta.0, delta.1);
-                        render_root.handle_pointer_event(PointerEvent::MouseWheel(
-                            delta,
-                            main_state.pointer_state.clone(),
-                        ));
+                        let mut render_root = RenderRoot::new(root_widget, WindowSizePolicy::User);
+                        render_root
+                            .state
+                            .ext_event_queue
+                            .set_waker(self.event_loop_proxy.clone());
+                        self.add_window(window_id, Arc::new(window), render_root);
+                        // The new window may already have signals from WidgetAdded.
+                        self.process_signals(window_id);
+                    }
+                    WindowRequest::Close(window_id) => {
+                        self.remove_window(window_id);
                     }
-                    _ => (),
                 }
-                main_state.process_signals(&mut render_root);
             }
-        })
+        }
+    }
+
+    fn process_signals(&mut self, window_id: WindowId) {
+        let Some(window) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        while let Some(signal) = window.render_root.pop_signal() {
+            match signal {
+                render_root::RenderRootSignal::Action(action, widget_id) => {
+                    window.render_root.edit_root_widget(|root| {
+                        let mut driver_ctx = DriverCtx {
+                            window_id,
+                            main_root_widget: root,
+                            window_requests: &mut self.window_requests,
+                        };
+                        self.app_driver
+                            .on_action(&mut driver_ctx, widget_id, action);
+                    });
+                }
+                render_root::RenderRootSignal::StartIme => {
+                    window.window.set_ime_allowed(true);
+                }
+                render_root::RenderRootSignal::EndIme => {
+                    window.window.set_ime_allowed(false);
+                }
+                render_root::RenderRootSignal::ImeMoved(position, size) => {
+                    window.window.set_ime_cursor_area(position, size);
+                }
+                render_root::RenderRootSignal::RequestRedraw => {
+                    window.window.request_redraw();
+                }
+                render_root::RenderRootSignal::RequestAnimFrame => {
+                    // TODO
+                    window.window.request_redraw();
+                }
+                render_root::RenderRootSignal::SpawnWorker(worker_fn) => {
+                    let worker_ctx = window.render_root.worker_ctx();
+                    self.worker_pool.spawn(move || (worker_fn.0)(worker_ctx));
+                }
+                render_root::RenderRootSignal::ExtPayload(payload) => {
+                    window.render_root.edit_root_widget(|root| {
+                        let mut driver_ctx = DriverCtx {
+                            window_id,
+                            main_root_widget: root,
+                            window_requests: &mut self.window_requests,
+                        };
+                        self.app_driver.on_ext_payload(&mut driver_ctx, payload);
+                    });
+                }
+                render_root::RenderRootSignal::TakeFocus => {
+                    window.window.focus_window();
+                }
+                render_root::RenderRootSignal::SetCursor(cursor_icon) => {
+                    window.window.set_cursor_icon(cursor_icon);
+                }
+                render_root::RenderRootSignal::SetSize(size) => {
+                    // TODO - Handle return value?
+                    let _ = window.window.request_inner_size(size);
+                }
+                render_root::RenderRootSignal::SetTitle(title) => {
+                    window.window.set_title(&title);
+                }
+            }
+        }
     }
 }
 
-impl MainState<'_> {
-    fn render(&mut self, scene: Scene) {
-        //let scale = self.window.scale_factor();
+impl WindowState {
+    fn render(&mut self, render_cx: &mut RenderContext, scene: Scene, scale_factor: f64) {
         let size = self.window.inner_size();
         let width = size.width;
         let height = size.height;
 
         if self.surface.config.width != width || self.surface.config.height != height {
-            self.render_cx
-                .resize_surface(&mut self.surface, width, height);
+            render_cx.resize_surface(&mut self.surface, width, height);
         }
 
-        #[cfg(FALSE)]
-        let transform = if scale != 1.0 {
-            Some(Affine::scale(scale))
+        // The scene is in logical pixels; the surface is in physical pixels.
+        let scene = if scale_factor != 1.0 {
+            let mut scaled_scene = Scene::new();
+            scaled_scene.append(&scene, Some(Affine::scale(scale_factor)));
+            scaled_scene
         } else {
-            None
+            scene
         };
 
         let Ok(surface_texture) = self.surface.surface.get_current_texture() else {
@@ -149,8 +440,8 @@ impl MainState<'_> {
             return;
         };
         let dev_id = self.surface.dev_id;
-        let device = &self.render_cx.devices[dev_id].device;
-        let queue = &self.render_cx.devices[dev_id].queue;
+        let device = &render_cx.devices[dev_id].device;
+        let queue = &render_cx.devices[dev_id].queue;
         let renderer_options = RendererOptions {
             surface_format: Some(self.surface.format),
             use_cpu: false,
@@ -174,61 +465,4 @@ impl MainState<'_> {
         surface_texture.present();
         device.poll(wgpu::Maintain::Wait);
     }
-
-    fn process_signals(&mut self, render_root: &mut RenderRoot) {
-        while let Some(signal) = render_root.pop_signal() {
-            match signal {
-                render_root::RenderRootSignal::Action(action, widget_id) => {
-                    render_root.edit_root_widget(|root| {
-                        let mut driver_ctx = DriverCtx {
-                            main_root_widget: root,
-                        };
-                        self.app_driver
-                            .on_action(&mut driver_ctx, widget_id, action);
-                    });
-                }
-                render_root::RenderRootSignal::TextFieldAdded => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::TextFieldRemoved => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::TextFieldFocused => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::ImeStarted => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::ImeMoved => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::ImeInvalidated => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::RequestRedraw => {
-                    self.window.request_redraw();
-                }
-                render_root::RenderRootSignal::RequestAnimFrame => {
-                    // TODO
-                    self.window.request_redraw();
-                }
-                render_root::RenderRootSignal::SpawnWorker(_worker_fn) => {
-                    // TODO
-                }
-                render_root::RenderRootSignal::TakeFocus => {
-                    self.window.focus_window();
-                }
-                render_root::RenderRootSignal::SetCursor(cursor_icon) => {
-                    self.window.set_cursor_icon(cursor_icon);
-                }
-                render_root::RenderRootSignal::SetSize(size) => {
-                    // TODO - Handle return value?
-                    let _ = self.window.request_inner_size(size);
-                }
-                render_root::RenderRootSignal::SetTitle(title) => {
-                    self.window.set_title(&title);
-                }
-            }
-        }
-    }
 }
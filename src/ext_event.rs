@@ -3,32 +3,37 @@
 // details.
 
 //! Simple handle for submitting external events.
-#![allow(unused)]
 
 use std::any::Any;
 use std::collections::VecDeque;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
-use druid_shell::IdleHandle;
+use accesskit::ActionRequest;
+use winit::event_loop::EventLoopProxy;
 
+use crate::action::Action;
 use crate::promise::PromiseResult;
 use crate::widget::WidgetId;
 
-// FIXME - Remove
-pub struct WindowId;
-
 pub(crate) enum ExtMessage {
-    Promise(PromiseResult, WidgetId, WindowId),
+    Promise(PromiseResult, WidgetId),
+    Action(Action, WidgetId),
+    Payload(Box<dyn Any + Send>),
+    AccessAction(ActionRequest),
+    AccessTreeRequested,
 }
 
-/// A thing that can move into other threads and be used to submit commands back
+/// A thing that can move into other threads and be used to submit events back
 /// to the running application.
 ///
+/// Each submitted event wakes up the event loop, if there is one.
+///
 /// This API is preliminary, and may be changed or removed without warning.
 #[derive(Clone)]
 pub struct ExtEventSink {
-    queue: Arc<Mutex<VecDeque<ExtMessage>>>,
-    handle: Arc<Mutex<Option<IdleHandle>>>,
+    // We only keep a weak reference, so that we can tell when the app has shut down.
+    queue: Weak<Mutex<VecDeque<ExtMessage>>>,
+    waker: Option<EventLoopProxy<()>>,
 }
 
 /// The stuff that we hold onto inside the app that is related to the
@@ -37,13 +42,10 @@ pub struct ExtEventSink {
 pub(crate) struct ExtEventQueue {
     /// A shared queue of items that have been sent to us.
     queue: Arc<Mutex<VecDeque<ExtMessage>>>,
-    /// This doesn't exist when the app starts and it can go away if a window closes, so we keep a
-    /// reference here and can update it when needed. Note that this reference is shared with all
-    /// `ExtEventSink`s, so that we can update them too.
-    handle: Arc<Mutex<Option<IdleHandle>>>,
-    /// The window that the handle belongs to, so we can keep track of when
-    /// we need to get a new handle.
-    pub(crate) handle_window_id: Option<WindowId>,
+    /// Used to wake up the event loop when an item is sent.
+    ///
+    /// This is `None` when running without an event loop, eg in unit tests.
+    waker: Option<EventLoopProxy<()>>,
 }
 
 /// An error that occurs if an external event cannot be submitted.
@@ -58,48 +60,101 @@ impl ExtEventQueue {
 
     pub(crate) fn make_sink(&self) -> ExtEventSink {
         ExtEventSink {
-            queue: self.queue.clone(),
-            handle: self.handle.clone(),
+            queue: Arc::downgrade(&self.queue),
+            waker: self.waker.clone(),
         }
     }
 
-    pub(crate) fn set_idle(&mut self, handle: IdleHandle, window_id: WindowId) {
-        self.handle.lock().unwrap().replace(handle);
-        self.handle_window_id = Some(window_id);
+    /// Set the proxy used to wake up the event loop.
+    ///
+    /// Only sinks created after this call will wake up the event loop.
+    pub(crate) fn set_waker(&mut self, waker: EventLoopProxy<()>) {
+        self.waker = Some(waker);
     }
 
-    pub(crate) fn has_pending_items(&self) -> bool {
-        !self.queue.lock().unwrap().is_empty()
-    }
-
-    pub(crate) fn recv(&mut self) -> Option<ExtMessage> {
-        self.queue.lock().unwrap().pop_front()
+    /// Take every item sent since the last call.
+    pub(crate) fn drain(&mut self) -> Vec<ExtMessage> {
+        self.queue.lock().unwrap().drain(..).collect()
     }
 }
 
 impl ExtEventSink {
-    #[allow(missing_docs)]
+    /// Send the result of a computation to the given widget.
+    ///
+    /// The widget will receive it as a [`LifeCycle::PromiseResult`](crate::LifeCycle::PromiseResult)
+    /// event.
     pub fn resolve_promise(
         &self,
         result: PromiseResult,
         target_widget: WidgetId,
-        target_window: WindowId,
     ) -> Result<(), ExtEventError> {
-        if let Some(handle) = self.handle.lock().unwrap().as_mut() {
-            todo!()
+        self.send(ExtMessage::Promise(result, target_widget))
+    }
+
+    /// Submit an [`Action`], as if it had been submitted by the given widget.
+    ///
+    /// The action will be passed to the [`AppDriver`](crate::app_driver::AppDriver).
+    pub fn submit_action(
+        &self,
+        action: Action,
+        source_widget: WidgetId,
+    ) -> Result<(), ExtEventError> {
+        self.send(ExtMessage::Action(action, source_widget))
+    }
+
+    /// Submit an arbitrary value to the application.
+    ///
+    /// The value will be passed to [`AppDriver::on_ext_payload`](crate::app_driver::AppDriver::on_ext_payload).
+    pub fn submit_payload(&self, payload: impl Any + Send) -> Result<(), ExtEventError> {
+        self.send(ExtMessage::Payload(Box::new(payload)))
+    }
+
+    /// Forward a request from an assistive technology.
+    pub(crate) fn submit_access_action(&self, request: ActionRequest) -> Result<(), ExtEventError> {
+        self.send(ExtMessage::AccessAction(request))
+    }
+
+    /// Ask for the whole accessibility tree to be sent on the next redraw.
+    pub(crate) fn request_access_tree(&self) -> Result<(), ExtEventError> {
+        self.send(ExtMessage::AccessTreeRequested)
+    }
+
+    fn send(&self, message: ExtMessage) -> Result<(), ExtEventError> {
+        let queue = self.queue.upgrade().ok_or(ExtEventError)?;
+        queue.lock().map_err(|_| ExtEventError)?.push_back(message);
+        if let Some(waker) = &self.waker {
+            waker.send_event(()).map_err(|_| ExtEventError)?;
         }
-        self.queue
-            .lock()
-            .map_err(|_| ExtEventError)?
-            .push_back(ExtMessage::Promise(result, target_widget, target_window));
         Ok(())
     }
 }
 
 impl std::fmt::Display for ExtEventError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Window missing for external event")
+        write!(f, "Event loop missing for external event")
     }
 }
 
 impl std::error::Error for ExtEventError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn sink_is_send() {
+        assert_send::<ExtEventSink>();
+    }
+
+    #[test]
+    fn send_after_shutdown() {
+        let queue = ExtEventQueue::new();
+        let sink = queue.make_sink();
+
+        sink.submit_payload(42).unwrap();
+        drop(queue);
+        assert!(sink.submit_payload(42).is_err());
+    }
+}
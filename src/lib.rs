@@ -95,6 +95,9 @@
 #[doc(inline)]
 pub use kurbo;
 
+#[doc(inline)]
+pub use accesskit;
+
 #[macro_use]
 mod util;
 
@@ -103,13 +106,16 @@ mod bloom;
 mod box_constraints;
 mod contexts;
 mod event;
+mod ext_event;
 pub mod paint_scene_helpers;
 pub mod promise;
 pub mod render_root;
 pub mod testing;
 pub mod text_helpers;
 pub mod theme;
+mod timers;
 pub mod widget;
+mod worker_pool;
 
 // TODO
 pub mod app_driver;
@@ -119,9 +125,14 @@ pub mod event_loop_runner;
 
 pub use action::Action;
 pub use box_constraints::BoxConstraints;
-pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx};
-pub use event::{InternalLifeCycle, LifeCycle, PointerEvent, StatusChange, TextEvent, WindowTheme};
+pub use contexts::{AccessCtx, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx, WorkerCtx};
+pub use event::{
+    AccessEvent, InternalLifeCycle, LifeCycle, PointerEvent, PopupResult, StatusChange, TextEvent,
+    WindowTheme,
+};
+pub use ext_event::{ExtEventError, ExtEventSink};
 pub use kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
+pub use timers::TimerToken;
 pub use util::{AsAny, Handled};
 pub use vello::peniko::{Color, Gradient};
 pub use widget::{BackgroundBrush, Widget, WidgetId, WidgetPod, WidgetState};
@@ -1,36 +1,58 @@
+use std::any::Any;
 use std::collections::VecDeque;
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
+use accesskit::{ActionData, NodeBuilder, NodeClassSet, NodeId, Role, Tree, TreeUpdate};
 use instant::Instant;
 use kurbo::Affine;
 use parley::FontContext;
 use tracing::{info_span, warn};
 use vello::peniko::{Color, Fill};
 use vello::Scene;
-use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
+use winit::event::ElementState;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
 use winit::window::CursorIcon;
 
-use crate::contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx, WorkerFn};
+use crate::contexts::{
+    AccessCtx, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx, WorkerCtx, WorkerFn,
+};
 use crate::debug_logger::DebugLogger;
-use crate::event::{PointerEvent, TextEvent, WindowEvent};
-use crate::kurbo::Point;
-use crate::widget::{FocusChange, StoreInWidgetMut, WidgetMut, WidgetState};
+use crate::event::{AccessEvent, PointerEvent, TextEvent, WindowEvent};
+use crate::ext_event::{ExtEventQueue, ExtEventSink, ExtMessage};
+use crate::kurbo::{Point, Rect, Size};
+use crate::text_helpers::TextFieldRegistration;
+use crate::timers::TimerQueue;
+use crate::widget::{Dialog, FocusChange, StoreInWidgetMut, WidgetMut, WidgetRef, WidgetState};
 use crate::{
-    Action, BoxConstraints, Handled, InternalLifeCycle, LifeCycle, Widget, WidgetId, WidgetPod,
+    theme, Action, BoxConstraints, Handled, InternalLifeCycle, LifeCycle, PopupResult, Widget,
+    WidgetId, WidgetPod,
 };
 
 // TODO - Remove pub(crate)
 pub struct RenderRoot {
     pub(crate) root: WidgetPod<Box<dyn Widget>>,
+    /// The popup layers above the root widget, from bottom to top.
+    pub(crate) layers: Vec<Layer>,
     pub(crate) size_policy: WindowSizePolicy,
     pub(crate) size: PhysicalSize<u32>,
-    // TODO - Currently this is always 1.0
-    // kurbo coordinates are assumed to be in logical pixels
+    /// The number of physical pixels per logical pixel.
+    ///
+    /// Layout and paint happen in logical pixels; `size` is in physical pixels.
     pub(crate) scale_factor: f64,
     /// Is `Some` if the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
-    pub(crate) last_mouse_pos: Option<PhysicalPosition<f64>>,
+    pub(crate) last_mouse_pos: Option<LogicalPosition<f64>>,
     pub(crate) cursor_icon: CursorIcon,
+    /// If `true`, the next accessibility pass rebuilds every node instead of
+    /// only the ones which changed.
+    pub(crate) rebuild_access_tree: bool,
+    /// The root of the accessibility tree: a window node whose children are the root
+    /// widget and the popup layers.
+    pub(crate) window_node_id: NodeId,
+    /// If `true`, the next accessibility pass rebuilds the window node, because
+    /// popups were opened or closed.
+    pub(crate) window_node_changed: bool,
     pub(crate) state: RenderRootState,
 }
 
@@ -39,6 +61,93 @@ pub(crate) struct RenderRootState {
     pub(crate) signal_queue: VecDeque<RenderRootSignal>,
     pub(crate) focused_widget: Option<WidgetId>,
     pub(crate) font_context: FontContext,
+    pub(crate) timers: TimerQueue,
+    pub(crate) ext_event_queue: ExtEventQueue,
+    pub(crate) access_node_classes: NodeClassSet,
+    /// The widgets which registered with [`register_as_text_input`](LifeCycleCtx::register_as_text_input).
+    pub(crate) text_fields: Vec<TextFieldRegistration>,
+    /// The IME area last sent to the platform, in window coordinates.
+    ///
+    /// `None` if no text field is focused, or if the area must be sent again.
+    pub(crate) ime_area: Option<Rect>,
+    /// The logical size of the window, as of the last layout pass.
+    pub(crate) window_size: Size,
+    /// Popups opened and closed since the requests were last applied.
+    pub(crate) layer_requests: Vec<LayerRequest>,
+    /// The widgets which used [`request_post_layout`](LayoutCtx::request_post_layout)
+    /// during the current layout pass.
+    pub(crate) post_layout_requests: Vec<WidgetId>,
+}
+
+/// A popup above the root widget, opened with [`EventCtx::open_popup`], a dialog
+/// opened with [`EventCtx::open_dialog`], or a tooltip opened with [`EventCtx::open_tooltip`].
+pub(crate) struct Layer {
+    pub(crate) widget: WidgetPod<Box<dyn Widget>>,
+    /// The widget which opened the popup. It's told when the popup closes.
+    pub(crate) owner: Option<WidgetId>,
+    /// The rect the popup is placed next to, in window coordinates.
+    ///
+    /// `None` for dialogs, which are centered in the window.
+    pub(crate) anchor: Option<Rect>,
+    /// Whether the layers below don't get pointer events or focus while this one is open.
+    pub(crate) modal: bool,
+    /// Whether the layer takes the pointer, and is closed by clicks outside of it or Escape.
+    ///
+    /// Tooltips aren't interactive: the pointer goes through them.
+    pub(crate) interactive: bool,
+    /// The widget which had focus when the layer was opened.
+    pub(crate) previous_focus: Option<WidgetId>,
+}
+
+impl Layer {
+    pub(crate) fn popup(
+        widget: WidgetPod<Box<dyn Widget>>,
+        owner: Option<WidgetId>,
+        anchor: Rect,
+    ) -> Self {
+        Self {
+            widget,
+            owner,
+            anchor: Some(anchor),
+            modal: false,
+            interactive: true,
+            previous_focus: None,
+        }
+    }
+
+    pub(crate) fn tooltip(
+        widget: WidgetPod<Box<dyn Widget>>,
+        owner: WidgetId,
+        anchor: Rect,
+    ) -> Self {
+        Self {
+            widget,
+            owner: Some(owner),
+            anchor: Some(anchor),
+            modal: false,
+            interactive: false,
+            previous_focus: None,
+        }
+    }
+
+    pub(crate) fn dialog(widget: WidgetPod<Box<dyn Widget>>, owner: Option<WidgetId>) -> Self {
+        Self {
+            widget,
+            owner,
+            anchor: None,
+            modal: true,
+            interactive: true,
+            previous_focus: None,
+        }
+    }
+}
+
+pub(crate) enum LayerRequest {
+    Open(Layer),
+    /// Close the popup with this id, and the ones above it.
+    ///
+    /// The result is passed to the owner of that popup.
+    Close(WidgetId, Option<PopupResult>),
 }
 
 /// Defines how a windows size should be determined
@@ -55,19 +164,19 @@ pub enum WindowSizePolicy {
 }
 
 // TODO - Handle custom cursors?
-// TODO - handling timers
-// TODO - Text fields
 pub enum RenderRootSignal {
     Action(Action, WidgetId),
-    TextFieldAdded,
-    TextFieldRemoved,
-    TextFieldFocused,
-    ImeStarted,
-    ImeMoved,
-    ImeInvalidated,
+    /// A text field gained focus; the platform should start sending IME events.
+    StartIme,
+    /// The focused text field lost focus; the platform should stop sending IME events.
+    EndIme,
+    /// The area the IME candidate window should avoid, usually the caret or selection.
+    ImeMoved(LogicalPosition<f64>, LogicalSize<f64>),
     RequestRedraw,
     RequestAnimFrame,
     SpawnWorker(WorkerFn),
+    /// A value submitted with [`ExtEventSink::submit_payload`].
+    ExtPayload(Box<dyn Any + Send>),
     TakeFocus,
     SetCursor(CursorIcon),
     SetSize(PhysicalSize<u32>),
@@ -78,17 +187,31 @@ impl RenderRoot {
     pub fn new(root_widget: impl Widget, size_policy: WindowSizePolicy) -> Self {
         let mut root = RenderRoot {
             root: WidgetPod::new(root_widget).boxed(),
+            layers: Vec::new(),
             size_policy,
             size: PhysicalSize::new(0, 0),
             scale_factor: 1.0,
             last_anim: None,
             last_mouse_pos: None,
             cursor_icon: CursorIcon::Default,
+            rebuild_access_tree: true,
+            // The window node isn't a widget, but it takes an id from the same
+            // counter so that it can't clash with one.
+            window_node_id: NodeId::from(WidgetId::next()),
+            window_node_changed: true,
             state: RenderRootState {
                 debug_logger: DebugLogger::new(false),
                 signal_queue: VecDeque::new(),
                 focused_widget: None,
                 font_context: FontContext::default(),
+                timers: TimerQueue::new(),
+                ext_event_queue: ExtEventQueue::new(),
+                access_node_classes: NodeClassSet::new(),
+                text_fields: Vec::new(),
+                ime_area: None,
+                window_size: Size::ZERO,
+                layer_requests: Vec::new(),
+                post_layout_requests: Vec::new(),
             },
         };
 
@@ -105,9 +228,13 @@ impl RenderRoot {
 
     pub fn handle_window_event(&mut self, event: WindowEvent) -> Handled {
         match event {
-            WindowEvent::Rescale(_scale) => {
-                // TODO - Handle dpi scaling
-                // For now we're assuming the scale factor is always 1.0
+            WindowEvent::Rescale(scale_factor) => {
+                self.scale_factor = scale_factor;
+                // The physical size is unchanged, but the logical size isn't.
+                self.root.state.needs_layout = true;
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::RequestRedraw);
                 Handled::No
             }
             WindowEvent::Resize(size) => {
@@ -144,7 +271,106 @@ impl RenderRoot {
         self.root_on_text_event(event)
     }
 
-    pub fn redraw(&mut self) -> Scene {
+    pub fn handle_access_event(&mut self, event: AccessEvent) -> Handled {
+        self.root_on_access_event(event)
+    }
+
+    /// Send a [`LifeCycle::Timer`] event for every timer whose deadline has passed.
+    ///
+    /// Timers are fired in order of their deadlines.
+    pub fn handle_timers(&mut self) {
+        for timer in self.state.timers.pop_expired() {
+            self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::RouteTimer {
+                token: timer.token,
+                widget_id: timer.widget_id,
+            }));
+        }
+    }
+
+    /// The time at which the next timer should fire, if any timer is pending.
+    ///
+    /// The platform event loop should wake up at this time and call [`handle_timers`](Self::handle_timers).
+    pub fn next_timer_deadline(&self) -> Option<Instant> {
+        self.state.timers.next_deadline()
+    }
+
+    /// Return a handle other threads can use to send events to this `RenderRoot`.
+    ///
+    /// Events sent through the handle are delivered by [`handle_ext_events`](Self::handle_ext_events).
+    pub fn ext_event_sink(&self) -> ExtEventSink {
+        self.state.ext_event_queue.make_sink()
+    }
+
+    /// Deliver every event submitted through an [`ExtEventSink`] since the last call.
+    ///
+    /// Promise results are sent to their target widget as [`LifeCycle::PromiseResult`] events.
+    /// Actions and payloads are added to the signal queue.
+    pub fn handle_ext_events(&mut self) {
+        for message in self.state.ext_event_queue.drain() {
+            match message {
+                ExtMessage::Promise(result, widget_id) => {
+                    self.root_lifecycle(LifeCycle::Internal(
+                        InternalLifeCycle::RoutePromiseResult { result, widget_id },
+                    ));
+                }
+                ExtMessage::Action(action, widget_id) => {
+                    self.state
+                        .signal_queue
+                        .push_back(RenderRootSignal::Action(action, widget_id));
+                }
+                ExtMessage::Payload(payload) => {
+                    self.state
+                        .signal_queue
+                        .push_back(RenderRootSignal::ExtPayload(payload));
+                }
+                ExtMessage::AccessAction(request) => {
+                    let Some(target) = WidgetId::from_node_id(request.target) else {
+                        warn!(
+                            "Accessibility action targets unknown node {:?}",
+                            request.target
+                        );
+                        continue;
+                    };
+                    // Widgets work in logical pixels.
+                    let data = match request.data {
+                        Some(ActionData::SetScrollOffset(offset)) => {
+                            Some(ActionData::SetScrollOffset(accesskit::Point::new(
+                                offset.x / self.scale_factor,
+                                offset.y / self.scale_factor,
+                            )))
+                        }
+                        data => data,
+                    };
+                    self.root_on_access_event(AccessEvent {
+                        target,
+                        action: request.action,
+                        data,
+                    });
+                }
+                ExtMessage::AccessTreeRequested => {
+                    self.rebuild_access_tree = true;
+                    self.state
+                        .signal_queue
+                        .push_back(RenderRootSignal::RequestRedraw);
+                }
+            }
+        }
+    }
+
+    /// Create the context passed to a [`WorkerFn`] when it's run.
+    ///
+    /// Results sent through this context are delivered by [`handle_ext_events`](Self::handle_ext_events).
+    pub(crate) fn worker_ctx(&self) -> WorkerCtx {
+        WorkerCtx {
+            ext_event_sink: self.ext_event_sink(),
+        }
+    }
+
+    /// Run the layout, paint and accessibility passes.
+    ///
+    /// Return the painted scene, and the changes to the accessibility tree since
+    /// the last call.
+    pub fn redraw(&mut self) -> (Scene, TreeUpdate) {
         // TODO - Xilem's reconciliation logic will have to be called
         // by the function that calls this
 
@@ -161,7 +387,24 @@ impl RenderRoot {
         }
 
         // TODO - Improve caching of scenes.
-        self.root_paint()
+        let scene = self.root_paint();
+        let tree_update = self.root_accessibility();
+        (scene, tree_update)
+    }
+
+    /// A minimal accessibility tree, for platform adapters which need one before
+    /// the first call to [`redraw`](Self::redraw).
+    ///
+    /// It only contains the window node; the full tree is sent by the next redraw.
+    pub fn placeholder_access_tree(&mut self) -> TreeUpdate {
+        let window_id = self.window_node_id;
+        let window_node = NodeBuilder::new(Role::Window).build(&mut self.state.access_node_classes);
+        self.rebuild_access_tree = true;
+        TreeUpdate {
+            nodes: vec![(window_id, window_node)],
+            tree: Some(Tree::new(window_id)),
+            focus: window_id,
+        }
     }
 
     pub fn pop_signal(&mut self) -> Option<RenderRootSignal> {
@@ -176,6 +419,14 @@ impl RenderRoot {
         self.state.signal_queue.remove(idx)
     }
 
+    /// The number of physical pixels per logical pixel.
+    ///
+    /// The [`Scene`] returned by [`redraw`](Self::redraw) is in logical pixels, and
+    /// must be scaled by this factor before being rendered.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
     pub fn cursor_icon(&self) -> CursorIcon {
         self.cursor_icon
     }
@@ -203,28 +454,81 @@ impl RenderRoot {
         res
     }
 
+    /// Open a modal dialog which no widget owns, and return its id.
+    pub(crate) fn open_dialog(&mut self, dialog: Dialog) -> WidgetId {
+        let widget = WidgetPod::new(dialog).boxed();
+        let dialog_id = widget.id();
+        self.state
+            .layer_requests
+            .push(LayerRequest::Open(Layer::dialog(widget, None)));
+        let mut widget_state =
+            WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+        self.post_event_processing(&mut widget_state);
+        dialog_id
+    }
+
     fn root_on_pointer_event(&mut self, event: PointerEvent) -> Handled {
         let mut widget_state =
             WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
 
+        // TODO - Only for primary pointer
+        self.last_mouse_pos = match event {
+            PointerEvent::PointerLeave(_) | PointerEvent::HoverFile(_, _) => None,
+            _ => Some(event.pointer_state().position),
+        };
+
+        // The topmost popup under the pointer takes it from the popups below and the root widget.
+        // Nothing below the topmost modal layer gets the pointer.
+        let modal_layer = self.layers.iter().rposition(|layer| layer.modal);
+        let hit_layer = self.last_mouse_pos.and_then(|pos| {
+            let pos = Point::new(pos.x, pos.y);
+            self.layers
+                .iter()
+                .rposition(|layer| layer.interactive && layer.widget.layout_rect().contains(pos))
+                .filter(|idx| modal_layer.map_or(true, |modal_idx| *idx >= modal_idx))
+        });
+
+        // Clicking outside of popups closes them, but not the modal layers.
+        if let PointerEvent::PointerDown(_, _) = event {
+            let kept_layers = hit_layer.or(modal_layer).map_or(0, |idx| idx + 1);
+            let dismissed = self.layers[kept_layers..]
+                .iter()
+                .find(|layer| layer.interactive)
+                .map(|layer| layer.widget.id());
+            if let Some(dismissed) = dismissed {
+                self.state
+                    .layer_requests
+                    .push(LayerRequest::Close(dismissed, None));
+            }
+            if hit_layer.is_none() && (dismissed.is_some() || modal_layer.is_some()) {
+                // The click only closes the popups, or is stopped by a modal layer;
+                // the widgets below don't see it.
+                self.post_event_processing(&mut widget_state);
+                return Handled::Yes;
+            }
+        }
+
         let mut ctx = EventCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
             is_handled: false,
             request_pan_to_child: None,
+            hot_child: None,
         };
-
-        // TODO - Only for primary pointer
-        self.last_mouse_pos = match event {
-            PointerEvent::PointerLeave(_) | PointerEvent::HoverFile(_, _) => None,
-            _ => Some(event.pointer_state().position),
-        };
+        if !self.layers.is_empty() {
+            let hot_layer = hit_layer.or(modal_layer);
+            ctx.hot_child =
+                Some(hot_layer.map_or(self.root.id(), |idx| self.layers[idx].widget.id()));
+        }
 
         let handled = {
             ctx.global_state
                 .debug_logger
                 .push_important_span(&format!("¨POINTER_EVENT {}", event.short_name()));
             let _span = info_span!("event").entered();
+            for layer in self.layers.iter_mut().rev() {
+                layer.widget.on_pointer_event(&mut ctx, &event);
+            }
             self.root.on_pointer_event(&mut ctx, &event);
             ctx.global_state.debug_logger.pop_span();
             Handled::from(ctx.is_handled)
@@ -242,7 +546,7 @@ impl RenderRoot {
         }
 
         self.post_event_processing(&mut widget_state);
-        self.root.as_dyn().debug_validate(false);
+        self.debug_validate_layers();
 
         handled
     }
@@ -256,6 +560,7 @@ impl RenderRoot {
             widget_state: &mut widget_state,
             is_handled: false,
             request_pan_to_child: None,
+            hot_child: None,
         };
 
         let handled = {
@@ -263,13 +568,103 @@ impl RenderRoot {
                 .debug_logger
                 .push_important_span(&format!("TEXT_EVENT {}", event.short_name()));
             let _span = info_span!("event").entered();
+            for layer in self.layers.iter_mut().rev() {
+                layer.widget.on_text_event(&mut ctx, &event);
+            }
             self.root.on_text_event(&mut ctx, &event);
             ctx.global_state.debug_logger.pop_span();
+            ctx.is_handled
+        };
+
+        let handled = match &event {
+            TextEvent::KeyboardKey(key_event, mods) if !handled => self.on_unused_key(
+                &mut widget_state,
+                &key_event.logical_key,
+                key_event.state,
+                *mods,
+            ),
+            _ => handled,
+        };
+
+        self.post_event_processing(&mut widget_state);
+        self.debug_validate_layers();
+
+        Handled::from(handled)
+    }
+
+    /// Handle a key press which no widget used, as if it came from a [`TextEvent::KeyboardKey`].
+    ///
+    /// Winit key events can't be created outside of winit, so this lets tests exercise
+    /// the Tab and Escape handling.
+    pub(crate) fn handle_unused_key(&mut self, key: &Key, mods: ModifiersState) -> Handled {
+        let mut widget_state =
+            WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+        let handled = self.on_unused_key(&mut widget_state, key, ElementState::Pressed, mods);
+
+        self.post_event_processing(&mut widget_state);
+        self.debug_validate_layers();
+
+        Handled::from(handled)
+    }
+
+    // Tab and Shift+Tab move the focus, and Escape closes the topmost popup,
+    // unless a widget used them. Dialogs handle Escape themselves.
+    fn on_unused_key(
+        &mut self,
+        widget_state: &mut WidgetState,
+        key: &Key,
+        state: ElementState,
+        mods: ModifiersState,
+    ) -> bool {
+        if let Some(focus_change) = focus_change_for_key(key, state, mods) {
+            widget_state.request_focus = Some(focus_change);
+            return true;
+        }
+        let top_layer = self.layers.iter().rev().find(|layer| layer.interactive);
+        let Some(layer) = top_layer.filter(|layer| !layer.modal) else {
+            return false;
+        };
+        if *key == Key::Named(NamedKey::Escape) && state == ElementState::Pressed {
+            self.state
+                .layer_requests
+                .push(LayerRequest::Close(layer.widget.id(), None));
+            return true;
+        }
+        false
+    }
+
+    fn root_on_access_event(&mut self, event: AccessEvent) -> Handled {
+        let mut widget_state =
+            WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+
+        let mut ctx = EventCtx {
+            global_state: &mut self.state,
+            widget_state: &mut widget_state,
+            is_handled: false,
+            request_pan_to_child: None,
+            hot_child: None,
+        };
+
+        // Assistive technologies can't reach the widgets below a modal layer either.
+        let modal_layer = self.layers.iter().rposition(|layer| layer.modal);
+
+        let handled = {
+            ctx.global_state
+                .debug_logger
+                .push_important_span(&format!("ACCESS_EVENT {}", event.short_name()));
+            let _span = info_span!("access_event").entered();
+            for layer in self.layers[modal_layer.unwrap_or(0)..].iter_mut().rev() {
+                layer.widget.on_access_event(&mut ctx, &event);
+            }
+            if modal_layer.is_none() {
+                self.root.on_access_event(&mut ctx, &event);
+            }
+            ctx.global_state.debug_logger.pop_span();
             Handled::from(ctx.is_handled)
         };
 
         self.post_event_processing(&mut widget_state);
-        self.root.as_dyn().debug_validate(false);
+        self.debug_validate_layers();
 
         handled
     }
@@ -288,6 +683,9 @@ impl RenderRoot {
                 .push_important_span(&format!("LIFECYCLE {}", event.short_name()));
             let _span = info_span!("lifecycle").entered();
             self.root.lifecycle(&mut ctx, &event);
+            for layer in &mut self.layers {
+                layer.widget.lifecycle(&mut ctx, &event);
+            }
             self.state.debug_logger.pop_span();
         }
 
@@ -298,9 +696,36 @@ impl RenderRoot {
     }
 
     pub(crate) fn root_layout(&mut self) {
+        self.layout_pass();
+
+        // Widgets which found out during layout that they need new children add them
+        // now, and are laid out again so they're never painted without them.
+        for _ in 0..MAX_POST_LAYOUT_PASSES {
+            let requests = std::mem::take(&mut self.state.post_layout_requests);
+            if requests.is_empty() {
+                return;
+            }
+            for widget_id in requests {
+                let event = LifeCycle::Internal(InternalLifeCycle::RoutePostLayout { widget_id });
+                self.root_lifecycle(event);
+            }
+            if self.root.state().needs_layout {
+                self.layout_pass();
+            }
+        }
+        if !self.state.post_layout_requests.is_empty() {
+            warn!("Widgets kept requesting post-layout events, painting the frame anyway.");
+            self.state.post_layout_requests.clear();
+        }
+    }
+
+    fn layout_pass(&mut self) {
         let mut widget_state =
             WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
         let size = self.get_kurbo_size();
+        if self.size_policy == WindowSizePolicy::User {
+            self.state.window_size = size;
+        }
         let mouse_pos = self.last_mouse_pos.map(|pos| (pos.x, pos.y).into());
         let mut layout_ctx = LayoutCtx {
             global_state: &mut self.state,
@@ -324,6 +749,7 @@ impl RenderRoot {
         layout_ctx.global_state.debug_logger.pop_span();
 
         if let WindowSizePolicy::Content = self.size_policy {
+            layout_ctx.global_state.window_size = size;
             let new_size = LogicalSize::new(size.width, size.height).to_physical(self.scale_factor);
             if self.size != new_size {
                 self.size = new_size;
@@ -335,6 +761,18 @@ impl RenderRoot {
         }
 
         layout_ctx.place_child(&mut self.root, Point::ORIGIN);
+
+        let window_size = layout_ctx.global_state.window_size;
+        for layer in &mut self.layers {
+            let bc = BoxConstraints::new(Size::ZERO, window_size);
+            let popup_size = layer.widget.layout(&mut layout_ctx, &bc);
+            let origin = match layer.anchor {
+                Some(anchor) => popup_origin(anchor, popup_size, window_size),
+                None => centered_origin(popup_size, window_size),
+            };
+            layout_ctx.place_child(&mut layer.widget, origin);
+        }
+
         self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin));
         self.post_event_processing(&mut widget_state);
     }
@@ -353,6 +791,20 @@ impl RenderRoot {
 
         let mut scene = Scene::new();
         self.root.paint(&mut ctx, &mut scene);
+        for layer in &mut self.layers {
+            // Modal layers dim everything below them.
+            if layer.modal {
+                let window_rect = ctx.global_state.window_size.to_rect();
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    theme::BACKDROP_COLOR,
+                    None,
+                    &window_rect,
+                );
+            }
+            layer.widget.paint(&mut ctx, &mut scene);
+        }
 
         // FIXME - This is a workaround to Vello panicking when given an
         // empty scene
@@ -369,45 +821,118 @@ impl RenderRoot {
         scene
     }
 
+    pub(crate) fn root_accessibility(&mut self) -> TreeUpdate {
+        let rebuild_all = std::mem::take(&mut self.rebuild_access_tree);
+        let window_id = self.window_node_id;
+        let root_id = NodeId::from(self.root.id());
+        let mut tree_update = TreeUpdate {
+            nodes: vec![],
+            tree: rebuild_all.then(|| Tree::new(window_id)),
+            focus: self.state.focused_widget.map_or(root_id, NodeId::from),
+        };
+
+        // The popups are siblings of the root widget, above it.
+        if std::mem::take(&mut self.window_node_changed) || rebuild_all {
+            let mut window_node = NodeBuilder::new(Role::Window);
+            window_node.set_children(
+                std::iter::once(root_id)
+                    .chain(
+                        self.layers
+                            .iter()
+                            .map(|layer| NodeId::from(layer.widget.id())),
+                    )
+                    .collect::<Vec<_>>(),
+            );
+            let window_node = window_node.build(&mut self.state.access_node_classes);
+            tree_update.nodes.push((window_id, window_node));
+        }
+
+        let widget_state = WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+        let mut ctx = AccessCtx {
+            global_state: &mut self.state,
+            widget_state: &widget_state,
+            tree_update: &mut tree_update,
+            current_node: NodeBuilder::default(),
+            rebuild_all,
+            scale_factor: self.scale_factor,
+        };
+
+        {
+            let _span = info_span!("accessibility").entered();
+            self.root.accessibility(&mut ctx);
+            for layer in &mut self.layers {
+                layer.widget.accessibility(&mut ctx);
+            }
+        }
+
+        tree_update
+    }
+
     fn get_kurbo_size(&self) -> kurbo::Size {
         let size = self.size.to_logical(self.scale_factor);
         kurbo::Size::new(size.width, size.height)
     }
 
     fn post_event_processing(&mut self, widget_state: &mut WidgetState) {
+        let mut closed_layers = self.apply_layer_requests(widget_state);
+
         // If children are changed during the handling of an event,
         // we need to send RouteWidgetAdded now, so that they are ready for update/layout.
         if widget_state.children_changed {
-            // TODO - Update IME handlers
-            // Send TextFieldRemoved signal
-
+            self.remove_stale_text_fields();
+            if self.close_orphaned_layers() {
+                closed_layers.extend(self.apply_layer_requests(widget_state));
+            }
             self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded));
         }
 
+        self.state
+            .text_fields
+            .append(&mut widget_state.text_registrations);
+
         if self.state.debug_logger.layout_tree.root.is_none() {
             self.state.debug_logger.layout_tree.root = Some(self.root.id().to_raw() as u32);
         }
 
-        if self.root.state().needs_window_origin && !self.root.state().needs_layout {
+        // Popups are laid out along with the root widget.
+        if self
+            .layers
+            .iter()
+            .any(|layer| layer.widget.state().needs_layout)
+        {
+            self.root.state.needs_layout = true;
+        }
+
+        if self.any_widget_state(|state| state.needs_window_origin && !state.needs_layout) {
             let event = LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin);
             self.root_lifecycle(event);
         }
 
         // Update the disabled state if necessary
         // Always do this before updating the focus-chain
-        if self.root.state().tree_disabled_changed() {
+        if self.any_widget_state(WidgetState::tree_disabled_changed) {
             let event = LifeCycle::Internal(InternalLifeCycle::RouteDisabledChanged);
             self.root_lifecycle(event);
         }
 
         // Update the focus-chain if necessary
         // Always do this before sending focus change, since this event updates the focus chain.
-        if self.root.state().update_focus_chain {
+        if self.any_widget_state(|state| state.update_focus_chain) {
             let event = LifeCycle::BuildFocusChain;
             self.root_lifecycle(event);
         }
 
         self.update_focus(widget_state);
+        self.update_ime_area();
+
+        for (popup_id, owner, result) in closed_layers {
+            let event = LifeCycle::Internal(InternalLifeCycle::RoutePopupClosed {
+                popup_id,
+                result,
+                widget_id: owner,
+            });
+            self.root_lifecycle(event);
+        }
 
         // If we need a new paint pass, make sure winit knows it.
         if self.wants_animation_frame() {
@@ -416,23 +941,149 @@ impl RenderRoot {
                 .push_back(RenderRootSignal::RequestAnimFrame);
         }
 
-        if self.root.state().needs_paint {
+        if self.any_widget_state(|state| state.needs_paint || state.needs_accessibility) {
             self.state
                 .signal_queue
                 .push_back(RenderRootSignal::RequestRedraw);
         }
+    }
 
-        #[cfg(FALSE)]
-        for ime_field in widget_state.text_registrations.drain(..) {
-            let token = self.handle.add_text_field();
-            tracing::debug!("{:?} added", token);
-            self.ime_handlers.push((token, ime_field));
+    /// `true` if `predicate` is true for the root widget or a popup.
+    fn any_widget_state(&self, predicate: impl Fn(&WidgetState) -> bool) -> bool {
+        predicate(self.root.state())
+            || self
+                .layers
+                .iter()
+                .any(|layer| predicate(layer.widget.state()))
+    }
+
+    /// Open and close the popups requested since the last call.
+    ///
+    /// Return the closed popups which have an owner, along with their owner and the
+    /// result they were closed with.
+    fn apply_layer_requests(
+        &mut self,
+        widget_state: &mut WidgetState,
+    ) -> Vec<(WidgetId, WidgetId, Option<PopupResult>)> {
+        let mut closed_layers = Vec::new();
+        for request in std::mem::take(&mut self.state.layer_requests) {
+            match request {
+                LayerRequest::Open(mut layer) => {
+                    // The focus moves into dialogs.
+                    if layer.modal {
+                        layer.previous_focus = self.state.focused_widget;
+                        widget_state.request_focus = Some(FocusChange::Next);
+                    }
+                    self.layers.push(layer);
+                }
+                LayerRequest::Close(popup_id, mut result) => {
+                    let Some(idx) = self
+                        .layers
+                        .iter()
+                        .position(|layer| layer.widget.id() == popup_id)
+                    else {
+                        // The popup was already closed.
+                        continue;
+                    };
+                    let closed: Vec<Layer> = self.layers.drain(idx..).rev().collect();
+                    for layer in closed {
+                        let had_focus = self.state.focused_widget.map_or(false, |focused| {
+                            layer.widget.as_dyn().find_widget_by_id(focused).is_some()
+                        });
+                        // The focus goes back to the widget which opened the popup.
+                        if had_focus {
+                            widget_state.request_focus = Some(
+                                layer
+                                    .owner
+                                    .or(layer.previous_focus)
+                                    .filter(|id| self.find_widget_by_id(*id).is_some())
+                                    .map_or(FocusChange::Resign, FocusChange::Focus),
+                            );
+                        }
+                        // The owner may be the reason the popup is closed, by leaving the tree.
+                        if let Some(owner) = layer
+                            .owner
+                            .filter(|id| self.find_widget_by_id(*id).is_some())
+                        {
+                            // Only the popup which was asked to close gets the result,
+                            // not the ones above it.
+                            let result = if layer.widget.id() == popup_id {
+                                result.take()
+                            } else {
+                                None
+                            };
+                            closed_layers.push((layer.widget.id(), owner, result));
+                        }
+                    }
+                }
+            }
+            widget_state.children_changed = true;
+            self.window_node_changed = true;
+        }
+        closed_layers
+    }
+
+    /// Close the popups whose owner was removed from the widget tree, or is stashed.
+    ///
+    /// Return `true` if any popup is closed.
+    fn close_orphaned_layers(&mut self) -> bool {
+        let orphans: Vec<WidgetId> = self
+            .layers
+            .iter()
+            .filter(|layer| {
+                layer
+                    .owner
+                    .map_or(false, |owner| !self.is_widget_shown(owner))
+            })
+            .map(|layer| layer.widget.id())
+            .collect();
+        let any_orphan = !orphans.is_empty();
+        self.state
+            .layer_requests
+            .extend(orphans.into_iter().map(|id| LayerRequest::Close(id, None)));
+        any_orphan
+    }
+
+    /// Whether the widget is in the root widget or a popup, and neither it nor any of
+    /// its ancestors is stashed.
+    fn is_widget_shown(&self, id: WidgetId) -> bool {
+        fn is_shown_in(widget: WidgetRef<'_, dyn Widget>, id: WidgetId) -> bool {
+            !widget.state().is_stashed
+                && (widget.id() == id
+                    // The bloom filter we're checking can return false positives.
+                    || widget.state().children.may_contain(&id)
+                        && widget
+                        .children()
+                        .into_iter()
+                        .any(|child| is_shown_in(child, id)))
+        }
+
+        is_shown_in(self.root.as_dyn(), id)
+            || self
+                .layers
+                .iter()
+                .any(|layer| is_shown_in(layer.widget.as_dyn(), id))
+    }
+
+    /// Return the widget with the given id, in the root widget or a popup.
+    pub(crate) fn find_widget_by_id(&self, id: WidgetId) -> Option<WidgetRef<'_, dyn Widget>> {
+        self.root.as_dyn().find_widget_by_id(id).or_else(|| {
+            self.layers
+                .iter()
+                .find_map(|layer| layer.widget.as_dyn().find_widget_by_id(id))
+        })
+    }
+
+    fn debug_validate_layers(&self) {
+        self.root.as_dyn().debug_validate(false);
+        for layer in &self.layers {
+            layer.widget.as_dyn().debug_validate(false);
         }
     }
 
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     fn wants_animation_frame(&self) -> bool {
-        self.root.state().request_anim
+        self.any_widget_state(|state| state.request_anim)
     }
 
     fn update_focus(&mut self, widget_state: &mut WidgetState) {
@@ -449,12 +1100,66 @@ impl RenderRoot {
                 self.root_lifecycle(event);
                 self.state.focused_widget = new;
 
-                // TODO - Handle IME
-                // Send TextFieldFocused(focused_widget) signal
+                // The accessibility tree update carries the new focus.
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::RequestRedraw);
+
+                let was_text_field = old.map_or(false, |id| self.is_text_field(id));
+                let is_text_field = new.map_or(false, |id| self.is_text_field(id));
+                if is_text_field {
+                    self.state
+                        .signal_queue
+                        .push_back(RenderRootSignal::StartIme);
+                } else if was_text_field {
+                    self.state.signal_queue.push_back(RenderRootSignal::EndIme);
+                }
+                // The area is sent again by `update_ime_area`.
+                self.state.ime_area = None;
             }
         }
     }
 
+    fn is_text_field(&self, id: WidgetId) -> bool {
+        self.state
+            .text_fields
+            .iter()
+            .any(|registration| registration.widget_id == id)
+    }
+
+    /// Forget the text fields which were removed from the widget tree.
+    fn remove_stale_text_fields(&mut self) {
+        let mut text_fields = std::mem::take(&mut self.state.text_fields);
+        text_fields.retain(|registration| self.find_widget_by_id(registration.widget_id).is_some());
+        self.state.text_fields = text_fields;
+    }
+
+    /// Tell the platform where the focused text field's IME area is, if it moved.
+    fn update_ime_area(&mut self) {
+        let Some(focused_id) = self.state.focused_widget else {
+            return;
+        };
+        if !self.is_text_field(focused_id) {
+            return;
+        }
+        let Some(focused) = self.find_widget_by_id(focused_id) else {
+            return;
+        };
+        let state = focused.state();
+        let ime_area = state.ime_area.unwrap_or_else(|| state.size.to_rect())
+            + state.window_origin().to_vec2();
+
+        if self.state.ime_area != Some(ime_area) {
+            self.state.ime_area = Some(ime_area);
+            self.state
+                .signal_queue
+                .push_back(RenderRootSignal::ImeMoved(
+                    LogicalPosition::new(ime_area.x0, ime_area.y0),
+                    LogicalSize::new(ime_area.width(), ime_area.height()),
+                ));
+        }
+    }
+
     fn widget_for_focus_request(&self, focus: FocusChange) -> Option<WidgetId> {
         match focus {
             FocusChange::Resign => None,
@@ -465,43 +1170,173 @@ impl RenderRoot {
     }
 
     fn widget_from_focus_chain(&self, forward: bool) -> Option<WidgetId> {
-        self.state.focused_widget.and_then(|focus| {
-            self.focus_chain()
-                .iter()
-                // Find where the focused widget is in the focus chain
-                .position(|id| id == &focus)
-                .map(|idx| {
-                    // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
-                    let new_idx = if forward {
-                        (idx + 1) % len
-                    } else {
-                        (idx + len - 1) % len
-                    };
-                    self.focus_chain()[new_idx]
-                })
-                .or_else(|| {
-                    // If the currently focused widget isn't in the focus chain,
-                    // then we'll just return the first/last entry of the chain, if any.
-                    if forward {
-                        self.focus_chain().first().copied()
-                    } else {
-                        self.focus_chain().last().copied()
-                    }
-                })
-        })
+        let focus_chain = self.focus_chain();
+        // Find where the focused widget is in the focus chain
+        let focused_idx = self
+            .state
+            .focused_widget
+            .and_then(|focus| focus_chain.iter().position(|id| *id == focus));
+
+        match focused_idx {
+            Some(idx) => {
+                // Return the id that's next to it in the focus chain
+                let len = focus_chain.len();
+                let new_idx = if forward {
+                    (idx + 1) % len
+                } else {
+                    (idx + len - 1) % len
+                };
+                Some(focus_chain[new_idx])
+            }
+            // If no widget is focused, or the focused widget isn't in the focus chain,
+            // then we'll just return the first/last entry of the chain, if any.
+            None if forward => focus_chain.first().copied(),
+            None => focus_chain.last().copied(),
+        }
     }
 
     // TODO - Store in RenderRootState
+    /// The focus chain of the topmost popup with focusable widgets, or of the root widget.
+    ///
+    /// Tab and Shift+Tab keep the focus inside a popup. The focus never leaves a modal
+    /// layer for the layers below it.
     pub(crate) fn focus_chain(&self) -> &[WidgetId] {
-        &self.root.state().focus_chain
+        let modal_layer = self.layers.iter().rposition(|layer| layer.modal);
+        let focus_chain = self.layers[modal_layer.unwrap_or(0)..]
+            .iter()
+            .rev()
+            .map(|layer| &layer.widget.state().focus_chain[..])
+            .find(|focus_chain| !focus_chain.is_empty());
+        match (focus_chain, modal_layer) {
+            (Some(focus_chain), _) => focus_chain,
+            (None, Some(_)) => &[],
+            (None, None) => &self.root.state().focus_chain,
+        }
+    }
+}
+
+/// How many times widgets can be laid out again after a [`LifeCycle::PostLayout`] event
+/// before the frame is painted.
+const MAX_POST_LAYOUT_PASSES: usize = 8;
+
+/// Where to place a popup of the given size, next to `anchor`.
+///
+/// The popup is below the anchor, or above it if there's only room there, and is
+/// kept inside the window.
+fn popup_origin(anchor: Rect, popup_size: Size, window_size: Size) -> Point {
+    let fits_below = anchor.y1 + popup_size.height <= window_size.height;
+    let fits_above = anchor.y0 - popup_size.height >= 0.0;
+    let y = if fits_below || !fits_above {
+        anchor.y1.min(window_size.height - popup_size.height)
+    } else {
+        anchor.y0 - popup_size.height
+    };
+    let x = anchor.x0.min(window_size.width - popup_size.width);
+    Point::new(x.max(0.0), y.max(0.0))
+}
+
+/// Where to place a dialog of the given size: the center of the window.
+fn centered_origin(dialog_size: Size, window_size: Size) -> Point {
+    let x = (window_size.width - dialog_size.width) / 2.0;
+    let y = (window_size.height - dialog_size.height) / 2.0;
+    Point::new(x.max(0.0), y.max(0.0))
+}
+
+/// The focus change for a key press which no widget handled.
+///
+/// Tab moves the focus forward along the focus chain, and Shift+Tab moves it back.
+fn focus_change_for_key(
+    key: &Key,
+    state: ElementState,
+    mods: ModifiersState,
+) -> Option<FocusChange> {
+    let other_mods = mods.control_key() || mods.alt_key() || mods.super_key();
+    if state != ElementState::Pressed || other_mods {
+        return None;
+    }
+    match key {
+        Key::Named(NamedKey::Tab) if mods.shift_key() => Some(FocusChange::Previous),
+        Key::Named(NamedKey::Tab) => Some(FocusChange::Next),
+        _ => None,
     }
 }
 
 /*
 TODO:
 - Invalidation regions
-- Timer handling
 - prepare_paint
 - Focus-related stuff
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_moves_focus() {
+        let tab = Key::Named(NamedKey::Tab);
+        let pressed = ElementState::Pressed;
+
+        assert!(matches!(
+            focus_change_for_key(&tab, pressed, ModifiersState::empty()),
+            Some(FocusChange::Next)
+        ));
+        assert!(matches!(
+            focus_change_for_key(&tab, pressed, ModifiersState::SHIFT),
+            Some(FocusChange::Previous)
+        ));
+
+        // Ctrl+Tab and friends are left to the app.
+        assert!(focus_change_for_key(&tab, pressed, ModifiersState::CONTROL).is_none());
+        assert!(
+            focus_change_for_key(&tab, ElementState::Released, ModifiersState::empty()).is_none()
+        );
+        assert!(focus_change_for_key(
+            &Key::Named(NamedKey::Enter),
+            pressed,
+            ModifiersState::empty()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn popup_placement() {
+        let window_size = Size::new(400.0, 300.0);
+        let popup_size = Size::new(100.0, 80.0);
+
+        // Below the anchor when there's room.
+        let anchor = Rect::new(10.0, 10.0, 60.0, 30.0);
+        assert_eq!(
+            popup_origin(anchor, popup_size, window_size),
+            Point::new(10.0, 30.0)
+        );
+
+        // Above the anchor near the bottom of the window.
+        let anchor = Rect::new(10.0, 250.0, 60.0, 270.0);
+        assert_eq!(
+            popup_origin(anchor, popup_size, window_size),
+            Point::new(10.0, 170.0)
+        );
+
+        // Pushed back inside the window near its right edge.
+        let anchor = Rect::new(350.0, 10.0, 390.0, 30.0);
+        assert_eq!(
+            popup_origin(anchor, popup_size, window_size),
+            Point::new(300.0, 30.0)
+        );
+    }
+
+    #[test]
+    fn dialog_placement() {
+        let window_size = Size::new(400.0, 300.0);
+        assert_eq!(
+            centered_origin(Size::new(100.0, 80.0), window_size),
+            Point::new(150.0, 110.0)
+        );
+        // Dialogs bigger than the window stick to its top-left corner.
+        assert_eq!(
+            centered_origin(Size::new(500.0, 80.0), window_size),
+            Point::new(0.0, 110.0)
+        );
+    }
+}
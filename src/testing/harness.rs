@@ -4,26 +4,33 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Write as _;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
+use accesskit::{Node, NodeId, TreeUpdate};
 use image::io::Reader as ImageReader;
 use image::RgbaImage;
 use vello::util::RenderContext;
-use vello::{block_on_wgpu, RendererOptions};
+use vello::{block_on_wgpu, RendererOptions, Scene};
 use wgpu::{
     BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
     TextureDescriptor, TextureFormat, TextureUsages,
 };
-use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
 use winit::event::{Ime, MouseButton};
+use winit::keyboard::{Key, ModifiersState};
 
 use super::screenshots::get_image_diff;
 use super::snapshot_utils::get_cargo_workspace;
 use crate::action::Action;
-use crate::event::{PointerEvent, PointerState, TextEvent, WindowEvent};
+use crate::event::{AccessEvent, PointerEvent, PointerState, TextEvent, WindowEvent};
+use crate::ext_event::ExtEventSink;
 use crate::render_root::{RenderRoot, RenderRootSignal, WindowSizePolicy};
-use crate::widget::{WidgetMut, WidgetRef};
-use crate::{Color, Handled, Point, Size, Vec2, Widget, WidgetId};
+use crate::widget::{Dialog, WidgetMut, WidgetRef};
+use crate::{Affine, Color, Handled, Point, Rect, Size, Vec2, Widget, WidgetId};
 
 // TODO - Get shorter names
 // TODO - Make them associated consts
@@ -65,7 +72,16 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 /// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods. **(TODO -
 /// Doesn't move animations forward.)**
 ///
-/// **(TODO - ExtEvents aren't handled.)**
+/// Background tasks started with [`run_in_background`](crate::EventCtx::run_in_background)
+/// or [`compute_in_background`](crate::EventCtx::compute_in_background) are run
+/// synchronously on the test thread, in the order they were started, at the end
+/// of the event that started them. Their results are delivered before the harness
+/// method returns.
+///
+/// Events submitted through an [`ExtEventSink`] are delivered at the end of every
+/// harness event, or when calling [`process_ext_events`](Self::process_ext_events).
+/// Payloads are not passed to an [`AppDriver`](crate::app_driver::AppDriver), but
+/// can be retrieved with [`pop_ext_payload`](Self::pop_ext_payload).
 ///
 /// **(TODO - Painting invalidation might not be accurate.)**
 ///
@@ -120,8 +136,20 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 pub struct TestHarness {
     render_root: RenderRoot,
     mouse_state: PointerState,
-    window_size: PhysicalSize<u32>,
+    window_size: Size,
+    scale_factor: f64,
     background_color: Color,
+    access_tree: AccessTree,
+    /// The IME area requested by the focused text field, if IME is enabled.
+    ime_area: Option<Rect>,
+}
+
+/// The accessibility tree, as seen by a platform adapter.
+#[derive(Default)]
+struct AccessTree {
+    root: Option<NodeId>,
+    focus: Option<NodeId>,
+    nodes: HashMap<NodeId, Node>,
 }
 
 /// Assert a snapshot of a rendered frame of your app.
@@ -172,19 +200,45 @@ impl TestHarness {
         background_color: Color,
     ) -> Self {
         let mouse_state = PointerState::empty();
-        let window_size = PhysicalSize::new(window_size.width as _, window_size.height as _);
+
+        let mut render_root = RenderRoot::new(root_widget, WindowSizePolicy::User);
+        // Timers are driven by `move_timers_forward`, never by the system clock.
+        render_root.state.timers.enable_mock_clock();
 
         let mut harness = TestHarness {
-            render_root: RenderRoot::new(root_widget, WindowSizePolicy::User),
+            render_root,
             mouse_state,
             window_size,
+            scale_factor: 1.0,
             background_color,
+            access_tree: AccessTree::default(),
+            ime_area: None,
         };
-        harness.process_window_event(WindowEvent::Resize(window_size));
+        harness.process_window_event(WindowEvent::Resize(harness.physical_window_size()));
 
         harness
     }
 
+    /// Set the number of physical pixels per logical pixel.
+    ///
+    /// The window keeps the same logical size, so widgets are laid out the same way,
+    /// but frames returned by [`render`](Self::render) are `scale_factor` times larger.
+    /// This is useful to check how widgets render on HiDPI screens.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        // Both events are sent before layout, so widgets never see a mismatched size.
+        self.render_root
+            .handle_window_event(WindowEvent::Rescale(scale_factor));
+        self.render_root
+            .handle_window_event(WindowEvent::Resize(self.physical_window_size()));
+        self.process_state_after_event();
+    }
+
+    fn physical_window_size(&self) -> PhysicalSize<u32> {
+        LogicalSize::new(self.window_size.width, self.window_size.height)
+            .to_physical(self.scale_factor)
+    }
+
     // FIXME - The docs for these three functions are copy-pasted. Rewrite them.
 
     /// Send an event to the widget.
@@ -220,10 +274,70 @@ impl TestHarness {
         handled
     }
 
+    /// Send a request from an assistive technology to the widget.
+    ///
+    /// If this event triggers lifecycle events, they will also be dispatched.
+    pub fn process_access_event(&mut self, event: AccessEvent) -> Handled {
+        let handled = self.render_root.handle_access_event(event);
+        self.process_state_after_event();
+        handled
+    }
+
+    /// Deliver events submitted through an [`ExtEventSink`].
+    ///
+    /// If this triggers lifecycle events, they will also be dispatched.
+    pub fn process_ext_events(&mut self) {
+        self.process_state_after_event();
+    }
+
     fn process_state_after_event(&mut self) {
+        self.render_root.handle_ext_events();
+        self.run_background_tasks();
         if self.root_widget().state().needs_layout {
             self.render_root.root_layout();
         }
+        let tree_update = self.render_root.root_accessibility();
+        self.access_tree.update(tree_update);
+        self.process_ime_signals();
+    }
+
+    /// Apply IME requests the way a platform would.
+    fn process_ime_signals(&mut self) {
+        while let Some(signal) = self.render_root.pop_signal_matching(|signal| {
+            matches!(
+                signal,
+                RenderRootSignal::StartIme
+                    | RenderRootSignal::EndIme
+                    | RenderRootSignal::ImeMoved(..)
+            )
+        }) {
+            match signal {
+                RenderRootSignal::StartIme => self.ime_area = Some(Rect::ZERO),
+                RenderRootSignal::EndIme => self.ime_area = None,
+                RenderRootSignal::ImeMoved(position, size) => {
+                    if self.ime_area.is_some() {
+                        self.ime_area = Some(Rect::from_origin_size(
+                            (position.x, position.y),
+                            (size.width, size.height),
+                        ));
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Run pending background tasks on the current thread, and deliver their results.
+    ///
+    /// Tasks spawned while delivering results are run too.
+    fn run_background_tasks(&mut self) {
+        while let Some(RenderRootSignal::SpawnWorker(worker_fn)) = self
+            .render_root
+            .pop_signal_matching(|signal| matches!(signal, RenderRootSignal::SpawnWorker(_)))
+        {
+            (worker_fn.0)(self.render_root.worker_ctx());
+            self.render_root.handle_ext_events();
+        }
     }
 
     // TODO - We add way too many dependencies in this code
@@ -250,10 +364,12 @@ impl TestHarness {
         )
         .expect("Got non-Send/Sync error from creating renderer");
 
-        let scene = self.render_root.redraw();
+        let (unscaled_scene, tree_update) = self.render_root.redraw();
+        self.access_tree.update(tree_update);
+        let mut scene = Scene::new();
+        scene.append(&unscaled_scene, Some(Affine::scale(self.scale_factor)));
 
-        // TODO - fix window_size
-        let (width, height) = (self.window_size.width, self.window_size.height);
+        let PhysicalSize { width, height } = self.physical_window_size();
         let render_params = vello::RenderParams {
             // TODO - Parameterize
             base_color: self.background_color,
@@ -328,9 +444,8 @@ impl TestHarness {
 
     /// Move an internal mouse state, and send a MouseMove event to the window.
     pub fn mouse_move(&mut self, pos: impl Into<Point>) {
-        // FIXME - Account for scaling
         let pos = pos.into();
-        let pos = PhysicalPosition::new(pos.x, pos.y);
+        let pos = LogicalPosition::new(pos.x, pos.y);
         self.mouse_state.position = dbg!(pos);
 
         self.process_pointer_event(PointerEvent::PointerMove(self.mouse_state.clone()));
@@ -350,7 +465,7 @@ impl TestHarness {
 
     /// Send a Wheel event to the window
     pub fn mouse_wheel(&mut self, wheel_delta: Vec2) {
-        let pixel_delta = PhysicalPosition::new(wheel_delta.x, wheel_delta.y);
+        let pixel_delta = LogicalPosition::new(wheel_delta.x, wheel_delta.y);
         self.process_pointer_event(PointerEvent::MouseWheel(
             pixel_delta,
             self.mouse_state.clone(),
@@ -390,26 +505,37 @@ impl TestHarness {
         self.process_state_after_event();
     }
 
-    #[cfg(FALSE)]
+    /// Simulate a key press which no widget uses, eg Tab to move the focus.
+    ///
+    /// The key isn't sent to widgets, since winit key events can't be created in tests.
+    pub fn keyboard_press_unused_key(&mut self, key: Key, mods: ModifiersState) {
+        self.render_root.handle_unused_key(&key, mods);
+        self.process_state_after_event();
+    }
+
     /// Simulate the passage of time.
     ///
     /// If you create any timer in a widget, this method is the only way to trigger
     /// them in unit tests. The testing model assumes that everything else executes
     /// instantly, and timers are never triggered "spontaneously".
     ///
+    /// Timers fire in order of their deadlines. Timers requested while handling a
+    /// timer event will also fire if their deadline falls within `duration`.
+    ///
     /// **(TODO - Doesn't move animations forward.)**
     pub fn move_timers_forward(&mut self, duration: Duration) {
         // TODO - handle animations
-        let tokens = self
-            .mock_app
-            .window
-            .mock_timer_queue
-            .as_mut()
-            .unwrap()
-            .move_forward(duration);
-        for token in tokens {
-            self.process_event(Event::Timer(token));
+        let target = self.render_root.state.timers.now() + duration;
+
+        while let Some(deadline) = self.render_root.next_timer_deadline() {
+            if deadline > target {
+                break;
+            }
+            self.render_root.state.timers.set_mock_now(deadline);
+            self.render_root.handle_timers();
+            self.process_state_after_event();
         }
+        self.render_root.state.timers.set_mock_now(target);
     }
 
     // --- Getters ---
@@ -424,24 +550,46 @@ impl TestHarness {
     /// ## Panics
     ///
     /// Panics if no Widget with this id can be found.
+    ///
+    /// Widgets in popups are found too.
     pub fn get_widget(&self, id: WidgetId) -> WidgetRef<'_, dyn Widget> {
-        self.root_widget()
+        self.render_root
             .find_widget_by_id(id)
             .expect("could not find widget")
     }
 
     /// Try to return the widget with the given id.
     pub fn try_get_widget(&self, id: WidgetId) -> Option<WidgetRef<'_, dyn Widget>> {
-        self.root_widget().find_widget_by_id(id)
+        self.render_root.find_widget_by_id(id)
     }
 
     // TODO - link to focus documentation.
     /// Return the widget that receives keyboard events.
     pub fn focused_widget(&self) -> Option<WidgetRef<'_, dyn Widget>> {
-        self.root_widget()
+        self.render_root
             .find_widget_by_id(self.render_root.state.focused_widget?)
     }
 
+    /// Return the open popups, from bottom to top.
+    ///
+    /// Each popup is the widget passed to [`EventCtx::open_popup`](crate::EventCtx::open_popup).
+    pub fn popups(&self) -> Vec<WidgetRef<'_, dyn Widget>> {
+        self.render_root
+            .layers
+            .iter()
+            .map(|layer| layer.widget.as_dyn())
+            .collect()
+    }
+
+    /// Open a modal dialog above the root widget, the way
+    /// [`DriverCtx::open_dialog`](crate::app_driver::DriverCtx::open_dialog) does, and
+    /// return its id.
+    pub fn open_dialog(&mut self, dialog: Dialog) -> WidgetId {
+        let dialog_id = self.render_root.open_dialog(dialog);
+        self.process_state_after_event();
+        dialog_id
+    }
+
     /// Call the provided visitor on every widget in the widget tree.
     pub fn inspect_widgets(&mut self, f: impl Fn(WidgetRef<'_, dyn Widget>) + 'static) {
         fn inspect(
@@ -469,6 +617,35 @@ impl TestHarness {
         res
     }
 
+    /// Return the area where the platform would show the IME candidate window,
+    /// in window coordinates.
+    ///
+    /// Returns `None` if IME is disabled, ie if no text field is focused.
+    pub fn ime_area(&self) -> Option<Rect> {
+        self.ime_area
+    }
+
+    /// Return the id of the widget which has accessibility focus.
+    ///
+    /// This is the root widget if no widget has keyboard focus.
+    pub fn access_focus(&self) -> Option<WidgetId> {
+        WidgetId::from_node_id(self.access_tree.focus?)
+    }
+
+    /// Render the accessibility tree as indented text, one node per line.
+    ///
+    /// Each line has the node's role, followed by its name, value and state if it has any.
+    /// Widget ids are left out, so the result can be compared against a snapshot.
+    /// A node which is its own ancestor is written as `<cycle>`.
+    pub fn access_tree_snapshot(&self) -> String {
+        let mut text = String::new();
+        if let Some(root) = self.access_tree.root {
+            self.access_tree
+                .write_node(&mut text, root, &mut Vec::new());
+        }
+        text
+    }
+
     /// Pop next action from the queue
     ///
     /// Note: Actions are still a WIP feature.
@@ -483,6 +660,23 @@ impl TestHarness {
         }
     }
 
+    /// Pop the oldest value submitted through [`ExtEventSink::submit_payload`].
+    pub fn pop_ext_payload(&mut self) -> Option<Box<dyn Any + Send>> {
+        let signal = self
+            .render_root
+            .pop_signal_matching(|signal| matches!(signal, RenderRootSignal::ExtPayload(_)));
+        match signal {
+            Some(RenderRootSignal::ExtPayload(payload)) => Some(payload),
+            Some(_) => unreachable!(),
+            _ => None,
+        }
+    }
+
+    /// Return a handle other threads can use to send events to the tested widgets.
+    pub fn ext_event_sink(&self) -> ExtEventSink {
+        self.render_root.ext_event_sink()
+    }
+
     // --- Screenshots ---
 
     /// Method used by [`assert_render_snapshot`]. Use the macro instead.
@@ -508,7 +702,8 @@ impl TestHarness {
             // different platforms.
 
             // We still redraw to get some coverage in the paint code.
-            let _ = self.render_root.redraw();
+            let (_, tree_update) = self.render_root.redraw();
+            self.access_tree.update(tree_update);
 
             return;
         }
@@ -555,3 +750,55 @@ impl TestHarness {
         self.render_root.state.debug_logger.write_to_file(path);
     }
 }
+
+impl AccessTree {
+    /// Apply an update, the way a platform adapter would.
+    fn update(&mut self, tree_update: TreeUpdate) {
+        if let Some(tree) = tree_update.tree {
+            self.root = Some(tree.root);
+            self.nodes.clear();
+        }
+        self.focus = Some(tree_update.focus);
+        self.nodes.extend(tree_update.nodes);
+    }
+
+    /// `ancestors` is the path from the root to the parent of `id`.
+    fn write_node(&self, text: &mut String, id: NodeId, ancestors: &mut Vec<NodeId>) {
+        let indent = "  ".repeat(ancestors.len());
+        if ancestors.contains(&id) {
+            writeln!(text, "{indent}<cycle>").unwrap();
+            return;
+        }
+        let Some(node) = self.nodes.get(&id) else {
+            writeln!(text, "{indent}<missing node>").unwrap();
+            return;
+        };
+
+        write!(text, "{indent}{:?}", node.role()).unwrap();
+        if let Some(name) = node.name() {
+            write!(text, " {name:?}").unwrap();
+        }
+        if let Some(value) = node.value() {
+            write!(text, " value={value:?}").unwrap();
+        }
+        if let Some(value) = node.numeric_value() {
+            write!(text, " value={value}").unwrap();
+        }
+        if let Some(checked) = node.checked() {
+            write!(text, " checked={checked:?}").unwrap();
+        }
+        if node.is_selected() == Some(true) {
+            write!(text, " selected").unwrap();
+        }
+        if node.is_disabled() {
+            write!(text, " disabled").unwrap();
+        }
+        writeln!(text).unwrap();
+
+        ancestors.push(id);
+        for &child in node.children() {
+            self.write_node(text, child, ancestors);
+        }
+        ancestors.pop();
+    }
+}
@@ -16,19 +16,22 @@ use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use accesskit::Role;
 use smallvec::SmallVec;
 use vello::Scene;
 
-use crate::event::{PointerEvent, TextEvent};
+use crate::event::{AccessEvent, PointerEvent, TextEvent};
 use crate::widget::{SizedBox, WidgetRef};
 use crate::*;
 
 pub type PointerEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &PointerEvent);
 pub type TextEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &TextEvent);
+pub type AccessEventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &AccessEvent);
 pub type StatusChangeFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &StatusChange);
 pub type LifeCycleFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &LifeCycle);
 pub type LayoutFn<S> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints) -> Size;
 pub type PaintFn<S> = dyn FnMut(&mut S, &mut PaintCtx, &mut Scene);
+pub type AccessFn<S> = dyn FnMut(&mut S, &mut AccessCtx);
 pub type ChildrenFn<S> = dyn Fn(&S) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]>;
 
 #[cfg(FALSE)]
@@ -41,10 +44,12 @@ pub struct ModularWidget<S> {
     state: S,
     on_pointer_event: Option<Box<PointerEventFn<S>>>,
     on_text_event: Option<Box<TextEventFn<S>>>,
+    on_access_event: Option<Box<AccessEventFn<S>>>,
     on_status_change: Option<Box<StatusChangeFn<S>>>,
     lifecycle: Option<Box<LifeCycleFn<S>>>,
     layout: Option<Box<LayoutFn<S>>>,
     paint: Option<Box<PaintFn<S>>>,
+    accessibility: Option<Box<AccessFn<S>>>,
     children: Option<Box<ChildrenFn<S>>>,
 }
 
@@ -85,10 +90,12 @@ pub struct Recording(Rc<RefCell<VecDeque<Record>>>);
 pub enum Record {
     PE(PointerEvent),
     TE(TextEvent),
+    AE(AccessEvent),
     SC(StatusChange),
     L(LifeCycle),
     Layout(Size),
     Paint,
+    Access,
 }
 
 /// like WidgetExt but just for this one thing
@@ -113,10 +120,12 @@ impl<S> ModularWidget<S> {
             state,
             on_pointer_event: None,
             on_text_event: None,
+            on_access_event: None,
             on_status_change: None,
             lifecycle: None,
             layout: None,
             paint: None,
+            accessibility: None,
             children: None,
         }
     }
@@ -137,6 +146,14 @@ impl<S> ModularWidget<S> {
         self
     }
 
+    pub fn access_event_fn(
+        mut self,
+        f: impl FnMut(&mut S, &mut EventCtx, &AccessEvent) + 'static,
+    ) -> Self {
+        self.on_access_event = Some(Box::new(f));
+        self
+    }
+
     pub fn status_change_fn(
         mut self,
         f: impl FnMut(&mut S, &mut LifeCycleCtx, &StatusChange) + 'static,
@@ -166,6 +183,11 @@ impl<S> ModularWidget<S> {
         self
     }
 
+    pub fn accessibility_fn(mut self, f: impl FnMut(&mut S, &mut AccessCtx) + 'static) -> Self {
+        self.accessibility = Some(Box::new(f));
+        self
+    }
+
     pub fn children_fn(
         mut self,
         children: impl Fn(&S) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> + 'static,
@@ -188,6 +210,12 @@ impl<S: 'static> Widget for ModularWidget<S> {
         }
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if let Some(f) = self.on_access_event.as_mut() {
+            f(&mut self.state, ctx, event)
+        }
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         if let Some(f) = self.on_status_change.as_mut() {
             f(&mut self.state, ctx, event)
@@ -218,6 +246,16 @@ impl<S: 'static> Widget for ModularWidget<S> {
         }
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Unknown
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if let Some(f) = self.accessibility.as_mut() {
+            f(&mut self.state, ctx)
+        }
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         if let Some(f) = self.children.as_ref() {
             f(&self.state)
@@ -257,6 +295,10 @@ impl Widget for ReplaceChild {
         todo!()
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event)
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
         ctx.request_layout();
     }
@@ -273,6 +315,14 @@ impl Widget for ReplaceChild {
         self.child.paint(ctx, scene)
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx)
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         self.child.widget().children()
     }
@@ -320,6 +370,11 @@ impl<W: Widget> Widget for Recorder<W> {
         self.child.on_text_event(ctx, event)
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.recording.push(Record::AE(event.clone()));
+        self.child.on_access_event(ctx, event)
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         self.recording.push(Record::SC(event.clone()));
         self.child.on_status_change(ctx, event)
@@ -341,6 +396,15 @@ impl<W: Widget> Widget for Recorder<W> {
         self.recording.push(Record::Paint)
     }
 
+    fn accessibility_role(&self) -> Role {
+        self.child.accessibility_role()
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+        self.recording.push(Record::Access)
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         self.child.children()
     }
@@ -1,13 +1,19 @@
 //! Helper functions for working with text in Masonry.
 
+use std::ops::Range;
+
+use parley::layout::Cursor;
 use parley::Layout;
 use vello::{
-    kurbo::Affine,
+    kurbo::{Affine, Point, Rect},
     peniko::{Brush, Fill},
     Scene,
 };
 
-use crate::WidgetId;
+use winit::event::{Ime, KeyEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::{EventCtx, WidgetId};
 
 /// A reference counted string slice.
 ///
@@ -18,7 +24,6 @@ pub type ArcStr = std::sync::Arc<str>;
 /// A type we use to keep track of which widgets are responsible for which
 /// ime sessions.
 #[derive(Clone, Debug)]
-#[allow(unused)]
 pub(crate) struct TextFieldRegistration {
     pub widget_id: WidgetId,
 }
@@ -47,6 +52,414 @@ pub enum ImeChangeSignal {
     Reset,
 }
 
+/// A range of selected text, or a caret if the range is empty.
+///
+/// Offsets are in bytes, and always fall on `char` boundaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Selection {
+    /// The end of the selection which stays in place when the selection is extended.
+    pub anchor: usize,
+    /// The end of the selection which moves when the selection is extended.
+    ///
+    /// This is where the caret is drawn.
+    pub active: usize,
+}
+
+impl Selection {
+    /// Create a selection from `anchor` to `active`.
+    pub fn new(anchor: usize, active: usize) -> Self {
+        Self { anchor, active }
+    }
+
+    /// Create an empty selection at `offset`.
+    pub fn caret(offset: usize) -> Self {
+        Self::new(offset, offset)
+    }
+
+    /// Return `true` if the selection is empty.
+    pub fn is_caret(&self) -> bool {
+        self.anchor == self.active
+    }
+
+    /// The smaller of the two offsets.
+    pub fn min(&self) -> usize {
+        self.anchor.min(self.active)
+    }
+
+    /// The larger of the two offsets.
+    pub fn max(&self) -> usize {
+        self.anchor.max(self.active)
+    }
+
+    /// The selected range, from the smaller offset to the larger one.
+    pub fn range(&self) -> Range<usize> {
+        self.min()..self.max()
+    }
+
+    /// Move the active end to `offset`; if `extend` is `false`, move the anchor too.
+    pub fn move_to(&self, offset: usize, extend: bool) -> Self {
+        if extend {
+            Self::new(self.anchor, offset)
+        } else {
+            Self::caret(offset)
+        }
+    }
+
+    /// Clamp both offsets to the length of `text`, and to `char` boundaries.
+    pub fn constrained(&self, text: &str) -> Self {
+        Self::new(
+            floor_char_boundary(text, self.anchor),
+            floor_char_boundary(text, self.active),
+        )
+    }
+}
+
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// The offset of the `char` before `offset`, or 0.
+pub(crate) fn prev_char_offset(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(idx, _)| idx)
+}
+
+/// The offset of the `char` after `offset`, or the length of `text`.
+pub(crate) fn next_char_offset(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map_or(text.len(), |c| offset + c.len_utf8())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The start of the word before `offset`.
+///
+/// Whitespace and punctuation between `offset` and that word are skipped.
+pub(crate) fn prev_word_offset(text: &str, offset: usize) -> usize {
+    let mut chars = text[..offset].char_indices().rev().peekable();
+    while chars.next_if(|(_, c)| !is_word_char(*c)).is_some() {}
+    let mut start = chars.peek().map_or(0, |(idx, _)| *idx);
+    while let Some((idx, _)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+        start = idx;
+    }
+    start
+}
+
+/// The end of the word after `offset`.
+///
+/// Whitespace and punctuation between `offset` and that word are skipped.
+pub(crate) fn next_word_offset(text: &str, offset: usize) -> usize {
+    let mut chars = text[offset..].char_indices().peekable();
+    while chars.next_if(|(_, c)| !is_word_char(*c)).is_some() {}
+    while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+    chars.peek().map_or(text.len(), |(idx, _)| offset + idx)
+}
+
+/// The range of the word touching `offset`.
+///
+/// If no word touches `offset`, the range is empty.
+pub(crate) fn word_range(text: &str, offset: usize) -> Range<usize> {
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()
+        .map_or(offset, |(idx, _)| idx);
+    let end = text[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_word_char(*c))
+        .map_or(text.len(), |(idx, _)| offset + idx);
+    start..end
+}
+
+/// A movement of the caret, decoded from a key press by [`edit_command`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Movement {
+    PrevChar,
+    NextChar,
+    PrevWord,
+    NextWord,
+    LineUp,
+    LineDown,
+    PageUp,
+    PageDown,
+    LineStart,
+    LineEnd,
+    TextStart,
+    TextEnd,
+}
+
+/// An editing command, decoded from a key press by [`edit_command`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditCommand<'a> {
+    /// Move the caret, extending the selection if the flag is set.
+    Move(Movement, bool),
+    /// Delete the selected text, or if the selection is empty, the text the movement
+    /// goes over.
+    Delete(Movement),
+    SelectAll,
+    Undo,
+    Redo,
+    /// The Enter key.
+    Enter,
+    /// Insert typed text.
+    Insert(&'a str),
+}
+
+/// Decode a key press into the command text fields run for it.
+///
+/// Returns `None` for keys text fields leave to other widgets, eg Tab and Escape.
+pub(crate) fn edit_command(key_event: &KeyEvent, mods: ModifiersState) -> Option<EditCommand<'_>> {
+    let shift = mods.shift_key();
+    let (word_mod, is_shortcut) = if cfg!(target_os = "macos") {
+        (mods.alt_key(), mods.super_key())
+    } else {
+        // AltGr is reported as Ctrl+Alt on some platforms.
+        (mods.control_key(), mods.control_key() && !mods.alt_key())
+    };
+    let (prev, next) = if word_mod {
+        (Movement::PrevWord, Movement::NextWord)
+    } else {
+        (Movement::PrevChar, Movement::NextChar)
+    };
+
+    let command = match &key_event.logical_key {
+        Key::Named(NamedKey::ArrowLeft) => EditCommand::Move(prev, shift),
+        Key::Named(NamedKey::ArrowRight) => EditCommand::Move(next, shift),
+        Key::Named(NamedKey::ArrowUp) => EditCommand::Move(Movement::LineUp, shift),
+        Key::Named(NamedKey::ArrowDown) => EditCommand::Move(Movement::LineDown, shift),
+        Key::Named(NamedKey::PageUp) => EditCommand::Move(Movement::PageUp, shift),
+        Key::Named(NamedKey::PageDown) => EditCommand::Move(Movement::PageDown, shift),
+        Key::Named(NamedKey::Home) if is_shortcut => EditCommand::Move(Movement::TextStart, shift),
+        Key::Named(NamedKey::Home) => EditCommand::Move(Movement::LineStart, shift),
+        Key::Named(NamedKey::End) if is_shortcut => EditCommand::Move(Movement::TextEnd, shift),
+        Key::Named(NamedKey::End) => EditCommand::Move(Movement::LineEnd, shift),
+        Key::Named(NamedKey::Backspace) => EditCommand::Delete(prev),
+        Key::Named(NamedKey::Delete) => EditCommand::Delete(next),
+        Key::Named(NamedKey::Enter) => EditCommand::Enter,
+        Key::Character(c) if is_shortcut && c.eq_ignore_ascii_case("a") => EditCommand::SelectAll,
+        Key::Character(c) if is_shortcut && c.eq_ignore_ascii_case("z") => {
+            if shift {
+                EditCommand::Redo
+            } else {
+                EditCommand::Undo
+            }
+        }
+        Key::Character(c) if is_shortcut && c.eq_ignore_ascii_case("y") => EditCommand::Redo,
+        _ => match key_event.text.as_deref() {
+            // Keys like Tab and Escape produce control characters; we leave them
+            // to other widgets.
+            Some(text) if !is_shortcut && text.chars().any(|c| !c.is_control()) => {
+                EditCommand::Insert(text)
+            }
+            _ => return None,
+        },
+    };
+    Some(command)
+}
+
+/// Where `movement` takes the caret from `offset`.
+///
+/// Returns `None` for movements between lines, which depend on the text layout.
+pub(crate) fn movement_offset(text: &str, offset: usize, movement: Movement) -> Option<usize> {
+    match movement {
+        Movement::PrevChar => Some(prev_char_offset(text, offset)),
+        Movement::NextChar => Some(next_char_offset(text, offset)),
+        Movement::PrevWord => Some(prev_word_offset(text, offset)),
+        Movement::NextWord => Some(next_word_offset(text, offset)),
+        Movement::TextStart => Some(0),
+        Movement::TextEnd => Some(text.len()),
+        _ => None,
+    }
+}
+
+/// The selection after moving its active end by `movement`.
+///
+/// Moving by a character without extending the selection collapses a non-empty
+/// selection to its start or end instead. Returns `None` for movements between lines.
+pub(crate) fn move_selection(
+    text: &str,
+    selection: Selection,
+    movement: Movement,
+    extend: bool,
+) -> Option<Selection> {
+    let offset = match movement {
+        Movement::PrevChar if !selection.is_caret() && !extend => selection.min(),
+        Movement::NextChar if !selection.is_caret() && !extend => selection.max(),
+        _ => movement_offset(text, selection.active, movement)?,
+    };
+    Some(selection.move_to(offset, extend))
+}
+
+/// Text being composed with the IME, and the IME's cursor inside it.
+///
+/// It's displayed at the caret, but isn't part of the text until it's committed.
+pub(crate) type Preedit = (String, Option<(usize, usize)>);
+
+/// The IME handling shared by text fields.
+///
+/// Text fields provide the required methods; the provided ones display the preedit
+/// text and apply IME events.
+pub(crate) trait ImeTextField {
+    /// The text, without the preedit text.
+    fn committed_text(&self) -> &str;
+
+    /// The offset of the caret in the text, where the preedit text is displayed.
+    fn caret(&self) -> usize;
+
+    fn preedit(&self) -> Option<&Preedit>;
+
+    fn preedit_mut(&mut self) -> &mut Option<Preedit>;
+
+    /// Delete the selected text.
+    fn delete_selection(&mut self, ctx: &mut EventCtx);
+
+    /// Replace the selected text with text committed by the IME.
+    fn insert_committed(&mut self, ctx: &mut EventCtx, text: &str);
+
+    /// Called when the displayed text changes but the text doesn't.
+    fn preedit_changed(&mut self, ctx: &mut EventCtx);
+
+    /// The text as displayed, with the IME's preedit text inserted at the caret.
+    fn display_text(&self) -> String {
+        let mut text = self.committed_text().to_string();
+        if let Some((preedit, _)) = self.preedit() {
+            text.insert_str(self.caret(), preedit);
+        }
+        text
+    }
+
+    /// Convert an offset in the text to an offset in the displayed text.
+    fn display_offset(&self, offset: usize) -> usize {
+        match self.preedit() {
+            Some((preedit, _)) if offset > self.caret() => offset + preedit.len(),
+            _ => offset,
+        }
+    }
+
+    /// Convert an offset in the displayed text to an offset in the text. Offsets in the
+    /// preedit text are at the caret.
+    fn committed_offset(&self, display_offset: usize) -> usize {
+        match self.preedit() {
+            Some((preedit, _)) if display_offset > self.caret() => display_offset
+                .saturating_sub(preedit.len())
+                .max(self.caret()),
+            _ => display_offset,
+        }
+    }
+
+    /// The offset of the caret in the displayed text.
+    fn display_caret(&self) -> usize {
+        match self.preedit() {
+            Some((_, Some((_, end)))) => self.caret() + end,
+            _ => self.caret(),
+        }
+    }
+
+    fn set_preedit(&mut self, ctx: &mut EventCtx, preedit: Option<Preedit>) {
+        // Composition replaces the selected text.
+        if preedit.is_some() && self.preedit().is_none() {
+            self.delete_selection(ctx);
+        }
+        *self.preedit_mut() = preedit;
+        self.preedit_changed(ctx);
+    }
+
+    /// Apply an IME event. Returns `true` if the event should be marked as handled.
+    fn handle_ime(&mut self, ctx: &mut EventCtx, ime: &Ime) -> bool {
+        match ime {
+            Ime::Preedit(preedit, cursor) => {
+                let preedit = (!preedit.is_empty()).then(|| (preedit.clone(), *cursor));
+                self.set_preedit(ctx, preedit);
+                true
+            }
+            Ime::Commit(text) => {
+                self.set_preedit(ctx, None);
+                self.insert_committed(ctx, text);
+                true
+            }
+            Ime::Disabled => {
+                if self.preedit().is_some() {
+                    self.set_preedit(ctx, None);
+                }
+                false
+            }
+            Ime::Enabled => false,
+        }
+    }
+}
+
+/// The horizontal position of the caret at `offset`, relative to the start of its line.
+pub(crate) fn caret_x(layout: &Layout<Brush>, offset: usize) -> f64 {
+    Cursor::from_position(layout, offset, true).offset as f64
+}
+
+/// The text offset closest to `point`, in the layout's coordinate space.
+pub(crate) fn offset_for_point(layout: &Layout<Brush>, point: Point) -> usize {
+    Cursor::from_point(layout, point.x as f32, point.y as f32).insert_point
+}
+
+/// The index of the line holding the caret at `offset`.
+pub(crate) fn line_index(layout: &Layout<Brush>, offset: usize) -> usize {
+    Cursor::from_position(layout, offset, true).path.line_index
+}
+
+/// The bounds of the line at `line_index`, in the layout's coordinate space.
+pub(crate) fn line_rect(layout: &Layout<Brush>, line_index: usize) -> Rect {
+    let Some(line) = layout.get(line_index) else {
+        return Rect::ZERO;
+    };
+    let metrics = line.metrics();
+    Rect::new(
+        metrics.offset as f64,
+        metrics.min_coord as f64,
+        (metrics.offset + metrics.advance) as f64,
+        metrics.max_coord as f64,
+    )
+}
+
+/// The caret at `offset`, spanning the height of its line.
+pub(crate) fn caret_rect(layout: &Layout<Brush>, offset: usize, width: f64) -> Rect {
+    let cursor = Cursor::from_position(layout, offset, true);
+    let line = line_rect(layout, cursor.path.line_index);
+    let x = cursor.offset as f64;
+    Rect::new(x, line.y0, x + width, line.y1)
+}
+
+/// The rects covering the text in `range`, one per line it spans.
+pub(crate) fn selection_rects(layout: &Layout<Brush>, range: Range<usize>) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    for (index, line) in layout.lines().enumerate() {
+        let line_range = line.text_range();
+        if line_range.start >= range.end || line_range.end <= range.start {
+            continue;
+        }
+        let line = line_rect(layout, index);
+        let x0 = if range.start > line_range.start {
+            caret_x(layout, range.start)
+        } else {
+            line.x0
+        };
+        let x1 = if range.end < line_range.end {
+            caret_x(layout, range.end)
+        } else {
+            line.x1
+        };
+        rects.push(Rect::new(x0, line.y0, x1, line.y1));
+    }
+    rects
+}
+
 /// A function that renders laid out glyphs to a [Scene].
 pub fn render_text(scene: &mut Scene, transform: Affine, layout: &Layout<Brush>) {
     for line in layout.lines() {
@@ -89,3 +502,76 @@ pub fn render_text(scene: &mut Scene, transform: Affine, layout: &Layout<Brush>)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_offsets() {
+        let text = "aé😀";
+        assert_eq!(next_char_offset(text, 0), 1);
+        assert_eq!(next_char_offset(text, 1), 3);
+        assert_eq!(next_char_offset(text, 3), 7);
+        assert_eq!(next_char_offset(text, 7), 7);
+
+        assert_eq!(prev_char_offset(text, 7), 3);
+        assert_eq!(prev_char_offset(text, 3), 1);
+        assert_eq!(prev_char_offset(text, 0), 0);
+    }
+
+    #[test]
+    fn word_offsets() {
+        let text = "hello, big_world ";
+        assert_eq!(next_word_offset(text, 0), 5);
+        assert_eq!(next_word_offset(text, 5), 16);
+        assert_eq!(next_word_offset(text, 16), 17);
+
+        assert_eq!(prev_word_offset(text, 17), 7);
+        assert_eq!(prev_word_offset(text, 7), 0);
+        assert_eq!(prev_word_offset(text, 3), 0);
+        assert_eq!(prev_word_offset(text, 0), 0);
+
+        assert_eq!(word_range(text, 2), 0..5);
+        assert_eq!(word_range(text, 9), 7..16);
+        assert_eq!(word_range(text, 6), 6..6);
+    }
+
+    #[test]
+    fn selection_movements() {
+        let text = "ab cd";
+        let caret = Selection::caret(1);
+        assert_eq!(
+            move_selection(text, caret, Movement::NextWord, false),
+            Some(Selection::caret(2))
+        );
+        assert_eq!(
+            move_selection(text, caret, Movement::TextEnd, true),
+            Some(Selection::new(1, 5))
+        );
+
+        // Moving by a character collapses the selection, unless it's extended.
+        let selection = Selection::new(1, 4);
+        assert_eq!(
+            move_selection(text, selection, Movement::PrevChar, false),
+            Some(Selection::caret(1))
+        );
+        assert_eq!(
+            move_selection(text, selection, Movement::PrevChar, true),
+            Some(Selection::new(1, 3))
+        );
+        assert_eq!(
+            move_selection(text, selection, Movement::LineDown, false),
+            None
+        );
+    }
+
+    #[test]
+    fn constrained_selection() {
+        let text = "aé";
+        assert_eq!(
+            Selection::new(2, 10).constrained(text),
+            Selection::new(1, 3)
+        );
+    }
+}
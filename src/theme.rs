@@ -34,6 +34,7 @@ pub const BUTTON_BORDER_RADIUS: f64 = 4.;
 pub const BUTTON_BORDER_WIDTH: f64 = 2.;
 pub const BORDER_DARK: Color = Color::rgb8(0x3a, 0x3a, 0x3a);
 pub const BORDER_LIGHT: Color = Color::rgb8(0xa1, 0xa1, 0xa1);
+pub const BACKDROP_COLOR: Color = Color::rgba8(0x00, 0x00, 0x00, 0x80);
 pub const SELECTED_TEXT_BACKGROUND_COLOR: Color = Color::rgb8(0x43, 0x70, 0xA8);
 pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Color = Color::rgb8(0x74, 0x74, 0x74);
 pub const SELECTION_TEXT_COLOR: Color = Color::rgb8(0x00, 0x00, 0x00);
@@ -0,0 +1,106 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Timer tokens and the queue of pending timers.
+
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
+use crate::WidgetId;
+
+/// A token that uniquely identifies a timer request.
+///
+/// Returned by [`request_timer`](crate::EventCtx::request_timer), and passed back
+/// to the requesting widget in [`LifeCycle::Timer`](crate::LifeCycle::Timer)
+/// once the timer expires.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct TimerToken(NonZeroU64);
+
+#[derive(Debug)]
+pub(crate) struct Timer {
+    pub(crate) deadline: Instant,
+    pub(crate) token: TimerToken,
+    pub(crate) widget_id: WidgetId,
+}
+
+/// The list of timers requested by widgets which haven't fired yet.
+///
+/// In unit tests, the queue runs on a mock clock which only moves forward when
+/// [`TestHarness::move_timers_forward`](crate::testing::TestHarness::move_timers_forward)
+/// is called.
+#[derive(Debug)]
+pub(crate) struct TimerQueue {
+    timers: Vec<Timer>,
+    mock_now: Option<Instant>,
+}
+
+// ---
+
+impl TimerToken {
+    pub(crate) fn next() -> TimerToken {
+        static TIMER_TOKEN_COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = TIMER_TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed);
+        TimerToken(id.try_into().unwrap())
+    }
+
+    /// Return the raw integer value of this token.
+    pub fn into_raw(self) -> u64 {
+        self.0.into()
+    }
+}
+
+impl TimerQueue {
+    pub(crate) fn new() -> Self {
+        TimerQueue {
+            timers: Vec::new(),
+            mock_now: None,
+        }
+    }
+
+    /// Stop using the system clock; time will only pass through [`Self::set_mock_now`].
+    pub(crate) fn enable_mock_clock(&mut self) {
+        self.mock_now = Some(Instant::now());
+    }
+
+    pub(crate) fn set_mock_now(&mut self, now: Instant) {
+        debug_assert!(self.mock_now.is_some(), "mock clock isn't enabled");
+        self.mock_now = Some(now);
+    }
+
+    /// The current time, as seen by timers.
+    pub(crate) fn now(&self) -> Instant {
+        self.mock_now.unwrap_or_else(Instant::now)
+    }
+
+    pub(crate) fn add(&mut self, delay: Duration, widget_id: WidgetId) -> TimerToken {
+        let token = TimerToken::next();
+        self.timers.push(Timer {
+            deadline: self.now() + delay,
+            token,
+            widget_id,
+        });
+        token
+    }
+
+    /// The earliest deadline among pending timers, if any.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Remove and return every timer whose deadline has passed, earliest first.
+    pub(crate) fn pop_expired(&mut self) -> Vec<Timer> {
+        let now = self.now();
+        let (mut expired, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|timer| timer.deadline <= now);
+        self.timers = pending;
+        // Stable sort, so timers with the same deadline fire in request order.
+        expired.sort_by_key(|timer| timer.deadline);
+        expired
+    }
+}
@@ -4,7 +4,8 @@
 
 //! A button widget.
 
-use smallvec::SmallVec;
+use accesskit::{DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, Span};
 use vello::Scene;
 
@@ -12,8 +13,8 @@ use crate::action::Action;
 use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
 use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
 use crate::{
-    theme, ArcStr, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Size, StatusChange, TextEvent, Widget,
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget,
 };
 
 // the minimum padding added to a button.
@@ -100,6 +101,18 @@ impl Widget for Button {
         self.label.on_text_event(ctx, event);
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::Default
+            && !ctx.is_disabled()
+        {
+            ctx.submit_action(Action::ButtonPressed);
+            trace!("Button {:?} activated", ctx.widget_id());
+            ctx.set_handled();
+        }
+        self.label.on_access_event(ctx, event);
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
         ctx.request_paint();
     }
@@ -170,8 +183,19 @@ impl Widget for Button {
         self.label.paint(ctx, scene);
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        SmallVec::new()
+        smallvec![self.label.as_dyn()]
     }
 
     fn make_trace_span(&self) -> Span {
@@ -4,8 +4,9 @@
 
 //! A checkbox widget.
 
+use accesskit::{Checked, DefaultActionVerb, Role};
 use kurbo::{Affine, Stroke};
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, Span};
 use vello::Scene;
 
@@ -14,8 +15,8 @@ use crate::kurbo::{BezPath, Cap, Join, Size};
 use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
 use crate::widget::{Label, WidgetMut, WidgetRef};
 use crate::{
-    theme, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
 };
 
 /// A checkbox that can be toggled.
@@ -42,12 +43,19 @@ impl Checkbox {
             label: WidgetPod::new(label),
         }
     }
+
+    fn toggle(&mut self, ctx: &mut EventCtx) {
+        self.checked = !self.checked;
+        ctx.submit_action(Action::CheckboxChecked(self.checked));
+        ctx.request_accessibility_update();
+    }
 }
 
 impl<'a> CheckboxMut<'a> {
     pub fn set_checked(&mut self, checked: bool) {
         self.widget.checked = checked;
         self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
     }
 
     /// Set the text.
@@ -62,6 +70,7 @@ impl<'a> CheckboxMut<'a> {
 
 impl Widget for Checkbox {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
         match event {
             PointerEvent::PointerDown(_, _) => {
                 if !ctx.is_disabled() {
@@ -73,8 +82,7 @@ impl Widget for Checkbox {
             PointerEvent::PointerUp(_, _) => {
                 if ctx.is_active() && !ctx.is_disabled() {
                     if ctx.is_hot() {
-                        self.checked = !self.checked;
-                        ctx.submit_action(Action::CheckboxChecked(self.checked));
+                        self.toggle(ctx);
                         trace!("Checkbox {:?} released", ctx.widget_id());
                     }
                     ctx.request_paint();
@@ -85,7 +93,22 @@ impl Widget for Checkbox {
         }
     }
 
-    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::Default
+            && !ctx.is_disabled()
+        {
+            self.toggle(ctx);
+            ctx.request_paint();
+            trace!("Checkbox {:?} activated", ctx.widget_id());
+            ctx.set_handled();
+        }
+    }
 
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
         ctx.request_paint();
@@ -168,8 +191,28 @@ impl Widget for Checkbox {
         self.label.paint(ctx, scene);
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::CheckBox
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_checked(if self.checked {
+            Checked::True
+        } else {
+            Checked::False
+        });
+        node.set_default_action_verb(if self.checked {
+            DefaultActionVerb::Uncheck
+        } else {
+            DefaultActionVerb::Check
+        });
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        SmallVec::new()
+        smallvec![self.label.as_dyn()]
     }
 
     fn make_trace_span(&self) -> Span {
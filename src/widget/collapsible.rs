@@ -0,0 +1,700 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A container whose body can be collapsed under its header.
+
+use accesskit::{DefaultActionVerb, Role};
+use kurbo::BezPath;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+/// How long the body takes to open or close, in seconds.
+const EXPAND_DURATION: f64 = 0.2;
+const HEADER_PADDING_HORIZONTAL: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+const HEADER_PADDING_VERTICAL: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+/// The width of the area holding the disclosure arrow, on the left of the title.
+const ARROW_AREA_WIDTH: f64 = 16.0;
+const ARROW_HALF_WIDTH: f64 = 4.0;
+
+/// A container with a clickable header, and a body which can be collapsed under it.
+///
+/// The body's height animates when it opens and closes. While collapsed, the body
+/// is stashed: it doesn't receive events, can't be focused, isn't laid out and isn't
+/// painted. It's stashed as soon as it starts closing, so only the space it took
+/// animates closed.
+///
+/// The header is a stop in the focus chain; when it's focused, Space and Enter
+/// toggle the body, and the Right and Left arrows expand and collapse it.
+///
+/// Emits [`Action::CollapsibleExpanded`] with the new state when the user expands or
+/// collapses the body.
+pub struct Collapsible {
+    header: WidgetPod<CollapsibleHeader>,
+    body: WidgetPod<Box<dyn Widget>>,
+    expanded: bool,
+    /// How much of the body is shown, from 0 (collapsed) to 1 (expanded).
+    progress: f64,
+    /// The size of the body the last time it was laid out, used while it closes.
+    body_size: Size,
+}
+
+crate::declare_widget!(CollapsibleMut, Collapsible);
+
+/// The header of a [`Collapsible`]: a disclosure arrow and a title.
+///
+/// Clicks and keys are handled by the collapsible.
+pub(crate) struct CollapsibleHeader {
+    label: WidgetPod<Label>,
+    expanded: bool,
+}
+
+crate::declare_widget!(CollapsibleHeaderMut, CollapsibleHeader);
+
+// --- METHODS ---
+
+impl Collapsible {
+    /// Create a new collapsible container, with its body collapsed.
+    pub fn new(title: impl Into<ArcStr>, body: impl Widget) -> Self {
+        Collapsible {
+            header: WidgetPod::new(CollapsibleHeader::new(title.into(), false)),
+            body: WidgetPod::new(body).boxed(),
+            expanded: false,
+            progress: 0.0,
+            body_size: Size::ZERO,
+        }
+    }
+
+    /// Builder-style method to set whether the body starts expanded.
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self.progress = self.target_progress();
+        self.header = WidgetPod::new(CollapsibleHeader::new(self.title(), expanded));
+        self
+    }
+
+    /// Return `true` if the body is expanded.
+    ///
+    /// This is the state the body is animating towards.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Return the text of the header.
+    pub fn title(&self) -> ArcStr {
+        self.header.as_ref().label.as_ref().text()
+    }
+
+    fn target_progress(&self) -> f64 {
+        if self.expanded {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// The body takes space while it's expanded or still closing.
+    fn body_takes_space(&self) -> bool {
+        self.expanded || self.progress > 0.0
+    }
+
+    /// Whether the widget should be expanded after a key press on the header, if the key
+    /// does anything.
+    fn expanded_for_key(&self, key: &Key) -> Option<bool> {
+        match key {
+            Key::Named(NamedKey::Space | NamedKey::Enter) => Some(!self.expanded),
+            Key::Named(NamedKey::ArrowRight) => Some(true),
+            Key::Named(NamedKey::ArrowLeft) => Some(false),
+            _ => None,
+        }
+    }
+
+    fn set_expanded(&mut self, ctx: &mut EventCtx, expanded: bool) {
+        if self.expanded == expanded {
+            return;
+        }
+        self.expanded = expanded;
+        trace!("Collapsible {:?} expanded: {}", ctx.widget_id(), expanded);
+        ctx.get_mut(&mut self.header).set_expanded(expanded);
+        if !expanded && self.body.has_focus() {
+            // A widget in the closing body can't keep the focus.
+            ctx.set_focus(self.header.id());
+        }
+        ctx.set_stashed(&mut self.body, !expanded);
+        ctx.request_anim_frame();
+        ctx.request_layout();
+        ctx.request_accessibility_update();
+        ctx.submit_action(Action::CollapsibleExpanded(expanded));
+    }
+}
+
+impl CollapsibleMut<'_> {
+    /// Expand or collapse the body.
+    ///
+    /// The body jumps to its new height without animating, and no action is emitted.
+    pub fn set_expanded(&mut self, expanded: bool) {
+        self.widget.expanded = expanded;
+        self.widget.progress = self.widget.target_progress();
+        self.ctx
+            .get_mut(&mut self.widget.header)
+            .set_expanded(expanded);
+        if !expanded && self.widget.body.has_focus() {
+            // A widget in the collapsed body can't keep the focus.
+            self.ctx.set_focus(self.widget.header.id());
+        }
+        self.ctx.set_stashed(&mut self.widget.body, !expanded);
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the text of the header.
+    pub fn set_title(&mut self, title: impl Into<ArcStr>) {
+        let mut header = self.ctx.get_mut(&mut self.widget.header);
+        header
+            .ctx
+            .get_mut(&mut header.widget.label)
+            .set_text(title.into());
+        header.ctx.request_accessibility_update();
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the body.
+    pub fn body_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.body)
+    }
+}
+
+impl CollapsibleHeader {
+    fn new(title: ArcStr, expanded: bool) -> Self {
+        CollapsibleHeader {
+            label: WidgetPod::new(Label::new(title)),
+            expanded,
+        }
+    }
+}
+
+impl CollapsibleHeaderMut<'_> {
+    fn set_expanded(&mut self, expanded: bool) {
+        self.widget.expanded = expanded;
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+/// Move `progress` towards `target`, by the fraction of the animation that took
+/// `interval` nanoseconds.
+fn animation_step(progress: f64, target: f64, interval: u64) -> f64 {
+    let step = (interval as f64) * 1e-9 / EXPAND_DURATION;
+    if target > progress {
+        (progress + step).min(target)
+    } else {
+        (progress - step).max(target)
+    }
+}
+
+/// Ease the body's height in and out, so that it starts and stops moving smoothly.
+fn eased(progress: f64) -> f64 {
+    progress * progress * (3.0 - 2.0 * progress)
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Collapsible {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.header.on_pointer_event(ctx, event);
+        self.body.on_pointer_event(ctx, event);
+        if ctx.is_disabled() {
+            return;
+        }
+
+        match event {
+            PointerEvent::PointerDown(_, _) => {
+                if !ctx.is_handled() && self.header.state().is_hot {
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if ctx.is_active() && self.header.state().is_hot {
+                    self.set_expanded(ctx, !self.expanded);
+                    ctx.set_handled();
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.header.on_text_event(ctx, event);
+        self.body.on_text_event(ctx, event);
+        if ctx.is_handled() || ctx.is_disabled() || !self.header.has_focus() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        let Some(expanded) = self.expanded_for_key(&key_event.logical_key) else {
+            return;
+        };
+        self.set_expanded(ctx, expanded);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.header.on_access_event(ctx, event);
+        self.body.on_access_event(ctx, event);
+        // Assistive technologies activate the header itself.
+        if event.target == self.header.id()
+            && event.action == accesskit::Action::Default
+            && !ctx.is_disabled()
+        {
+            self.set_expanded(ctx, !self.expanded);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                if !self.expanded {
+                    ctx.set_stashed(&mut self.body, true);
+                }
+            }
+            LifeCycle::AnimFrame(interval) => {
+                let target = self.target_progress();
+                if self.progress != target {
+                    self.progress = animation_step(self.progress, target, *interval);
+                    if self.progress != target {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_layout();
+                }
+            }
+            _ => {}
+        }
+
+        self.header.lifecycle(ctx, event);
+        // A stashed body is left out of the focus chain.
+        self.body.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let header_size = self.header.layout(ctx, &bc.loosen());
+        ctx.place_child(&mut self.header, Point::ORIGIN);
+
+        if self.expanded {
+            let body_bc = bc.shrink((0.0, header_size.height)).loosen();
+            self.body_size = self.body.layout(ctx, &body_bc);
+            ctx.place_child(&mut self.body, Point::new(0.0, header_size.height));
+        }
+        let body_size = if self.body_takes_space() {
+            self.body_size
+        } else {
+            Size::ZERO
+        };
+
+        let size = bc.constrain(Size::new(
+            header_size.width.max(body_size.width),
+            header_size.height + body_size.height * eased(self.progress),
+        ));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.header.paint(ctx, scene);
+
+        if self.expanded {
+            // While it opens, the body is cut off at the bottom.
+            let size = ctx.size();
+            let header_height = self.header.layout_rect().height();
+            let clip_rect = Rect::new(0.0, header_height, size.width, size.height);
+            scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+            self.body.paint(ctx, scene);
+            scene.pop_layer();
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        // A stashed body is left out of the tree.
+        self.header.accessibility(ctx);
+        self.body.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec::smallvec![self.header.as_dyn(), self.body.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Collapsible")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("expanded: {}", self.expanded))
+    }
+}
+
+impl Widget for CollapsibleHeader {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let label_size = self.label.layout(ctx, bc);
+        let content_height = label_size.height.max(theme::BASIC_WIDGET_HEIGHT);
+        let size = bc.constrain(Size::new(
+            ARROW_AREA_WIDTH + label_size.width + 2.0 * HEADER_PADDING_HORIZONTAL,
+            content_height + 2.0 * HEADER_PADDING_VERTICAL,
+        ));
+        ctx.place_child(
+            &mut self.label,
+            Point::new(
+                HEADER_PADDING_HORIZONTAL + ARROW_AREA_WIDTH,
+                (size.height - label_size.height) / 2.0,
+            ),
+        );
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        if ctx.is_hot() && !ctx.is_disabled() {
+            fill_color(scene, &size.to_rect(), theme::BACKGROUND_LIGHT);
+        }
+        if ctx.is_focused() && !ctx.is_disabled() {
+            stroke(
+                scene,
+                &size.to_rect().inset(-0.5),
+                theme::PRIMARY_LIGHT,
+                1.0,
+            );
+        }
+
+        // The arrow points right when collapsed, and down when expanded.
+        let center_x = HEADER_PADDING_HORIZONTAL + ARROW_AREA_WIDTH / 2.0;
+        let center_y = size.height / 2.0;
+        let half_depth = ARROW_HALF_WIDTH / 2.0;
+        let mut arrow = BezPath::new();
+        if self.expanded {
+            arrow.move_to((center_x - ARROW_HALF_WIDTH, center_y - half_depth));
+            arrow.line_to((center_x + ARROW_HALF_WIDTH, center_y - half_depth));
+            arrow.line_to((center_x, center_y + half_depth));
+        } else {
+            arrow.move_to((center_x - half_depth, center_y - ARROW_HALF_WIDTH));
+            arrow.line_to((center_x + half_depth, center_y));
+            arrow.line_to((center_x - half_depth, center_y + ARROW_HALF_WIDTH));
+        }
+        arrow.close_path();
+        let arrow_color = if ctx.is_disabled() {
+            theme::DISABLED_TEXT_COLOR
+        } else {
+            theme::TEXT_COLOR
+        };
+        fill_color(scene, &arrow, arrow_color);
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_expanded(self.expanded);
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("CollapsibleHeader")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.label.as_ref().text().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::ModifiersState;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, SizedBox, Switch};
+    use crate::WidgetId;
+
+    fn collapsible_of(harness: &TestHarness, id: WidgetId) -> WidgetRef<'_, Collapsible> {
+        harness.get_widget(id).downcast::<Collapsible>().unwrap()
+    }
+
+    fn header_id(harness: &TestHarness, id: WidgetId) -> WidgetId {
+        collapsible_of(harness, id).header.id()
+    }
+
+    fn body_is_stashed(harness: &TestHarness, id: WidgetId) -> bool {
+        collapsible_of(harness, id).body.state().is_stashed
+    }
+
+    #[test]
+    fn click_header() {
+        let [collapsible_id] = widget_ids();
+        let body = SizedBox::empty().width(100.0).height(50.0);
+        let widget =
+            Flex::column().with_child_id(Collapsible::new("Details", body), collapsible_id);
+
+        let mut harness = TestHarness::create(widget);
+        assert!(!collapsible_of(&harness, collapsible_id).is_expanded());
+        assert!(body_is_stashed(&harness, collapsible_id));
+
+        let header_id = header_id(&harness, collapsible_id);
+        harness.mouse_click_on(header_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CollapsibleExpanded(true), collapsible_id))
+        );
+        assert!(collapsible_of(&harness, collapsible_id).is_expanded());
+        assert!(!body_is_stashed(&harness, collapsible_id));
+
+        harness.mouse_click_on(header_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CollapsibleExpanded(false), collapsible_id))
+        );
+        assert!(!collapsible_of(&harness, collapsible_id).is_expanded());
+    }
+
+    #[test]
+    fn collapse_with_focused_body() {
+        let [collapsible_id, inside_id, after_id] = widget_ids();
+        let body = Flex::column().with_child_id(Switch::new(false), inside_id);
+        let widget = Flex::column()
+            .with_child_id(
+                Collapsible::new("Details", body).with_expanded(true),
+                collapsible_id,
+            )
+            .with_child_id(Switch::new(false), after_id);
+
+        let mut harness = TestHarness::create(widget);
+        let header_id = header_id(&harness, collapsible_id);
+        let expanded_height = height_of(&harness, collapsible_id);
+        harness.process_access_event(AccessEvent {
+            target: inside_id,
+            action: accesskit::Action::Focus,
+            data: None,
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(inside_id));
+
+        harness.mouse_click_on(header_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CollapsibleExpanded(false), collapsible_id))
+        );
+        // The body is stashed right away, while the space it took animates closed.
+        assert!(body_is_stashed(&harness, collapsible_id));
+        assert_eq!(height_of(&harness, collapsible_id), expanded_height);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(header_id));
+
+        // The focus chain skips the body.
+        let tab = Key::Named(NamedKey::Tab);
+        harness.keyboard_press_unused_key(tab.clone(), ModifiersState::empty());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(after_id));
+        harness.keyboard_press_unused_key(tab, ModifiersState::SHIFT);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(header_id));
+
+        // Expanding brings the body back in the focus chain.
+        harness.mouse_click_on(header_id);
+        assert!(!body_is_stashed(&harness, collapsible_id));
+        harness.keyboard_press_unused_key(Key::Named(NamedKey::Tab), ModifiersState::empty());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(inside_id));
+    }
+
+    #[test]
+    fn access_tree() {
+        let widget = Collapsible::new("Details", Label::new("Hidden text"));
+
+        let mut harness = TestHarness::create(widget);
+        let collapsible_id = harness.root_widget().id();
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  Group
+    Button
+      StaticText \"Details\"
+"
+        );
+
+        harness.process_access_event(AccessEvent {
+            target: header_id(&harness, collapsible_id),
+            action: accesskit::Action::Default,
+            data: None,
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CollapsibleExpanded(true), collapsible_id))
+        );
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  Group
+    Button
+      StaticText \"Details\"
+    StaticText \"Hidden text\"
+"
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let named = Key::Named;
+        let collapsed = Collapsible::new("Details", Label::new("Body"));
+        assert_eq!(
+            collapsed.expanded_for_key(&named(NamedKey::Space)),
+            Some(true)
+        );
+        assert_eq!(
+            collapsed.expanded_for_key(&named(NamedKey::Enter)),
+            Some(true)
+        );
+        assert_eq!(
+            collapsed.expanded_for_key(&named(NamedKey::ArrowRight)),
+            Some(true)
+        );
+        assert_eq!(
+            collapsed.expanded_for_key(&named(NamedKey::ArrowLeft)),
+            Some(false)
+        );
+        assert_eq!(
+            collapsed.expanded_for_key(&named(NamedKey::ArrowDown)),
+            None
+        );
+
+        let expanded = Collapsible::new("Details", Label::new("Body")).with_expanded(true);
+        assert_eq!(
+            expanded.expanded_for_key(&named(NamedKey::Space)),
+            Some(false)
+        );
+        assert_eq!(
+            expanded.expanded_for_key(&named(NamedKey::Enter)),
+            Some(false)
+        );
+        assert_eq!(
+            expanded.expanded_for_key(&named(NamedKey::ArrowRight)),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn animation_steps() {
+        // A 100ms frame is half of the animation.
+        assert_eq!(animation_step(0.0, 1.0, 100_000_000), 0.5);
+        assert_eq!(animation_step(0.5, 1.0, 200_000_000), 1.0);
+        assert_eq!(animation_step(1.0, 0.0, 100_000_000), 0.5);
+        assert_eq!(animation_step(0.25, 0.0, 200_000_000), 0.0);
+
+        assert_eq!(eased(0.0), 0.0);
+        assert_eq!(eased(0.5), 0.5);
+        assert_eq!(eased(1.0), 1.0);
+    }
+
+    fn height_of(harness: &TestHarness, id: WidgetId) -> f64 {
+        harness.get_widget(id).state().layout_rect().height()
+    }
+
+    #[test]
+    fn edit_collapsible() {
+        let [collapsible_id, inside_id] = widget_ids();
+        let body = SizedBox::new_with_id(Switch::new(false), inside_id)
+            .width(100.0)
+            .height(50.0);
+        let widget =
+            Flex::column().with_child_id(Collapsible::new("Details", body), collapsible_id);
+
+        let mut harness = TestHarness::create(widget);
+        let collapsed_height = height_of(&harness, collapsible_id);
+
+        harness.edit_root_widget(|mut root| {
+            let mut flex = root.downcast::<Flex>().unwrap();
+            let mut child = flex.child_mut(0).unwrap();
+            let mut collapsible = child.downcast::<Collapsible>().unwrap();
+            collapsible.set_expanded(true);
+            collapsible.set_title("More details");
+        });
+        assert_eq!(harness.pop_action(), None);
+        assert!(!body_is_stashed(&harness, collapsible_id));
+        assert_eq!(
+            &*collapsible_of(&harness, collapsible_id).title(),
+            "More details"
+        );
+        // The body jumps to its full height.
+        assert_eq!(height_of(&harness, collapsible_id), collapsed_height + 50.0);
+
+        harness.process_access_event(AccessEvent {
+            target: inside_id,
+            action: accesskit::Action::Focus,
+            data: None,
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(inside_id));
+
+        harness.edit_root_widget(|mut root| {
+            let mut flex = root.downcast::<Flex>().unwrap();
+            let mut child = flex.child_mut(0).unwrap();
+            let mut collapsible = child.downcast::<Collapsible>().unwrap();
+            collapsible.set_expanded(false);
+        });
+        assert!(body_is_stashed(&harness, collapsible_id));
+        assert_eq!(height_of(&harness, collapsible_id), collapsed_height);
+        // The focus moves from the collapsed body to the header.
+        let header_id = header_id(&harness, collapsible_id);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(header_id));
+    }
+}
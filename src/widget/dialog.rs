@@ -0,0 +1,584 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A modal dialog.
+
+use accesskit::{DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Point, Size};
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Label, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+const DIALOG_PADDING: f64 = 16.0;
+/// The space between the title, the content and the buttons.
+const SECTION_SPACING: f64 = 12.0;
+const BUTTON_SPACING: f64 = theme::WIDGET_PADDING_HORIZONTAL;
+const DIALOG_MIN_WIDTH: f64 = 240.0;
+const DIALOG_BORDER_RADIUS: f64 = 6.0;
+const BUTTON_LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+const BUTTON_MIN_WIDTH: f64 = 64.0;
+
+/// A modal dialog, with a title, a content widget, and a row of buttons.
+///
+/// Dialogs are opened above the root widget with [`EventCtx::open_dialog`],
+/// [`DriverCtx::open_dialog`](crate::app_driver::DriverCtx::open_dialog), or
+/// [`TestHarness::open_dialog`](crate::testing::TestHarness::open_dialog). While a dialog
+/// is open, the content below it is dimmed and doesn't get pointer events, and the focus
+/// stays inside the dialog.
+///
+/// Pressing a button closes the dialog and emits [`Action::DialogClosed`] with the
+/// button's index. Unless a widget inside the dialog uses them, Enter presses the
+/// confirm button and Escape presses the cancel button.
+///
+/// [`EventCtx::open_dialog`]: crate::EventCtx::open_dialog
+pub struct Dialog {
+    title: WidgetPod<Label>,
+    content: WidgetPod<Box<dyn Widget>>,
+    buttons: Vec<WidgetPod<DialogButton>>,
+    confirm_button: Option<usize>,
+    cancel_button: Option<usize>,
+}
+
+crate::declare_widget!(DialogMut, Dialog);
+
+/// A button of a [`Dialog`].
+///
+/// The dialog checks which of its buttons the user presses.
+pub(crate) struct DialogButton {
+    label: WidgetPod<Label>,
+}
+
+crate::declare_widget!(DialogButtonMut, DialogButton);
+
+// --- METHODS ---
+
+impl Dialog {
+    /// Create a new dialog without buttons.
+    pub fn new(title: impl Into<ArcStr>, content: impl Widget) -> Self {
+        Dialog {
+            title: WidgetPod::new(Label::new(title)),
+            content: WidgetPod::new(content).boxed(),
+            buttons: Vec::new(),
+            confirm_button: None,
+            cancel_button: None,
+        }
+    }
+
+    /// Builder-style method to add a button at the end of the button row.
+    pub fn with_button(mut self, text: impl Into<ArcStr>) -> Self {
+        self.buttons.push(WidgetPod::new(DialogButton {
+            label: WidgetPod::new(Label::new(text)),
+        }));
+        self
+    }
+
+    /// Builder-style method to add a button which Enter presses.
+    pub fn with_confirm_button(mut self, text: impl Into<ArcStr>) -> Self {
+        self.confirm_button = Some(self.buttons.len());
+        self.with_button(text)
+    }
+
+    /// Builder-style method to add a button which Escape presses.
+    pub fn with_cancel_button(mut self, text: impl Into<ArcStr>) -> Self {
+        self.cancel_button = Some(self.buttons.len());
+        self.with_button(text)
+    }
+
+    /// Return the dialog's title.
+    pub fn title(&self) -> ArcStr {
+        self.title.as_ref().text()
+    }
+
+    /// Return the number of buttons.
+    pub fn button_count(&self) -> usize {
+        self.buttons.len()
+    }
+
+    /// Return the text of the button at `idx`.
+    pub fn button_text(&self, idx: usize) -> Option<ArcStr> {
+        let button = self.buttons.get(idx)?;
+        Some(button.as_ref().label.as_ref().text())
+    }
+
+    /// Return the index of the button which Enter presses.
+    pub fn confirm_button(&self) -> Option<usize> {
+        self.confirm_button
+    }
+
+    /// Return the index of the button which Escape presses.
+    pub fn cancel_button(&self) -> Option<usize> {
+        self.cancel_button
+    }
+
+    /// The button pressed by a key which no widget in the dialog used.
+    ///
+    /// Enter and Space press the focused button, if any.
+    fn button_for_key(&self, key: &Key) -> Option<usize> {
+        let focused_button = self.buttons.iter().position(|button| button.has_focus());
+        match (key, focused_button) {
+            (Key::Named(NamedKey::Enter | NamedKey::Space), Some(idx)) => Some(idx),
+            (Key::Named(NamedKey::Enter), None) => self.confirm_button,
+            (Key::Named(NamedKey::Escape), _) => self.cancel_button,
+            _ => None,
+        }
+    }
+
+    fn close(&mut self, ctx: &mut EventCtx, idx: usize) {
+        trace!("Dialog {:?} closed with button {}", ctx.widget_id(), idx);
+        ctx.submit_action(Action::DialogClosed(idx));
+        ctx.close_popup(ctx.widget_id());
+        ctx.set_handled();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Dialog {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // A button is pressed when the pointer is released over it after being
+        // pressed on it, which made it active.
+        let active_button = match event {
+            PointerEvent::PointerUp(_, _) => self.buttons.iter().position(WidgetPod::is_active),
+            _ => None,
+        };
+        self.title.on_pointer_event(ctx, event);
+        self.content.on_pointer_event(ctx, event);
+        for button in &mut self.buttons {
+            button.on_pointer_event(ctx, event);
+        }
+        if let Some(idx) = active_button.filter(|&idx| self.buttons[idx].is_hot()) {
+            self.close(ctx, idx);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.title.on_text_event(ctx, event);
+        self.content.on_text_event(ctx, event);
+        for button in &mut self.buttons {
+            button.on_text_event(ctx, event);
+        }
+
+        if ctx.is_handled() {
+            return;
+        }
+        if let TextEvent::KeyboardKey(key_event, _) = event {
+            if key_event.state != ElementState::Pressed {
+                return;
+            }
+            if let Some(idx) = self.button_for_key(&key_event.logical_key) {
+                self.close(ctx, idx);
+            }
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.title.on_access_event(ctx, event);
+        self.content.on_access_event(ctx, event);
+        for button in &mut self.buttons {
+            button.on_access_event(ctx, event);
+        }
+
+        if ctx.is_handled() || event.action != accesskit::Action::Default {
+            return;
+        }
+        // Assistive technologies press the buttons themselves.
+        let target = self
+            .buttons
+            .iter()
+            .position(|button| button.id() == event.target);
+        if let Some(idx) = target {
+            self.close(ctx, idx);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.title.lifecycle(ctx, event);
+        self.content.lifecycle(ctx, event);
+        for button in &mut self.buttons {
+            button.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max_inner = Size::new(
+            (bc.max().width - 2.0 * DIALOG_PADDING).max(0.0),
+            (bc.max().height - 2.0 * DIALOG_PADDING).max(0.0),
+        );
+        let inner_bc = BoxConstraints::new(Size::ZERO, max_inner);
+
+        let title_size = self.title.layout(ctx, &inner_bc);
+        let button_sizes: Vec<Size> = self
+            .buttons
+            .iter_mut()
+            .map(|button| button.layout(ctx, &inner_bc))
+            .collect();
+        let buttons_width = button_sizes.iter().map(|size| size.width).sum::<f64>()
+            + BUTTON_SPACING * button_sizes.len().saturating_sub(1) as f64;
+        let buttons_height = button_sizes
+            .iter()
+            .map(|size| size.height)
+            .fold(0.0, f64::max);
+        let buttons_section = if self.buttons.is_empty() {
+            0.0
+        } else {
+            SECTION_SPACING + buttons_height
+        };
+
+        // The content gets the height left by the title and the buttons.
+        let content_max_height =
+            (max_inner.height - title_size.height - SECTION_SPACING - buttons_section).max(0.0);
+        let content_bc =
+            BoxConstraints::new(Size::ZERO, Size::new(max_inner.width, content_max_height));
+        let content_size = self.content.layout(ctx, &content_bc);
+
+        let inner_width = title_size
+            .width
+            .max(content_size.width)
+            .max(buttons_width)
+            .max(DIALOG_MIN_WIDTH - 2.0 * DIALOG_PADDING);
+        let size = bc.constrain(Size::new(
+            inner_width + 2.0 * DIALOG_PADDING,
+            title_size.height
+                + SECTION_SPACING
+                + content_size.height
+                + buttons_section
+                + 2.0 * DIALOG_PADDING,
+        ));
+
+        ctx.place_child(&mut self.title, Point::new(DIALOG_PADDING, DIALOG_PADDING));
+        let content_y = DIALOG_PADDING + title_size.height + SECTION_SPACING;
+        ctx.place_child(&mut self.content, Point::new(DIALOG_PADDING, content_y));
+
+        // The buttons are aligned to the right.
+        let buttons_y = size.height - DIALOG_PADDING - buttons_height;
+        let mut x = size.width - DIALOG_PADDING - buttons_width;
+        for (button, button_size) in self.buttons.iter_mut().zip(button_sizes) {
+            let y = buttons_y + (buttons_height - button_size.height) / 2.0;
+            ctx.place_child(button, Point::new(x, y));
+            x += button_size.width + BUTTON_SPACING;
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let rounded_rect = ctx.size().to_rect().to_rounded_rect(DIALOG_BORDER_RADIUS);
+        fill_color(scene, &rounded_rect, theme::BACKGROUND_LIGHT);
+        stroke(scene, &rounded_rect, theme::BORDER_LIGHT, 1.0);
+
+        self.title.paint(ctx, scene);
+        self.content.paint(ctx, scene);
+        for button in &mut self.buttons {
+            button.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Dialog
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_name(self.title.as_ref().text().to_string());
+        node.set_modal();
+
+        self.title.accessibility(ctx);
+        self.content.accessibility(ctx);
+        for button in &mut self.buttons {
+            button.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        let mut children = SmallVec::new();
+        children.push(self.title.as_dyn());
+        children.push(self.content.as_dyn());
+        children.extend(self.buttons.iter().map(|button| button.as_dyn()));
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Dialog")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.title.as_ref().text().to_string())
+    }
+}
+
+impl Widget for DialogButton {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+        match event {
+            PointerEvent::PointerDown(_, _) => {
+                ctx.set_active(true);
+                ctx.request_paint();
+            }
+            PointerEvent::PointerUp(_, _) => {
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(BUTTON_LABEL_INSETS.x_value(), BUTTON_LABEL_INSETS.y_value());
+        let label_size = self.label.layout(ctx, &bc.shrink(padding).loosen());
+        let size = bc.constrain(Size::new(
+            (label_size.width + padding.width).max(BUTTON_MIN_WIDTH),
+            (label_size.height + padding.height).max(theme::BORDERED_WIDGET_HEIGHT),
+        ));
+        let label_offset = (size.to_vec2() - label_size.to_vec2()) / 2.0;
+        ctx.place_child(&mut self.label, label_offset.to_point());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let stroke_width = theme::BUTTON_BORDER_WIDTH;
+        let rounded_rect = ctx
+            .size()
+            .to_rect()
+            .inset(-stroke_width / 2.0)
+            .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+
+        let bg_gradient = if ctx.is_active() {
+            [theme::BUTTON_DARK, theme::BUTTON_LIGHT]
+        } else {
+            [theme::BUTTON_LIGHT, theme::BUTTON_DARK]
+        };
+        let border_color = if ctx.is_focused() {
+            theme::PRIMARY_LIGHT
+        } else if ctx.is_hot() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &rounded_rect, border_color, stroke_width);
+        fill_lin_gradient(
+            scene,
+            &rounded_rect,
+            bg_gradient,
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("DialogButton")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.label.as_ref().text().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Button, Flex};
+    use crate::WidgetId;
+
+    fn dialog_of(harness: &TestHarness, id: WidgetId) -> WidgetRef<'_, Dialog> {
+        harness.get_widget(id).downcast::<Dialog>().unwrap()
+    }
+
+    fn button_ids(harness: &TestHarness, id: WidgetId) -> Vec<WidgetId> {
+        let dialog = dialog_of(harness, id);
+        dialog.buttons.iter().map(|button| button.id()).collect()
+    }
+
+    fn delete_dialog() -> Dialog {
+        Dialog::new("Delete file?", Label::new("This can't be undone."))
+            .with_cancel_button("Cancel")
+            .with_confirm_button("Delete")
+    }
+
+    #[test]
+    fn button_closes_dialog() {
+        let [button_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Button::new("Delete"), button_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 400.0));
+        let dialog_id = harness.open_dialog(delete_dialog());
+        assert_eq!(harness.popups().len(), 1);
+
+        // The dialog is centered in the window.
+        let dialog_rect = harness.get_widget(dialog_id).state().window_layout_rect();
+        let offset = dialog_rect.center() - Point::new(200.0, 200.0);
+        assert!(offset.hypot() < 1e-6);
+
+        let buttons = button_ids(&harness, dialog_id);
+        harness.mouse_click_on(buttons[1]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DialogClosed(1), dialog_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert!(harness.popups().is_empty());
+    }
+
+    #[test]
+    fn release_outside_button_does_nothing() {
+        let mut harness = TestHarness::create_with_size(Label::new(""), Size::new(400.0, 400.0));
+        let dialog_id = harness.open_dialog(delete_dialog());
+        let buttons = button_ids(&harness, dialog_id);
+
+        harness.mouse_move_to(buttons[1]);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move_to(buttons[0]);
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(harness.popups().len(), 1);
+
+        harness.mouse_click_on(buttons[0]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DialogClosed(0), dialog_id))
+        );
+    }
+
+    #[test]
+    fn backdrop_blocks_pointer() {
+        let [button_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Button::new("Covered"), button_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 400.0));
+        let dialog_id = harness.open_dialog(delete_dialog());
+
+        // Clicking outside of the dialog neither closes it nor reaches the root widget.
+        harness.mouse_click_on(button_id);
+        assert_eq!(harness.pop_action(), None);
+        assert!(!harness.get_widget(button_id).state().is_hot);
+        assert_eq!(harness.popups()[0].id(), dialog_id);
+    }
+
+    #[test]
+    fn focus_is_trapped_in_dialog() {
+        let [button_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Button::new("Covered"), button_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 400.0));
+        let dialog_id = harness.open_dialog(delete_dialog());
+        let buttons = button_ids(&harness, dialog_id);
+
+        // The first focusable widget of the dialog gets the focus.
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(buttons[0]));
+
+        // Assistive technologies can't move the focus out of the dialog.
+        harness.process_access_event(AccessEvent {
+            target: button_id,
+            action: accesskit::Action::Focus,
+            data: None,
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(buttons[0]));
+
+        harness.process_access_event(AccessEvent {
+            target: buttons[1],
+            action: accesskit::Action::Default,
+            data: None,
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DialogClosed(1), dialog_id))
+        );
+        assert_eq!(harness.focused_widget().map(|w| w.id()), None);
+    }
+
+    #[test]
+    fn keys_press_confirm_and_cancel() {
+        // Without a focused button.
+        let dialog = delete_dialog();
+        assert_eq!(dialog.button_for_key(&Key::Named(NamedKey::Enter)), Some(1));
+        assert_eq!(
+            dialog.button_for_key(&Key::Named(NamedKey::Escape)),
+            Some(0)
+        );
+        assert_eq!(dialog.button_for_key(&Key::Named(NamedKey::Space)), None);
+
+        let dialog = Dialog::new("Done", Label::new("")).with_button("OK");
+        assert_eq!(dialog.button_for_key(&Key::Named(NamedKey::Enter)), None);
+        assert_eq!(dialog.button_for_key(&Key::Named(NamedKey::Escape)), None);
+    }
+
+    #[test]
+    fn access_tree() {
+        let mut harness = TestHarness::create(Label::new("Root"));
+        harness.open_dialog(Dialog::new("Rename", Label::new("New name?")).with_button("OK"));
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  StaticText \"Root\"
+  Dialog \"Rename\"
+    StaticText \"Rename\"
+    StaticText \"New name?\"
+    Button
+      StaticText \"OK\"
+"
+        );
+    }
+
+    #[test]
+    fn dialog_buttons() {
+        let dialog = delete_dialog();
+        assert_eq!(&*dialog.title(), "Delete file?");
+        assert_eq!(dialog.button_count(), 2);
+        assert_eq!(dialog.button_text(1).as_deref(), Some("Delete"));
+        assert_eq!(dialog.button_text(2), None);
+        assert_eq!(dialog.confirm_button(), Some(1));
+        assert_eq!(dialog.cancel_button(), Some(0));
+    }
+}
@@ -0,0 +1,1016 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A button showing the current choice, which opens a list of options in a popup.
+
+use std::time::Duration;
+
+use accesskit::{DefaultActionVerb, NodeId, Role};
+use kurbo::{Affine, BezPath};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::event::{ElementState, Ime};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Insets, Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, TimerToken, Widget, WidgetId,
+};
+
+/// The space between the edges of the dropdown and its label.
+const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+/// The width of the area holding the arrow, on the right of the label.
+const ARROW_AREA_WIDTH: f64 = 16.0;
+const ARROW_HALF_WIDTH: f64 = 4.0;
+/// The space between the edges of an option in the list and its label.
+const OPTION_INSETS: Insets = Insets::uniform_xy(8., 3.);
+/// The space above the first option and below the last one.
+const LIST_PADDING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+/// How long after a key the typed letters are forgotten, and the next key starts a
+/// new search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A button showing the selected option, which opens a list of all the options
+/// in a popup when clicked.
+///
+/// The popup is placed below the dropdown, or above it if there isn't enough room
+/// below, and scrolls if it's taller than the window. In the list, the arrow keys,
+/// Home and End move the highlighted option, Enter or Space choose it, and Escape
+/// closes the list without choosing. Typing, in the list or on the focused dropdown,
+/// jumps to the first option starting with the letters typed in quick succession;
+/// typing the same letter again jumps to the next option starting with it.
+///
+/// Emits [`Action::DropdownSelected`] with the index of the option when the user
+/// chooses a different one.
+pub struct Dropdown {
+    options: Vec<ArcStr>,
+    selected: usize,
+    label: WidgetPod<Label>,
+    /// The popup with the list of options, while it's open.
+    popup: Option<WidgetId>,
+    type_ahead: TypeAhead,
+}
+
+crate::declare_widget!(DropdownMut, Dropdown);
+
+/// The list of options of a [`Dropdown`], shown in a popup.
+pub(crate) struct DropdownList {
+    options: Vec<WidgetPod<Label>>,
+    highlighted: usize,
+    /// The width of the dropdown, so that the list is at least as wide.
+    min_width: f64,
+    row_height: f64,
+    /// How far the options are scrolled up, when they don't all fit in the window.
+    scroll_offset: f64,
+    max_scroll_offset: f64,
+    /// Whether the next layout should scroll the highlighted option into view.
+    reveal_highlighted: bool,
+    type_ahead: TypeAhead,
+}
+
+crate::declare_widget!(DropdownListMut, DropdownList);
+
+/// The letters typed in quick succession, to find an option by its first letters.
+#[derive(Default)]
+struct TypeAhead {
+    text: String,
+    timer: Option<TimerToken>,
+}
+
+// --- METHODS ---
+
+impl Dropdown {
+    /// Create a new dropdown with the given option labels.
+    ///
+    /// `selected` is clamped to the options.
+    pub fn new<T: Into<ArcStr>>(options: impl IntoIterator<Item = T>, selected: usize) -> Self {
+        let options: Vec<ArcStr> = options.into_iter().map(Into::into).collect();
+        let selected = selected.min(options.len().saturating_sub(1));
+        let text = options.get(selected).cloned().unwrap_or_else(|| "".into());
+        Dropdown {
+            options,
+            selected,
+            label: WidgetPod::new(Label::new(text)),
+            popup: None,
+            type_ahead: TypeAhead::default(),
+        }
+    }
+
+    /// Return the index of the selected option.
+    ///
+    /// If the dropdown has no options, this is 0.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Return the number of options.
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Return `true` if the dropdown has no options.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Return the label of the option at `idx`.
+    pub fn option_text(&self, idx: usize) -> Option<ArcStr> {
+        self.options.get(idx).cloned()
+    }
+
+    /// Return `true` if the list of options is open.
+    pub fn is_open(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx) {
+        if self.popup.is_some() || self.options.is_empty() {
+            return;
+        }
+        let anchor = ctx.size().to_rect() + ctx.window_origin().to_vec2();
+        let list = DropdownList::new(&self.options, self.selected, ctx.size().width);
+        let popup_id = ctx.open_popup(list, anchor);
+        trace!("Dropdown {:?} opened popup {:?}", ctx.widget_id(), popup_id);
+        self.popup = Some(popup_id);
+        self.type_ahead = TypeAhead::default();
+        ctx.set_focus(popup_id);
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+}
+
+impl DropdownList {
+    fn new(options: &[ArcStr], highlighted: usize, min_width: f64) -> Self {
+        DropdownList {
+            options: options
+                .iter()
+                .map(|text| WidgetPod::new(Label::new(text.clone())))
+                .collect(),
+            highlighted,
+            min_width,
+            row_height: 0.0,
+            scroll_offset: 0.0,
+            max_scroll_offset: 0.0,
+            reveal_highlighted: true,
+            type_ahead: TypeAhead::default(),
+        }
+    }
+
+    /// The index of the option under `pos`, in local coordinates.
+    fn option_at(&self, pos: Point) -> Option<usize> {
+        let y = pos.y + self.scroll_offset;
+        if y < LIST_PADDING || self.row_height <= 0.0 {
+            return None;
+        }
+        let idx = ((y - LIST_PADDING) / self.row_height) as usize;
+        (idx < self.options.len()).then_some(idx)
+    }
+
+    fn option_texts(&self) -> Vec<ArcStr> {
+        self.options
+            .iter()
+            .map(|option| option.widget().text())
+            .collect()
+    }
+
+    fn highlight(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx != self.highlighted {
+            self.highlighted = idx;
+            ctx.request_paint();
+            ctx.request_accessibility_update();
+        }
+    }
+
+    /// Highlight the option at `idx`, and scroll it into view.
+    fn highlight_and_reveal(&mut self, ctx: &mut EventCtx, idx: usize) {
+        self.highlight(ctx, idx);
+        self.reveal_highlighted = true;
+        ctx.request_layout();
+    }
+
+    /// Close the popup, telling the dropdown which option was chosen.
+    fn choose(&mut self, ctx: &mut EventCtx, idx: usize) {
+        trace!("DropdownList {:?} chose option {}", ctx.widget_id(), idx);
+        ctx.close_popup_with_result(ctx.widget_id(), idx);
+    }
+}
+
+impl TypeAhead {
+    /// Add `text` to the typed letters, and return the letters typed since the last pause.
+    fn push(&mut self, ctx: &mut EventCtx, text: &str) -> &str {
+        self.text.push_str(text);
+        self.timer = Some(ctx.request_timer(TYPE_AHEAD_TIMEOUT));
+        &self.text
+    }
+
+    /// Forget the typed letters if `token` is the timer of the last key.
+    fn on_timer(&mut self, token: TimerToken) {
+        if self.timer == Some(token) {
+            self.text.clear();
+            self.timer = None;
+        }
+    }
+}
+
+impl DropdownMut<'_> {
+    /// Add an option after the others.
+    pub fn add_option(&mut self, text: impl Into<ArcStr>) {
+        self.widget.options.push(text.into());
+        if self.widget.options.len() == 1 {
+            self.update_label();
+        }
+        self.close();
+    }
+
+    /// Remove the option at `idx`.
+    ///
+    /// If it was selected, the option before it is selected instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_option(&mut self, idx: usize) {
+        self.widget.options.remove(idx);
+        let selected = self.widget.selected;
+        if idx <= selected && selected > 0 {
+            self.widget.selected -= 1;
+        }
+        if idx == selected {
+            self.update_label();
+        }
+        self.close();
+    }
+
+    /// Set the label of the option at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_option_text(&mut self, idx: usize, text: impl Into<ArcStr>) {
+        self.widget.options[idx] = text.into();
+        if idx == self.widget.selected {
+            self.update_label();
+        }
+        self.close();
+    }
+
+    /// Select the option at `idx`.
+    ///
+    /// This doesn't emit [`Action::DropdownSelected`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_selected(&mut self, idx: usize) {
+        assert!(
+            idx < self.widget.options.len(),
+            "option index {idx} out of bounds"
+        );
+        if idx != self.widget.selected {
+            self.widget.selected = idx;
+            self.update_label();
+        }
+    }
+
+    /// Close the list of options, if it's open.
+    ///
+    /// This doesn't emit an action.
+    pub fn close(&mut self) {
+        if let Some(popup_id) = self.widget.popup {
+            self.ctx.close_popup(popup_id);
+        }
+    }
+
+    fn update_label(&mut self) {
+        let text = self
+            .widget
+            .options
+            .get(self.widget.selected)
+            .cloned()
+            .unwrap_or_else(|| "".into());
+        self.ctx.get_mut(&mut self.widget.label).set_text(text);
+        self.ctx.request_accessibility_update();
+    }
+}
+
+/// The option starting with the letters `typed` since the last pause, ignoring case
+/// and wrapping around.
+///
+/// The search starts at `current`, so that typing more letters keeps the current
+/// option while it matches. A single letter, or the same letter typed repeatedly,
+/// moves to the next option starting with it instead.
+fn option_for_typed(options: &[ArcStr], current: usize, typed: &str) -> Option<usize> {
+    let typed = typed.to_lowercase();
+    let first = typed.chars().next()?;
+    let (prefix, first_offset) = if typed.chars().all(|c| c == first) {
+        (first.to_string(), 1)
+    } else {
+        (typed, 0)
+    };
+    let len = options.len();
+    (first_offset..first_offset + len)
+        .map(|offset| (current + offset) % len)
+        .find(|&idx| options[idx].to_lowercase().starts_with(&prefix))
+}
+
+/// The printable text typed with `event`, if any.
+///
+/// Text committed by an input method counts, but not keys pressed with Ctrl or Super,
+/// which are shortcuts.
+fn typed_text(event: &TextEvent) -> Option<&str> {
+    let text = match event {
+        TextEvent::KeyboardKey(key_event, mods) => match &key_event.logical_key {
+            Key::Character(text)
+                if key_event.state == ElementState::Pressed
+                    && !mods.control_key()
+                    && !mods.super_key() =>
+            {
+                text.as_str()
+            }
+            _ => return None,
+        },
+        TextEvent::Ime(Ime::Commit(text)) => text.as_str(),
+        _ => return None,
+    };
+    (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Dropdown {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+        match event {
+            PointerEvent::PointerDown(_, _) => {
+                if !ctx.is_disabled() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if ctx.is_active() && ctx.is_hot() && !ctx.is_disabled() {
+                    self.open(ctx);
+                }
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+        if ctx.is_disabled() || self.options.is_empty() || self.popup.is_some() {
+            return;
+        }
+        if let TextEvent::KeyboardKey(key_event, _) = event {
+            if key_event.state == ElementState::Pressed
+                && matches!(
+                    key_event.logical_key,
+                    Key::Named(
+                        NamedKey::Enter | NamedKey::Space | NamedKey::ArrowDown | NamedKey::ArrowUp
+                    )
+                )
+            {
+                self.open(ctx);
+                ctx.set_handled();
+                return;
+            }
+        }
+        let Some(text) = typed_text(event) else {
+            return;
+        };
+        let typed = self.type_ahead.push(ctx, text);
+        if let Some(idx) = option_for_typed(&self.options, self.selected, typed) {
+            if idx != self.selected {
+                self.selected = idx;
+                ctx.get_mut(&mut self.label)
+                    .set_text(self.options[idx].clone());
+                ctx.submit_action(Action::DropdownSelected(idx));
+                ctx.request_accessibility_update();
+            }
+            ctx.set_handled();
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::Default
+            && !ctx.is_disabled()
+        {
+            self.open(ctx);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                if !self.options.is_empty() {
+                    ctx.register_for_focus();
+                }
+            }
+            LifeCycle::Timer(token) => self.type_ahead.on_timer(*token),
+            LifeCycle::PopupClosed(popup_id, result) if self.popup == Some(*popup_id) => {
+                self.popup = None;
+                // The list closes itself with the index of the option the user chose.
+                if let Some(&idx) = result.as_ref().and_then(|result| result.get::<usize>()) {
+                    if idx != self.selected && idx < self.options.len() {
+                        self.selected = idx;
+                        ctx.get_mut(&mut self.label)
+                            .set_text(self.options[idx].clone());
+                        trace!("Dropdown {:?} selected option {}", ctx.widget_id(), idx);
+                        ctx.submit_action(Action::DropdownSelected(idx));
+                    }
+                }
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+            _ => {}
+        }
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(
+            LABEL_INSETS.x_value() + ARROW_AREA_WIDTH,
+            LABEL_INSETS.y_value(),
+        );
+        let label_bc = bc.shrink(padding).loosen();
+        let label_size = self.label.layout(ctx, &label_bc);
+
+        let size = bc.constrain(Size::new(
+            (label_size.width + padding.width).max(theme::WIDE_WIDGET_WIDTH),
+            (label_size.height + padding.height).max(theme::BORDERED_WIDGET_HEIGHT),
+        ));
+        let label_y = (size.height - label_size.height) / 2.0;
+        ctx.place_child(&mut self.label, Point::new(LABEL_INSETS.x0, label_y));
+        ctx.set_baseline_offset(self.label.baseline_offset() + label_y);
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let stroke_width = theme::BUTTON_BORDER_WIDTH;
+        let rounded_rect = size
+            .to_rect()
+            .inset(-stroke_width / 2.0)
+            .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+
+        let bg_gradient = if ctx.is_disabled() {
+            [theme::DISABLED_BUTTON_LIGHT, theme::DISABLED_BUTTON_DARK]
+        } else if ctx.is_active() {
+            [theme::BUTTON_DARK, theme::BUTTON_LIGHT]
+        } else {
+            [theme::BUTTON_LIGHT, theme::BUTTON_DARK]
+        };
+        let border_color = if ctx.is_disabled() {
+            theme::BORDER_DARK
+        } else if ctx.is_focused() || self.popup.is_some() {
+            theme::PRIMARY_LIGHT
+        } else if ctx.is_hot() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &rounded_rect, border_color, stroke_width);
+        fill_lin_gradient(
+            scene,
+            &rounded_rect,
+            bg_gradient,
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        // A downward arrow, centered in its area.
+        let center_x = size.width - LABEL_INSETS.x1 - ARROW_AREA_WIDTH / 2.0;
+        let center_y = size.height / 2.0;
+        let half_height = ARROW_HALF_WIDTH / 2.0;
+        let mut arrow = BezPath::new();
+        arrow.move_to((center_x - ARROW_HALF_WIDTH, center_y - half_height));
+        arrow.line_to((center_x + ARROW_HALF_WIDTH, center_y - half_height));
+        arrow.line_to((center_x, center_y + half_height));
+        arrow.close_path();
+        let arrow_color = if ctx.is_disabled() {
+            theme::DISABLED_TEXT_COLOR
+        } else {
+            theme::TEXT_COLOR
+        };
+        fill_color(scene, &arrow, arrow_color);
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        if let Some(text) = self.options.get(self.selected) {
+            node.set_value(text.to_string());
+        }
+        node.set_expanded(self.popup.is_some());
+        node.set_default_action_verb(DefaultActionVerb::Open);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Dropdown")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        self.options.get(self.selected).map(|text| text.to_string())
+    }
+}
+
+impl Widget for DropdownList {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for option in &mut self.options {
+            option.on_pointer_event(ctx, event);
+        }
+        let window_origin = ctx.window_origin().to_vec2();
+        match event {
+            PointerEvent::PointerMove(state) => {
+                let local_pos = Point::new(state.position.x, state.position.y) - window_origin;
+                if let Some(idx) = self.option_at(local_pos) {
+                    self.highlight(ctx, idx);
+                }
+            }
+            PointerEvent::PointerDown(_, _) => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            PointerEvent::PointerUp(_, state) => {
+                if ctx.is_active() && ctx.is_hot() {
+                    let local_pos = Point::new(state.position.x, state.position.y) - window_origin;
+                    if let Some(idx) = self.option_at(local_pos) {
+                        self.choose(ctx, idx);
+                    }
+                }
+                ctx.set_active(false);
+                ctx.set_handled();
+            }
+            PointerEvent::MouseWheel(delta, _) => {
+                let scroll_offset =
+                    (self.scroll_offset + delta.y).clamp(0.0, self.max_scroll_offset);
+                if scroll_offset != self.scroll_offset {
+                    self.scroll_offset = scroll_offset;
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if self.options.is_empty() {
+            return;
+        }
+        let len = self.options.len();
+        let named_key = match event {
+            TextEvent::KeyboardKey(key_event, _) if key_event.state == ElementState::Pressed => {
+                match &key_event.logical_key {
+                    Key::Named(named_key) => Some(*named_key),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let idx = match named_key {
+            Some(NamedKey::Enter | NamedKey::Space) => {
+                self.choose(ctx, self.highlighted);
+                ctx.set_handled();
+                return;
+            }
+            Some(NamedKey::ArrowDown) => (self.highlighted + 1).min(len - 1),
+            Some(NamedKey::ArrowUp) => self.highlighted.saturating_sub(1),
+            Some(NamedKey::Home) => 0,
+            Some(NamedKey::End) => len - 1,
+            // Escape is left to the popup, which closes without a choice.
+            _ => {
+                let Some(text) = typed_text(event) else {
+                    return;
+                };
+                let options = self.option_texts();
+                let typed = self.type_ahead.push(ctx, text);
+                let Some(idx) = option_for_typed(&options, self.highlighted, typed) else {
+                    return;
+                };
+                idx
+            }
+        };
+        self.highlight_and_reveal(ctx, idx);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action != accesskit::Action::Default {
+            return;
+        }
+        // Assistive technologies activate the options themselves.
+        let target = self
+            .options
+            .iter()
+            .position(|option| option.id() == event.target);
+        if let Some(idx) = target {
+            self.choose(ctx, idx);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::Timer(token) => self.type_ahead.on_timer(*token),
+            _ => {}
+        }
+        for option in &mut self.options {
+            option.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(OPTION_INSETS.x_value(), OPTION_INSETS.y_value());
+        let label_bc = bc.shrink(padding).loosen();
+        let label_sizes: Vec<Size> = self
+            .options
+            .iter_mut()
+            .map(|option| option.layout(ctx, &label_bc))
+            .collect();
+
+        let label_width = label_sizes
+            .iter()
+            .map(|size| size.width)
+            .fold(0.0, f64::max);
+        let label_height = label_sizes
+            .iter()
+            .map(|size| size.height)
+            .fold(theme::BASIC_WIDGET_HEIGHT, f64::max);
+        self.row_height = label_height + padding.height;
+
+        let content_height = self.options.len() as f64 * self.row_height + 2.0 * LIST_PADDING;
+        let size = bc.constrain(Size::new(
+            (label_width + padding.width).max(self.min_width),
+            content_height,
+        ));
+
+        // The list is as tall as the window at most, and scrolls past that.
+        self.max_scroll_offset = (content_height - size.height).max(0.0);
+        if std::mem::take(&mut self.reveal_highlighted) {
+            let row_top = self.highlighted as f64 * self.row_height;
+            let row_bottom = row_top + self.row_height + 2.0 * LIST_PADDING;
+            if row_top < self.scroll_offset {
+                self.scroll_offset = row_top;
+            } else if row_bottom > self.scroll_offset + size.height {
+                self.scroll_offset = row_bottom - size.height;
+            }
+        }
+        self.scroll_offset = self.scroll_offset.clamp(0.0, self.max_scroll_offset);
+
+        for (idx, (option, label_size)) in self.options.iter_mut().zip(label_sizes).enumerate() {
+            let row_top = LIST_PADDING + idx as f64 * self.row_height - self.scroll_offset;
+            let label_y = row_top + (self.row_height - label_size.height) / 2.0;
+            ctx.place_child(option, Point::new(OPTION_INSETS.x0, label_y));
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let rounded_rect = size.to_rect().to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        fill_color(scene, &rounded_rect, theme::BACKGROUND_DARK);
+        stroke(scene, &rounded_rect, theme::BORDER_LIGHT, 1.0);
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &rounded_rect);
+        if self.highlighted < self.options.len() {
+            let row_top =
+                LIST_PADDING + self.highlighted as f64 * self.row_height - self.scroll_offset;
+            let row_rect = Rect::new(0.0, row_top, size.width, row_top + self.row_height);
+            fill_color(scene, &row_rect, theme::SELECTED_TEXT_BACKGROUND_COLOR);
+        }
+
+        for option in &mut self.options {
+            option.paint(ctx, scene);
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ListBox
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if let Some(option) = self.options.get(self.highlighted) {
+            ctx.current_node()
+                .set_active_descendant(NodeId::from(option.id()));
+        }
+        for option in &mut self.options {
+            option.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.options.iter().map(|option| option.as_dyn()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("DropdownList")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("highlighted: {}", self.highlighted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use winit::keyboard::ModifiersState;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::Flex;
+    use crate::Vec2;
+
+    fn dropdown_of(harness: &TestHarness, id: WidgetId) -> WidgetRef<'_, Dropdown> {
+        harness.get_widget(id).downcast::<Dropdown>().unwrap()
+    }
+
+    fn list_of(harness: &TestHarness) -> WidgetRef<'_, DropdownList> {
+        harness.popups()[0].downcast::<DropdownList>().unwrap()
+    }
+
+    #[test]
+    fn choose_option() {
+        let [dropdown_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(Dropdown::new(["Small", "Medium", "Large"], 0), dropdown_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(dropdown_id);
+        assert!(dropdown_of(&harness, dropdown_id).is_open());
+        let list_id = list_of(&harness).id();
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(list_id));
+
+        // The list opens below the dropdown, and is at least as wide.
+        let dropdown_rect = harness.get_widget(dropdown_id).state().window_layout_rect();
+        let list_rect = harness.get_widget(list_id).state().window_layout_rect();
+        assert_eq!(
+            list_rect.origin(),
+            Point::new(dropdown_rect.x0, dropdown_rect.y1)
+        );
+        assert!(list_rect.width() >= dropdown_rect.width());
+
+        let option_ids: Vec<_> = list_of(&harness)
+            .children()
+            .iter()
+            .map(|option| option.id())
+            .collect();
+        harness.mouse_click_on(option_ids[2]);
+        assert!(harness.popups().is_empty());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DropdownSelected(2), dropdown_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 2);
+        assert!(!dropdown_of(&harness, dropdown_id).is_open());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(dropdown_id));
+    }
+
+    #[test]
+    fn dismiss_without_choosing() {
+        let [dropdown_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(Dropdown::new(["Small", "Medium", "Large"], 1), dropdown_id)
+            .with_spacer(200.0);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(dropdown_id);
+        assert_eq!(list_of(&harness).widget().highlighted, 1);
+
+        harness.mouse_move((1.0, 300.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        assert!(harness.popups().is_empty());
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 1);
+    }
+
+    #[test]
+    fn opens_above_near_window_bottom() {
+        let [dropdown_id] = widget_ids();
+        let widget = Flex::column()
+            .with_spacer(260.0)
+            .with_child_id(Dropdown::new(["A", "B", "C", "D"], 0), dropdown_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300.0, 300.0));
+        harness.mouse_click_on(dropdown_id);
+
+        let dropdown_rect = harness.get_widget(dropdown_id).state().window_layout_rect();
+        let list_rect = list_of(&harness).state().window_layout_rect();
+        assert_eq!(list_rect.y1, dropdown_rect.y0);
+    }
+
+    #[test]
+    fn option_for_typed_letters() {
+        let options: Vec<ArcStr> = ["Apple", "banana", "Avocado", "Cherry", "Aardvark"]
+            .into_iter()
+            .map(ArcStr::from)
+            .collect();
+        assert_eq!(option_for_typed(&options, 0, "a"), Some(2));
+        assert_eq!(option_for_typed(&options, 2, "A"), Some(4));
+        assert_eq!(option_for_typed(&options, 4, "a"), Some(0));
+        assert_eq!(option_for_typed(&options, 0, "B"), Some(1));
+        // Longer prefixes keep the current option while it matches.
+        assert_eq!(option_for_typed(&options, 3, "ch"), Some(3));
+        assert_eq!(option_for_typed(&options, 2, "av"), Some(2));
+        assert_eq!(option_for_typed(&options, 0, "av"), Some(2));
+        // Repeating a letter cycles through the options starting with it.
+        assert_eq!(option_for_typed(&options, 2, "aa"), Some(4));
+        assert_eq!(option_for_typed(&options, 0, "z"), None);
+        assert_eq!(option_for_typed(&options, 0, ""), None);
+    }
+
+    #[test]
+    fn type_to_select() {
+        let [dropdown_id] = widget_ids();
+        let options = ["Apple", "Banana", "Carrot", "Cherry", "Coconut"];
+        let widget = Flex::column().with_child_id(Dropdown::new(options, 0), dropdown_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.keyboard_press_unused_key(Key::Named(NamedKey::Tab), ModifiersState::empty());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(dropdown_id));
+
+        // Letters typed in quick succession add up.
+        harness.keyboard_type_chars("ch");
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DropdownSelected(2), dropdown_id))
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DropdownSelected(3), dropdown_id))
+        );
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 3);
+
+        // After a pause, typing starts a new search.
+        harness.move_timers_forward(TYPE_AHEAD_TIMEOUT);
+        harness.keyboard_type_chars("c");
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DropdownSelected(4), dropdown_id))
+        );
+        harness.move_timers_forward(TYPE_AHEAD_TIMEOUT);
+        harness.keyboard_type_chars("z");
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 4);
+
+        // In the open list, typing moves the highlight without choosing.
+        harness.move_timers_forward(TYPE_AHEAD_TIMEOUT);
+        harness.mouse_click_on(dropdown_id);
+        harness.keyboard_type_chars("ba");
+        assert_eq!(list_of(&harness).widget().highlighted, 1);
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 4);
+    }
+
+    #[test]
+    fn long_list_scrolls() {
+        let [dropdown_id] = widget_ids();
+        let options: Vec<String> = (0..40).map(|idx| format!("Option {idx}")).collect();
+        let widget = Flex::column().with_child_id(Dropdown::new(options, 0), dropdown_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300.0, 300.0));
+        harness.mouse_click_on(dropdown_id);
+        let list_id = list_of(&harness).id();
+        let list_rect = harness.get_widget(list_id).state().window_layout_rect();
+        assert_eq!(list_rect.height(), 300.0);
+
+        let option_rect = |harness: &TestHarness, idx: usize| {
+            list_of(harness).children()[idx]
+                .state()
+                .window_layout_rect()
+        };
+        let is_visible = |rect: Rect| rect.y0 >= list_rect.y0 && rect.y1 <= list_rect.y1;
+        assert!(is_visible(option_rect(&harness, 0)));
+        assert!(!is_visible(option_rect(&harness, 39)));
+
+        // The option highlighted with the keyboard is scrolled into view.
+        harness.keyboard_type_chars("option 39");
+        assert_eq!(list_of(&harness).widget().highlighted, 39);
+        assert!(is_visible(option_rect(&harness, 39)));
+        assert!(!is_visible(option_rect(&harness, 0)));
+
+        // The mouse wheel scrolls the list too.
+        harness.mouse_move_to(list_id);
+        harness.mouse_wheel(Vec2::new(0.0, -10_000.0));
+        assert!(is_visible(option_rect(&harness, 0)));
+
+        // Clicking a scrolled option chooses it.
+        harness.mouse_wheel(Vec2::new(0.0, 10_000.0));
+        let option_id = list_of(&harness).children()[38].id();
+        harness.mouse_click_on(option_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DropdownSelected(38), dropdown_id))
+        );
+    }
+
+    #[test]
+    fn access_tree() {
+        let widget = Dropdown::new(["Small", "Medium"], 1);
+
+        let harness = TestHarness::create(widget);
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  ComboBox value=\"Medium\"
+    StaticText \"Medium\"
+"
+        );
+    }
+
+    #[test]
+    fn hover_keeps_access_tree_acyclic() {
+        let widget = Dropdown::new(["Small", "Medium"], 1);
+
+        let mut harness = TestHarness::create(widget);
+        let dropdown_id = harness.root_widget().id();
+        harness.mouse_click_on(dropdown_id);
+        // Hovering an option only updates the popup's node.
+        let option_id = list_of(&harness).children()[0].id();
+        harness.mouse_move_to(option_id);
+        assert_eq!(list_of(&harness).widget().highlighted, 0);
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  ComboBox value=\"Medium\"
+    StaticText \"Medium\"
+  ListBox
+    StaticText \"Small\"
+    StaticText \"Medium\"
+"
+        );
+    }
+
+    #[test]
+    fn edit_dropdown() {
+        let widget = Dropdown::new(["A", "B", "C"], 2);
+
+        let mut harness = TestHarness::create(widget);
+        let dropdown_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut dropdown = root.downcast::<Dropdown>().unwrap();
+            dropdown.remove_option(2);
+            dropdown.add_option("D");
+            dropdown.set_option_text(0, "Z");
+        });
+        assert_eq!(harness.pop_action(), None);
+
+        let dropdown = dropdown_of(&harness, dropdown_id);
+        // Removing the selected option selects the one before it.
+        assert_eq!(dropdown.selected(), 1);
+        assert_eq!(dropdown.len(), 3);
+        assert_eq!(dropdown.option_text(0).as_deref(), Some("Z"));
+        assert_eq!(dropdown.option_text(2).as_deref(), Some("D"));
+
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<Dropdown>().unwrap().set_selected(2);
+        });
+        assert_eq!(dropdown_of(&harness, dropdown_id).selected(), 2);
+        assert_eq!(
+            dropdown_of(&harness, dropdown_id)
+                .get_debug_text()
+                .as_deref(),
+            Some("D")
+        );
+    }
+}
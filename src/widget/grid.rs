@@ -0,0 +1,744 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that arranges its children in rows and columns.
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, warn, Span};
+use vello::Scene;
+
+use crate::paint_scene_helpers::UnitPoint;
+use crate::widget::{WidgetMut, WidgetRef};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+/// A container that lays out its children in a grid of rows and columns.
+///
+/// Each row and column is a [`GridTrack`], and each child occupies one or more
+/// contiguous cells, as described by its [`GridParams`].
+pub struct Grid {
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    column_gap: f64,
+    row_gap: f64,
+    children: Vec<Child>,
+}
+
+crate::declare_widget!(GridMut, Grid);
+
+/// The size of a row or column in a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    /// A track with a fixed size.
+    Fixed(f64),
+    /// A track that takes a share of the space left over by the other tracks,
+    /// proportional to its factor.
+    ///
+    /// If the grid is unbounded along this axis, the track is sized like [`Auto`](Self::Auto).
+    Fraction(f64),
+    /// A track that is as large as its largest child.
+    ///
+    /// The children in auto-sized tracks are laid out at their natural size along
+    /// that axis.
+    Auto,
+}
+
+/// The placement of a child in a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridParams {
+    column: usize,
+    row: usize,
+    column_span: usize,
+    row_span: usize,
+    alignment: UnitPoint,
+}
+
+struct Child {
+    widget: WidgetPod<Box<dyn Widget>>,
+    params: GridParams,
+}
+
+// --- METHODS ---
+
+impl Grid {
+    /// Create a new grid with the given column and row tracks.
+    pub fn new(
+        columns: impl IntoIterator<Item = GridTrack>,
+        rows: impl IntoIterator<Item = GridTrack>,
+    ) -> Self {
+        Grid {
+            columns: columns.into_iter().collect(),
+            rows: rows.into_iter().collect(),
+            column_gap: 0.0,
+            row_gap: 0.0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the space between both rows and columns.
+    pub fn with_gap(mut self, gap: f64) -> Self {
+        self.column_gap = gap;
+        self.row_gap = gap;
+        self
+    }
+
+    /// Builder-style method to set the space between columns.
+    pub fn with_column_gap(mut self, gap: f64) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    /// Builder-style method to set the space between rows.
+    pub fn with_row_gap(mut self, gap: f64) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    /// Builder-style method to add a child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cells in `params` are outside the grid.
+    pub fn with_child(mut self, child: impl Widget, params: GridParams) -> Self {
+        self.check_params(&params);
+        self.children.push(Child {
+            widget: WidgetPod::new(Box::new(child)),
+            params,
+        });
+        self
+    }
+
+    /// Builder-style method to add a child with a given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cells in `params` are outside the grid.
+    pub fn with_child_id(mut self, child: impl Widget, id: WidgetId, params: GridParams) -> Self {
+        self.check_params(&params);
+        self.children.push(Child {
+            widget: WidgetPod::new_with_id(Box::new(child), id),
+            params,
+        });
+        self
+    }
+
+    /// Return the number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Return `true` if the grid has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Return the placement of the child at `idx`.
+    pub fn child_params(&self, idx: usize) -> Option<GridParams> {
+        self.children.get(idx).map(|child| child.params)
+    }
+
+    fn check_params(&self, params: &GridParams) {
+        assert!(
+            params.column + params.column_span <= self.columns.len()
+                && params.row + params.row_span <= self.rows.len(),
+            "grid child at {params:?} doesn't fit in {} columns and {} rows",
+            self.columns.len(),
+            self.rows.len()
+        );
+    }
+}
+
+impl GridMut<'_> {
+    /// Set the space between both rows and columns.
+    pub fn set_gap(&mut self, gap: f64) {
+        self.widget.column_gap = gap;
+        self.widget.row_gap = gap;
+        self.ctx.request_layout();
+    }
+
+    /// Add a child.
+    ///
+    /// See also [`with_child`](Grid::with_child).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cells in `params` are outside the grid.
+    pub fn add_child(&mut self, child: impl Widget, params: GridParams) {
+        self.add_child_pod(WidgetPod::new(Box::new(child)), params);
+    }
+
+    /// Add a child with a given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cells in `params` are outside the grid.
+    pub fn add_child_id(&mut self, child: impl Widget, id: WidgetId, params: GridParams) {
+        self.add_child_pod(WidgetPod::new_with_id(Box::new(child), id), params);
+    }
+
+    fn add_child_pod(&mut self, widget: WidgetPod<Box<dyn Widget>>, params: GridParams) {
+        self.widget.check_params(&params);
+        self.widget.children.push(Child { widget, params });
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Move the child at `idx` to other cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds, or if the cells in `params` are outside the grid.
+    pub fn move_child(&mut self, idx: usize, params: GridParams) {
+        self.widget.check_params(&params);
+        self.widget.children[idx].params = params;
+        self.ctx.request_layout();
+    }
+
+    /// Remove the child at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_child(&mut self, idx: usize) {
+        self.widget.children.remove(idx);
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the child at `idx`.
+    pub fn child_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let child = &mut self.widget.children.get_mut(idx)?.widget;
+        Some(self.ctx.get_mut(child))
+    }
+}
+
+impl GridParams {
+    /// Place a child in a single cell.
+    ///
+    /// The child is aligned to the top left of its cell.
+    pub fn new(column: usize, row: usize) -> Self {
+        GridParams {
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+            alignment: UnitPoint::TOP_LEFT,
+        }
+    }
+
+    /// Builder-style method to make the child span several columns and rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either span is zero.
+    pub fn with_span(mut self, column_span: usize, row_span: usize) -> Self {
+        assert!(
+            column_span > 0 && row_span > 0,
+            "grid spans must be at least 1"
+        );
+        self.column_span = column_span;
+        self.row_span = row_span;
+        self
+    }
+
+    /// Builder-style method to set where the child is placed if it's smaller than its cells.
+    pub fn with_alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Return the first column of the child.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Return the first row of the child.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+}
+
+impl GridTrack {
+    /// Return `true` if the track is sized to fit its children.
+    fn is_auto(self, available: f64) -> bool {
+        match self {
+            GridTrack::Fixed(_) => false,
+            GridTrack::Fraction(_) => !available.is_finite(),
+            GridTrack::Auto => true,
+        }
+    }
+}
+
+/// Compute the sizes of a list of tracks.
+///
+/// `items` are the `(first track, span, size)` of the children that overlap auto-sized tracks.
+fn resolve_tracks(
+    tracks: &[GridTrack],
+    gap: f64,
+    available: f64,
+    items: &[(usize, usize, f64)],
+) -> Vec<f64> {
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+
+    // Children in a single track set its size first, then children spanning
+    // several tracks grow the auto-sized ones if they still don't fit.
+    for &(start, _, size) in items.iter().filter(|item| item.1 == 1) {
+        if tracks[start].is_auto(available) {
+            sizes[start] = sizes[start].max(size);
+        }
+    }
+    for &(start, span, size) in items.iter().filter(|item| item.1 > 1) {
+        let range = start..start + span;
+        let has_fraction = tracks[range.clone()]
+            .iter()
+            .any(|track| matches!(track, GridTrack::Fraction(_)) && available.is_finite());
+        let auto_count = tracks[range.clone()]
+            .iter()
+            .filter(|track| track.is_auto(available))
+            .count();
+        let spanned = sizes[range.clone()].iter().sum::<f64>() + gap * (span - 1) as f64;
+        if has_fraction || auto_count == 0 || size <= spanned {
+            continue;
+        }
+        let extra = (size - spanned) / auto_count as f64;
+        for idx in range.filter(|&idx| tracks[idx].is_auto(available)) {
+            sizes[idx] += extra;
+        }
+    }
+
+    if available.is_finite() {
+        let fraction_sum: f64 = tracks
+            .iter()
+            .map(|track| match track {
+                GridTrack::Fraction(fraction) => fraction.max(0.0),
+                _ => 0.0,
+            })
+            .sum();
+        if fraction_sum > 0.0 {
+            let used = sizes.iter().sum::<f64>() + gap * tracks.len().saturating_sub(1) as f64;
+            let px_per_fraction = (available - used).max(0.0) / fraction_sum;
+            for (size, track) in sizes.iter_mut().zip(tracks) {
+                if let GridTrack::Fraction(fraction) = track {
+                    *size = fraction.max(0.0) * px_per_fraction;
+                }
+            }
+        }
+    }
+
+    sizes
+}
+
+/// Return the offset of each track, and the total length.
+fn track_offsets(sizes: &[f64], gap: f64) -> (Vec<f64>, f64) {
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut offset = 0.0;
+    for (idx, size) in sizes.iter().enumerate() {
+        if idx > 0 {
+            offset += gap;
+        }
+        offsets.push(offset);
+        offset += size;
+    }
+    (offsets, offset)
+}
+
+/// The length of the tracks from `start` to `start + span`, including the gaps between them.
+fn span_length(sizes: &[f64], gap: f64, start: usize, span: usize) -> f64 {
+    sizes[start..start + span].iter().sum::<f64>() + gap * (span - 1) as f64
+}
+
+/// Lay out `child` with a maximum size of `max`, unless its last layout in this pass,
+/// stored in `last_layout`, had the same constraints.
+fn layout_child(
+    ctx: &mut LayoutCtx,
+    child: &mut Child,
+    last_layout: &mut Option<(Size, Size)>,
+    max: Size,
+) -> Size {
+    if let Some((last_max, size)) = *last_layout {
+        if last_max == max {
+            return size;
+        }
+    }
+    let size = child
+        .widget
+        .layout(ctx, &BoxConstraints::new(Size::ZERO, max));
+    *last_layout = Some((max, size));
+    size
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Grid {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for child in &mut self.children {
+            child.widget.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for child in &mut self.children {
+            child.widget.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for child in &mut self.children {
+            child.widget.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let available = bc.max();
+        // The maximum size and the resulting size of each child's last layout, so that
+        // children aren't laid out twice with the same constraints.
+        let mut last_layouts = vec![None; self.children.len()];
+        // Whether each child keeps its natural width and height, because it's in
+        // auto-sized tracks, which fit it.
+        let mut natural_widths = vec![false; self.children.len()];
+        let mut natural_heights = vec![false; self.children.len()];
+
+        // Measure the children in auto-sized columns at their natural size.
+        let mut column_items = Vec::new();
+        for (idx, child) in self.children.iter_mut().enumerate() {
+            let GridParams {
+                column,
+                column_span,
+                ..
+            } = child.params;
+            let is_auto = self.columns[column..column + column_span]
+                .iter()
+                .any(|track| track.is_auto(available.width));
+            if is_auto {
+                let max = Size::new(f64::INFINITY, f64::INFINITY);
+                let size = layout_child(ctx, child, &mut last_layouts[idx], max);
+                if size.width.is_finite() {
+                    column_items.push((column, column_span, size.width));
+                    natural_widths[idx] = true;
+                } else {
+                    warn!("A Grid child in an auto-sized column has an infinite width.");
+                }
+            }
+        }
+        let column_sizes = resolve_tracks(
+            &self.columns,
+            self.column_gap,
+            available.width,
+            &column_items,
+        );
+        let max_width = |idx: usize, params: GridParams| {
+            if natural_widths[idx] {
+                f64::INFINITY
+            } else {
+                span_length(
+                    &column_sizes,
+                    self.column_gap,
+                    params.column,
+                    params.column_span,
+                )
+            }
+        };
+
+        // Measure the children in auto-sized rows, now that we know how wide they are.
+        let mut row_items = Vec::new();
+        for (idx, child) in self.children.iter_mut().enumerate() {
+            let params = child.params;
+            let is_auto = self.rows[params.row..params.row + params.row_span]
+                .iter()
+                .any(|track| track.is_auto(available.height));
+            if is_auto {
+                let max = Size::new(max_width(idx, params), f64::INFINITY);
+                let size = layout_child(ctx, child, &mut last_layouts[idx], max);
+                if size.height.is_finite() {
+                    row_items.push((params.row, params.row_span, size.height));
+                    natural_heights[idx] = true;
+                } else {
+                    warn!("A Grid child in an auto-sized row has an infinite height.");
+                }
+            }
+        }
+        let row_sizes = resolve_tracks(&self.rows, self.row_gap, available.height, &row_items);
+
+        let (column_offsets, width) = track_offsets(&column_sizes, self.column_gap);
+        let (row_offsets, height) = track_offsets(&row_sizes, self.row_gap);
+
+        // Children measured above usually get the same constraints again, and aren't
+        // laid out again.
+        for (idx, child) in self.children.iter_mut().enumerate() {
+            let params = child.params;
+            let cell_size = Size::new(
+                span_length(
+                    &column_sizes,
+                    self.column_gap,
+                    params.column,
+                    params.column_span,
+                ),
+                span_length(&row_sizes, self.row_gap, params.row, params.row_span),
+            );
+            let max = Size::new(
+                max_width(idx, params),
+                if natural_heights[idx] {
+                    f64::INFINITY
+                } else {
+                    cell_size.height
+                },
+            );
+            let child_size = layout_child(ctx, child, &mut last_layouts[idx], max);
+            if child_size.width > cell_size.width || child_size.height > cell_size.height {
+                warn!("A Grid child is larger than its cells.");
+            }
+
+            let free_space = (cell_size - child_size).to_rect();
+            let cell_origin = Point::new(column_offsets[params.column], row_offsets[params.row]);
+            let origin = cell_origin + params.alignment.resolve(free_space).to_vec2();
+            ctx.place_child(&mut child.widget, origin);
+        }
+
+        let size = bc.constrain(Size::new(width, height));
+        let paint_rect = self
+            .children
+            .iter()
+            .map(|child| child.widget.paint_rect())
+            .fold(Rect::ZERO.with_size(size), |acc, rect| acc.union(rect));
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for child in &mut self.children {
+            child.widget.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.children
+            .iter()
+            .map(|child| child.widget.as_dyn())
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::SizedBox;
+
+    fn sized_box(width: f64, height: f64) -> SizedBox {
+        SizedBox::empty().width(width).height(height)
+    }
+
+    fn child_rect(harness: &TestHarness, id: WidgetId) -> Rect {
+        harness.get_widget(id).state().layout_rect()
+    }
+
+    #[test]
+    fn track_sizes() {
+        let [fixed_id, auto_id, fraction_id, spanning_id] = widget_ids();
+        let widget = Grid::new(
+            [
+                GridTrack::Fixed(50.0),
+                GridTrack::Auto,
+                GridTrack::Fraction(1.0),
+            ],
+            [GridTrack::Auto, GridTrack::Fraction(1.0)],
+        )
+        .with_gap(10.0)
+        .with_child_id(sized_box(20.0, 20.0), fixed_id, GridParams::new(0, 0))
+        .with_child_id(sized_box(80.0, 30.0), auto_id, GridParams::new(1, 0))
+        .with_child_id(
+            SizedBox::empty().expand_width().height(10.0),
+            fraction_id,
+            GridParams::new(2, 0),
+        )
+        .with_child_id(
+            SizedBox::empty().expand(),
+            spanning_id,
+            GridParams::new(0, 1).with_span(3, 1),
+        );
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 200.0));
+
+        assert_eq!(
+            child_rect(&harness, fixed_id),
+            Rect::new(0.0, 0.0, 20.0, 20.0)
+        );
+        assert_eq!(
+            child_rect(&harness, auto_id),
+            Rect::new(60.0, 0.0, 140.0, 30.0)
+        );
+        // The fraction column takes the rest of the width.
+        assert_eq!(
+            child_rect(&harness, fraction_id),
+            Rect::new(150.0, 0.0, 400.0, 10.0)
+        );
+        assert_eq!(
+            child_rect(&harness, spanning_id),
+            Rect::new(0.0, 40.0, 400.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn spanning_child_grows_auto_tracks() {
+        let [id_1, id_2, id_3] = widget_ids();
+        let widget = Grid::new([GridTrack::Auto, GridTrack::Auto], [GridTrack::Auto; 2])
+            .with_column_gap(10.0)
+            .with_child_id(
+                sized_box(20.0, 20.0),
+                id_1,
+                GridParams::new(0, 0).with_alignment(UnitPoint::TOP_RIGHT),
+            )
+            .with_child_id(sized_box(0.0, 20.0), id_3, GridParams::new(1, 0))
+            .with_child_id(
+                sized_box(130.0, 20.0),
+                id_2,
+                GridParams::new(0, 1).with_span(2, 1),
+            );
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 200.0));
+
+        // The 100px missing are shared by both columns: the first one is 70px wide,
+        // and the second one is 50px wide.
+        assert_eq!(child_rect(&harness, id_1), Rect::new(50.0, 0.0, 70.0, 20.0));
+        assert_eq!(child_rect(&harness, id_3), Rect::new(80.0, 0.0, 80.0, 20.0));
+        assert_eq!(
+            child_rect(&harness, id_2),
+            Rect::new(0.0, 20.0, 130.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn children_are_laid_out_once() {
+        let auto_rec = Recording::default();
+        let fixed_rec = Recording::default();
+        let widget = Grid::new(
+            [GridTrack::Auto, GridTrack::Fixed(50.0)],
+            [GridTrack::Auto, GridTrack::Auto],
+        )
+        .with_child(
+            sized_box(30.0, 20.0).record(&auto_rec),
+            GridParams::new(0, 0),
+        )
+        .with_child(
+            sized_box(30.0, 20.0).record(&fixed_rec),
+            GridParams::new(1, 1),
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 200.0));
+        auto_rec.clear();
+        fixed_rec.clear();
+
+        harness.edit_root_widget(|mut root| {
+            let mut grid = root.downcast::<Grid>().unwrap();
+            grid.set_gap(10.0);
+        });
+        let layouts = |recording: &Recording| {
+            recording
+                .drain()
+                .iter()
+                .filter(|record| matches!(record, Record::Layout(_)))
+                .count()
+        };
+        assert_eq!(layouts(&auto_rec), 1);
+        assert_eq!(layouts(&fixed_rec), 1);
+    }
+
+    #[test]
+    fn cell_alignment() {
+        let [id_1, id_2] = widget_ids();
+        let widget = Grid::new([GridTrack::Fixed(100.0)], [GridTrack::Fixed(50.0); 2])
+            .with_child_id(
+                sized_box(20.0, 10.0),
+                id_1,
+                GridParams::new(0, 0).with_alignment(UnitPoint::CENTER),
+            )
+            .with_child_id(
+                sized_box(20.0, 10.0),
+                id_2,
+                GridParams::new(0, 1).with_alignment(UnitPoint::BOTTOM_RIGHT),
+            );
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 200.0));
+
+        assert_eq!(
+            child_rect(&harness, id_1),
+            Rect::new(40.0, 20.0, 60.0, 30.0)
+        );
+        assert_eq!(
+            child_rect(&harness, id_2),
+            Rect::new(80.0, 90.0, 100.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn edit_grid() {
+        let [id_1, id_2] = widget_ids();
+        let widget = Grid::new([GridTrack::Fixed(50.0); 2], [GridTrack::Fixed(50.0); 2])
+            .with_child_id(sized_box(10.0, 10.0), id_1, GridParams::new(0, 0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 200.0));
+
+        harness.edit_root_widget(|mut root| {
+            let mut grid = root.downcast::<Grid>().unwrap();
+            grid.add_child_id(sized_box(10.0, 10.0), id_2, GridParams::new(1, 0));
+            grid.move_child(0, GridParams::new(1, 1));
+        });
+        assert_eq!(
+            child_rect(&harness, id_1),
+            Rect::new(50.0, 50.0, 60.0, 60.0)
+        );
+        assert_eq!(child_rect(&harness, id_2), Rect::new(50.0, 0.0, 60.0, 10.0));
+
+        harness.edit_root_widget(|mut root| {
+            let mut grid = root.downcast::<Grid>().unwrap();
+            grid.remove_child(0);
+        });
+        assert!(harness.try_get_widget(id_1).is_none());
+        let grid = harness.root_widget();
+        let grid = grid.downcast::<Grid>().unwrap();
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.child_params(0), Some(GridParams::new(1, 0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn child_outside_grid() {
+        let _ = Grid::new([GridTrack::Auto], [GridTrack::Auto])
+            .with_child(sized_box(10.0, 10.0), GridParams::new(0, 1));
+    }
+}
@@ -5,6 +5,7 @@
 //! An Image widget.
 //! Please consider using SVG and the SVG widget as it scales much better.
 
+use accesskit::Role;
 use kurbo::Affine;
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
@@ -13,8 +14,8 @@ use vello::Scene;
 
 use crate::widget::{FillStrat, WidgetRef};
 use crate::{
-    BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, Size,
-    StatusChange, TextEvent, Widget,
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    PointerEvent, Size, StatusChange, TextEvent, Widget,
 };
 
 // TODO - Resolve name collision between masonry::Image and peniko::Image
@@ -71,6 +72,8 @@ impl Widget for Image {
 
     fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
 
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
     fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
@@ -104,6 +107,12 @@ impl Widget for Image {
         scene.pop_layer();
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         SmallVec::new()
     }
@@ -4,6 +4,7 @@
 
 //! A label widget.
 
+use accesskit::Role;
 use kurbo::Affine;
 use parley::layout::Alignment;
 use parley::style::{FontFamily, FontStack, GenericFamily, StyleProperty};
@@ -15,8 +16,8 @@ use vello::Scene;
 
 use crate::widget::WidgetRef;
 use crate::{
-    ArcStr, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Size, StatusChange, TextEvent, Widget,
+    AccessCtx, AccessEvent, ArcStr, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget,
 };
 
 // added padding between the edges of the widget and the text.
@@ -189,6 +190,8 @@ impl Widget for Label {
 
     fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
 
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
@@ -252,6 +255,14 @@ impl Widget for Label {
         }
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::StaticText
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_name(self.current_text.to_string());
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         SmallVec::new()
     }
@@ -0,0 +1,1875 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Menus, and the widgets which show them in popups.
+
+use accesskit::{Checked, DefaultActionVerb, NodeId, Role};
+use kurbo::{Affine, BezPath, Line};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::event::{ElementState, MouseButton};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, PopupResult, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+const ROW_PADDING_HORIZONTAL: f64 = theme::WIDGET_PADDING_HORIZONTAL;
+const ROW_PADDING_VERTICAL: f64 = 3.0;
+/// The width of the column holding check marks, on the left of item labels.
+const CHECK_COLUMN_WIDTH: f64 = 16.0;
+/// The width of the column holding submenu arrows, on the right of item labels.
+const ARROW_COLUMN_WIDTH: f64 = 12.0;
+/// The minimum space between the label of an item and its accelerator hint.
+const ACCELERATOR_SPACING: f64 = 24.0;
+const SEPARATOR_HEIGHT: f64 = 9.0;
+/// The space above the first row of a menu and below the last one.
+const LIST_PADDING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+const MENU_MIN_WIDTH: f64 = 120.0;
+const TITLE_PADDING_HORIZONTAL: f64 = theme::WIDGET_PADDING_HORIZONTAL;
+const TITLE_PADDING_VERTICAL: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+
+/// The translations of the standard menus, by locale. The first one is the fallback.
+const BUILTIN_TRANSLATIONS: &[(&str, &str)] = &[
+    (
+        "en-US",
+        include_str!("../../resources/i18n/en-US/builtin.ftl"),
+    ),
+    (
+        "de-DE",
+        include_str!("../../resources/i18n/de-DE/builtin.ftl"),
+    ),
+    (
+        "fr-CA",
+        include_str!("../../resources/i18n/fr-CA/builtin.ftl"),
+    ),
+    (
+        "it-IT",
+        include_str!("../../resources/i18n/it-IT/builtin.ftl"),
+    ),
+];
+
+/// The description of a menu: a title, and a list of items, separators and submenus.
+///
+/// Menus are shown by a [`MenuBar`] or a [`ContextMenu`], which emit
+/// [`Action::MenuItemActivated`] with the id of the item the user activates.
+/// A menu taller than the window scrolls.
+#[derive(Clone, Debug)]
+pub struct Menu {
+    title: ArcStr,
+    entries: Vec<MenuEntry>,
+}
+
+#[derive(Clone, Debug)]
+enum MenuEntry {
+    Item(MenuItem),
+    Separator,
+    Submenu(Menu),
+}
+
+/// An item of a [`Menu`].
+///
+/// The item's id is reported by [`Action::MenuItemActivated`] when the user activates it.
+#[derive(Clone, Debug)]
+pub struct MenuItem {
+    id: ArcStr,
+    text: ArcStr,
+    accelerator: Option<ArcStr>,
+    /// `None` if the item isn't checkable.
+    checked: Option<bool>,
+    enabled: bool,
+}
+
+/// A widget which opens a menu when its child is right-clicked.
+///
+/// The menu opens at the pointer. It can also be opened with the context menu key,
+/// or Shift+F10, while the child has focus.
+///
+/// Emits [`Action::MenuItemActivated`] when the user activates an item. Checkable
+/// items are toggled before the action is emitted.
+pub struct ContextMenu {
+    child: WidgetPod<Box<dyn Widget>>,
+    menu: Menu,
+    /// The popup with the menu, while it's open.
+    popup: Option<WidgetId>,
+}
+
+crate::declare_widget!(ContextMenuMut, ContextMenu);
+
+/// A row of menu titles, each of which opens its menu when clicked.
+///
+/// The bar is a single stop in the focus chain; when it's focused, the left and right
+/// arrow keys move between titles, and Enter, Space or the down arrow open the menu.
+///
+/// Emits [`Action::MenuItemActivated`] when the user activates an item. Checkable
+/// items are toggled before the action is emitted.
+pub struct MenuBar {
+    menus: Vec<Menu>,
+    titles: Vec<WidgetPod<MenuTitle>>,
+    /// The title which the keyboard acts on.
+    focused_title: usize,
+    /// The index of the open menu, and its popup.
+    open_menu: Option<(usize, WidgetId)>,
+}
+
+crate::declare_widget!(MenuBarMut, MenuBar);
+
+/// The title of a menu, in a [`MenuBar`].
+pub(crate) struct MenuTitle {
+    label: WidgetPod<Label>,
+    open: bool,
+}
+
+crate::declare_widget!(MenuTitleMut, MenuTitle);
+
+/// The rows of a menu, shown in a popup.
+pub(crate) struct MenuList {
+    entries: Vec<MenuEntry>,
+    rows: Vec<WidgetPod<MenuRow>>,
+    highlighted: Option<usize>,
+    /// The row whose submenu is open, and the submenu's popup.
+    open_submenu: Option<(usize, WidgetId)>,
+    /// The popup of the first menu of the chain, which is closed once an item is activated.
+    ///
+    /// `None` if this is the first menu.
+    root_popup: Option<WidgetId>,
+    min_width: f64,
+    /// How far the rows are scrolled up, when they don't all fit in the window.
+    scroll_offset: f64,
+    max_scroll_offset: f64,
+    /// Whether the next layout should scroll the highlighted row into view.
+    reveal_highlighted: bool,
+}
+
+crate::declare_widget!(MenuListMut, MenuList);
+
+/// An item, separator or submenu of a [`MenuList`].
+pub(crate) struct MenuRow {
+    /// `None` for separators.
+    label: Option<WidgetPod<Label>>,
+    accelerator: Option<WidgetPod<Label>>,
+    checked: Option<bool>,
+    has_submenu: bool,
+    enabled: bool,
+    highlighted: bool,
+    expanded: bool,
+}
+
+crate::declare_widget!(MenuRowMut, MenuRow);
+
+/// What a key press does while a [`MenuBar`] is focused.
+#[derive(Debug, PartialEq)]
+enum MenuBarKeyAction {
+    FocusTitle(usize),
+    Open(usize),
+}
+
+/// What a key press does in a [`MenuList`].
+#[derive(Debug, PartialEq)]
+enum MenuListKeyAction {
+    Highlight(Option<usize>),
+    Activate(usize),
+    OpenSubmenu(usize),
+    /// Close the list, which is a submenu.
+    Close,
+}
+
+// --- METHODS ---
+
+impl Menu {
+    /// Create a new empty menu with the given title.
+    ///
+    /// The title is shown by the [`MenuBar`], and by the item opening the menu if
+    /// it's a submenu.
+    pub fn new(title: impl Into<ArcStr>) -> Self {
+        Menu {
+            title: title.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Create the standard File menu, in the given locale.
+    ///
+    /// The ids of the items are the keys of their text in `resources/i18n/*/builtin.ftl`,
+    /// eg `common-menu-file-open`. The locale is matched against the ones of these
+    /// files, first exactly, then by language, eg `fr` picks `fr-CA`; other locales
+    /// get English text. Apps with their own translations can replace the text with
+    /// [`set_title`](Self::set_title) and [`set_item_text`](Self::set_item_text).
+    pub fn standard_file(locale: &str) -> Self {
+        let text = |key| builtin_text(locale, key);
+        let item = |key| MenuItem::new(key, text(key));
+        let menu = Menu::new(text("common-menu-file-menu"))
+            .with_item(item("common-menu-file-new"))
+            .with_item(item("common-menu-file-open"))
+            .with_item(item("common-menu-file-close"))
+            .with_separator()
+            .with_item(item("common-menu-file-save"))
+            .with_item(item("common-menu-file-save-as"))
+            .with_separator()
+            .with_item(item("common-menu-file-page-setup"))
+            .with_item(item("common-menu-file-print"));
+        // On macOS, quitting is in the application menu.
+        if cfg!(target_os = "macos") {
+            menu
+        } else {
+            menu.with_separator().with_item(item("win-menu-file-exit"))
+        }
+    }
+
+    /// Create the standard Edit menu, in the given locale.
+    ///
+    /// See [`standard_file`](Self::standard_file) for the item ids and locales.
+    pub fn standard_edit(locale: &str) -> Self {
+        let text = |key| builtin_text(locale, key);
+        let item = |key| MenuItem::new(key, text(key));
+        Menu::new(text("common-menu-edit-menu"))
+            .with_item(item("common-menu-undo"))
+            .with_item(item("common-menu-redo"))
+            .with_separator()
+            .with_item(item("common-menu-cut"))
+            .with_item(item("common-menu-copy"))
+            .with_item(item("common-menu-paste"))
+    }
+
+    /// Builder-style method to add an item after the others.
+    pub fn with_item(mut self, item: MenuItem) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+        self
+    }
+
+    /// Builder-style method to add a separator after the other entries.
+    pub fn with_separator(mut self) -> Self {
+        self.entries.push(MenuEntry::Separator);
+        self
+    }
+
+    /// Builder-style method to add a submenu after the other entries.
+    pub fn with_submenu(mut self, submenu: Menu) -> Self {
+        self.entries.push(MenuEntry::Submenu(submenu));
+        self
+    }
+
+    /// Return the title of the menu.
+    pub fn title(&self) -> ArcStr {
+        self.title.clone()
+    }
+
+    /// Set the title of the menu.
+    pub fn set_title(&mut self, title: impl Into<ArcStr>) {
+        self.title = title.into();
+    }
+
+    /// Return the number of entries, counting separators and submenus.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if the menu has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the item with the given id, in this menu or its submenus.
+    pub fn find_item(&self, id: &str) -> Option<&MenuItem> {
+        self.entries.iter().find_map(|entry| match entry {
+            MenuEntry::Item(item) if &*item.id == id => Some(item),
+            MenuEntry::Submenu(submenu) => submenu.find_item(id),
+            _ => None,
+        })
+    }
+
+    /// Set whether the item with the given id is checked, making it checkable.
+    ///
+    /// Return `false` if there's no item with this id.
+    pub fn set_item_checked(&mut self, id: &str, checked: bool) -> bool {
+        match self.find_item_mut(id) {
+            Some(item) => {
+                item.checked = Some(checked);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the text of the item with the given id.
+    ///
+    /// Return `false` if there's no item with this id.
+    pub fn set_item_text(&mut self, id: &str, text: impl Into<ArcStr>) -> bool {
+        match self.find_item_mut(id) {
+            Some(item) => {
+                item.text = text.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn find_item_mut(&mut self, id: &str) -> Option<&mut MenuItem> {
+        self.entries.iter_mut().find_map(|entry| match entry {
+            MenuEntry::Item(item) if &*item.id == id => Some(item),
+            MenuEntry::Submenu(submenu) => submenu.find_item_mut(id),
+            _ => None,
+        })
+    }
+
+    /// Toggle the item with the given id, if it's checkable.
+    fn toggle_item(&mut self, id: &str) {
+        if let Some(item) = self.find_item_mut(id) {
+            item.checked = item.checked.map(|checked| !checked);
+        }
+    }
+}
+
+impl MenuItem {
+    /// Create a new item with the given id and label.
+    pub fn new(id: impl Into<ArcStr>, text: impl Into<ArcStr>) -> Self {
+        MenuItem {
+            id: id.into(),
+            text: text.into(),
+            accelerator: None,
+            checked: None,
+            enabled: true,
+        }
+    }
+
+    /// Builder-style method to show a keyboard shortcut next to the label, eg "Ctrl+S".
+    ///
+    /// This is only a hint: the shortcut itself is up to the app.
+    pub fn with_accelerator(mut self, accelerator: impl Into<ArcStr>) -> Self {
+        self.accelerator = Some(accelerator.into());
+        self
+    }
+
+    /// Builder-style method to make the item checkable, and set whether it's checked.
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    /// Builder-style method to set whether the item can be activated.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Return the id of the item.
+    pub fn id(&self) -> ArcStr {
+        self.id.clone()
+    }
+
+    /// Return the label of the item.
+    pub fn text(&self) -> ArcStr {
+        self.text.clone()
+    }
+
+    /// Return the keyboard shortcut shown next to the label, if any.
+    pub fn accelerator(&self) -> Option<ArcStr> {
+        self.accelerator.clone()
+    }
+
+    /// Return whether the item is checked, or `None` if it isn't checkable.
+    pub fn checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    /// Return `true` if the item can be activated.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+impl ContextMenu {
+    /// Create a new widget which opens `menu` when `child` is right-clicked.
+    pub fn new(child: impl Widget, menu: Menu) -> Self {
+        ContextMenu {
+            child: WidgetPod::new(child).boxed(),
+            menu,
+            popup: None,
+        }
+    }
+
+    /// Return the menu.
+    pub fn menu(&self) -> &Menu {
+        &self.menu
+    }
+
+    /// Return `true` if the menu is open.
+    pub fn is_open(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    /// Open the menu next to `anchor`, in window coordinates.
+    fn open(&mut self, ctx: &mut EventCtx, anchor: Rect, highlight_first: bool) {
+        if self.popup.is_some() || self.menu.is_empty() {
+            return;
+        }
+        let list = MenuList::new(&self.menu, 0.0, None, highlight_first);
+        let popup_id = ctx.open_popup(list, anchor);
+        trace!(
+            "ContextMenu {:?} opened popup {:?}",
+            ctx.widget_id(),
+            popup_id
+        );
+        self.popup = Some(popup_id);
+        ctx.set_focus(popup_id);
+    }
+}
+
+impl ContextMenuMut<'_> {
+    /// Replace the menu.
+    ///
+    /// If the menu is open, it's closed without emitting an action.
+    pub fn set_menu(&mut self, menu: Menu) {
+        self.widget.menu = menu;
+        if let Some(popup_id) = self.widget.popup {
+            self.ctx.close_popup(popup_id);
+        }
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl MenuBar {
+    /// Create a new menu bar without menus.
+    pub fn new() -> Self {
+        MenuBar {
+            menus: Vec::new(),
+            titles: Vec::new(),
+            focused_title: 0,
+            open_menu: None,
+        }
+    }
+
+    /// Builder-style method to add a menu after the others.
+    pub fn with_menu(mut self, menu: Menu) -> Self {
+        self.titles
+            .push(WidgetPod::new(MenuTitle::new(menu.title())));
+        self.menus.push(menu);
+        self
+    }
+
+    /// Return the number of menus.
+    pub fn len(&self) -> usize {
+        self.menus.len()
+    }
+
+    /// Return `true` if the bar has no menus.
+    pub fn is_empty(&self) -> bool {
+        self.menus.is_empty()
+    }
+
+    /// Return the menu at `idx`.
+    pub fn menu(&self, idx: usize) -> Option<&Menu> {
+        self.menus.get(idx)
+    }
+
+    /// Return `true` if one of the menus is open.
+    pub fn is_open(&self) -> bool {
+        self.open_menu.is_some()
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx, idx: usize, highlight_first: bool) {
+        if self.open_menu.is_some() || self.menus[idx].is_empty() {
+            return;
+        }
+        let title_rect = self.titles[idx].layout_rect();
+        let anchor = title_rect + ctx.window_origin().to_vec2();
+        let list = MenuList::new(&self.menus[idx], title_rect.width(), None, highlight_first);
+        let popup_id = ctx.open_popup(list, anchor);
+        trace!("MenuBar {:?} opened menu {}", ctx.widget_id(), idx);
+        self.open_menu = Some((idx, popup_id));
+        self.focused_title = idx;
+        ctx.get_mut(&mut self.titles[idx]).set_open(true);
+        ctx.set_focus(popup_id);
+        ctx.request_paint();
+    }
+
+    /// The index of the title under `pos`, in local coordinates.
+    /// What a key press does while the bar is focused and no menu is open, if anything.
+    fn action_for_key(&self, key: &Key) -> Option<MenuBarKeyAction> {
+        let len = self.menus.len();
+        if len == 0 {
+            return None;
+        }
+        match key {
+            Key::Named(NamedKey::ArrowRight) => {
+                Some(MenuBarKeyAction::FocusTitle((self.focused_title + 1) % len))
+            }
+            Key::Named(NamedKey::ArrowLeft) => Some(MenuBarKeyAction::FocusTitle(
+                (self.focused_title + len - 1) % len,
+            )),
+            Key::Named(NamedKey::Enter | NamedKey::Space | NamedKey::ArrowDown) => {
+                Some(MenuBarKeyAction::Open(self.focused_title))
+            }
+            _ => None,
+        }
+    }
+
+    fn title_at(&self, pos: Point) -> Option<usize> {
+        self.titles
+            .iter()
+            .position(|title| title.layout_rect().contains(pos))
+    }
+}
+
+impl Default for MenuBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MenuBarMut<'_> {
+    /// Add a menu after the others.
+    pub fn add_menu(&mut self, menu: Menu) {
+        self.close();
+        let title = WidgetPod::new(MenuTitle::new(menu.title()));
+        self.widget.titles.push(title);
+        self.widget.menus.push(menu);
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Replace the menu at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_menu(&mut self, idx: usize, menu: Menu) {
+        self.close();
+        let title = menu.title();
+        self.widget.menus[idx] = menu;
+        let mut title_mut = self.ctx.get_mut(&mut self.widget.titles[idx]);
+        title_mut.ctx.request_accessibility_update();
+        title_mut.label_mut().set_text(title);
+    }
+
+    /// Remove the menu at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_menu(&mut self, idx: usize) {
+        self.close();
+        self.widget.menus.remove(idx);
+        self.widget.titles.remove(idx);
+        self.widget.focused_title = self
+            .widget
+            .focused_title
+            .min(self.widget.menus.len().saturating_sub(1));
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Close the open menu, if any.
+    ///
+    /// This doesn't emit an action.
+    pub fn close(&mut self) {
+        if let Some((_, popup_id)) = self.widget.open_menu {
+            self.ctx.close_popup(popup_id);
+        }
+    }
+}
+
+impl MenuTitle {
+    fn new(text: ArcStr) -> Self {
+        MenuTitle {
+            label: WidgetPod::new(Label::new(text)),
+            open: false,
+        }
+    }
+}
+
+impl MenuTitleMut<'_> {
+    fn set_open(&mut self, open: bool) {
+        self.widget.open = open;
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    fn label_mut(&mut self) -> WidgetMut<'_, Label> {
+        self.ctx.get_mut(&mut self.widget.label)
+    }
+}
+
+impl MenuList {
+    fn new(
+        menu: &Menu,
+        min_width: f64,
+        root_popup: Option<WidgetId>,
+        highlight_first: bool,
+    ) -> Self {
+        let rows = menu
+            .entries
+            .iter()
+            .map(|entry| WidgetPod::new(MenuRow::new(entry)))
+            .collect();
+        let mut list = MenuList {
+            entries: menu.entries.clone(),
+            rows,
+            highlighted: None,
+            open_submenu: None,
+            root_popup,
+            min_width: min_width.max(MENU_MIN_WIDTH),
+            scroll_offset: 0.0,
+            max_scroll_offset: 0.0,
+            reveal_highlighted: false,
+        };
+        if highlight_first {
+            list.highlighted = list.next_row(None, true);
+            if let Some(idx) = list.highlighted {
+                list.rows[idx].widget_mut().highlighted = true;
+            }
+        }
+        list
+    }
+
+    /// `true` if the row at `idx` reacts to the pointer and keyboard.
+    fn is_interactive(&self, idx: usize) -> bool {
+        match &self.entries[idx] {
+            MenuEntry::Item(item) => item.enabled,
+            MenuEntry::Separator => false,
+            MenuEntry::Submenu(submenu) => !submenu.is_empty(),
+        }
+    }
+
+    /// The next interactive row after `from`, or before it if `forward` is false,
+    /// wrapping around.
+    fn next_row(&self, from: Option<usize>, forward: bool) -> Option<usize> {
+        let len = self.rows.len();
+        (1..=len)
+            .map(|offset| match (from, forward) {
+                (Some(from), true) => (from + offset) % len,
+                (Some(from), false) => (from + len - offset) % len,
+                (None, true) => offset - 1,
+                (None, false) => len - offset,
+            })
+            .find(|&idx| self.is_interactive(idx))
+    }
+
+    /// What a key press does in the list, if anything.
+    fn action_for_key(&self, key: &Key) -> Option<MenuListKeyAction> {
+        match key {
+            Key::Named(NamedKey::ArrowDown) => Some(MenuListKeyAction::Highlight(
+                self.next_row(self.highlighted, true),
+            )),
+            Key::Named(NamedKey::ArrowUp) => Some(MenuListKeyAction::Highlight(
+                self.next_row(self.highlighted, false),
+            )),
+            Key::Named(NamedKey::Home) => {
+                Some(MenuListKeyAction::Highlight(self.next_row(None, true)))
+            }
+            Key::Named(NamedKey::End) => {
+                Some(MenuListKeyAction::Highlight(self.next_row(None, false)))
+            }
+            Key::Named(NamedKey::Enter | NamedKey::Space) => {
+                self.highlighted.map(MenuListKeyAction::Activate)
+            }
+            Key::Named(NamedKey::ArrowRight) => self
+                .highlighted
+                .filter(|&idx| matches!(self.entries[idx], MenuEntry::Submenu(_)))
+                .map(MenuListKeyAction::OpenSubmenu),
+            Key::Named(NamedKey::ArrowLeft) if self.root_popup.is_some() => {
+                Some(MenuListKeyAction::Close)
+            }
+            _ => None,
+        }
+    }
+
+    /// The index of the row under `pos`, in local coordinates.
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|row| row.layout_rect().contains(pos))
+    }
+
+    fn highlight(&mut self, ctx: &mut EventCtx, idx: Option<usize>) {
+        if idx == self.highlighted {
+            return;
+        }
+        if let Some(old) = self.highlighted {
+            ctx.get_mut(&mut self.rows[old]).set_highlighted(false);
+        }
+        if let Some(new) = idx {
+            ctx.get_mut(&mut self.rows[new]).set_highlighted(true);
+        }
+        self.highlighted = idx;
+        ctx.request_accessibility_update();
+    }
+
+    /// Highlight the row at `idx`, and scroll it into view.
+    fn highlight_and_reveal(&mut self, ctx: &mut EventCtx, idx: Option<usize>) {
+        self.highlight(ctx, idx);
+        self.reveal_highlighted = true;
+        ctx.request_layout();
+    }
+
+    fn open_submenu(&mut self, ctx: &mut EventCtx, idx: usize, focus: bool) {
+        if self.open_submenu.map(|(open_idx, _)| open_idx) == Some(idx) {
+            return;
+        }
+        self.close_submenu(ctx);
+        let MenuEntry::Submenu(submenu) = &self.entries[idx] else {
+            return;
+        };
+        if submenu.is_empty() {
+            return;
+        }
+
+        // Submenus open on the right of their row, aligned with its top.
+        let row_rect = self.rows[idx].layout_rect() + ctx.window_origin().to_vec2();
+        let anchor = Rect::new(row_rect.x1, row_rect.y0, row_rect.x1, row_rect.y0);
+        let root_popup = self.root_popup.unwrap_or(ctx.widget_id());
+        let list = MenuList::new(submenu, 0.0, Some(root_popup), focus);
+        let popup_id = ctx.open_popup(list, anchor);
+        self.open_submenu = Some((idx, popup_id));
+        ctx.get_mut(&mut self.rows[idx]).set_expanded(true);
+        if focus {
+            ctx.set_focus(popup_id);
+        }
+    }
+
+    fn close_submenu(&mut self, ctx: &mut EventCtx) {
+        if let Some((idx, popup_id)) = self.open_submenu.take() {
+            ctx.close_popup(popup_id);
+            ctx.get_mut(&mut self.rows[idx]).set_expanded(false);
+        }
+    }
+
+    fn activate(&mut self, ctx: &mut EventCtx, idx: usize) {
+        match &self.entries[idx] {
+            MenuEntry::Item(item) if item.enabled => {
+                trace!(
+                    "MenuList {:?} activated item {:?}",
+                    ctx.widget_id(),
+                    item.id
+                );
+                // This closes the submenus along with the first menu, whose owner
+                // gets the item's id.
+                let root_popup = self.root_popup.unwrap_or(ctx.widget_id());
+                ctx.close_popup_with_result(root_popup, item.id.clone());
+            }
+            MenuEntry::Submenu(_) => {
+                self.open_submenu(ctx, idx, true);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl MenuRow {
+    fn new(entry: &MenuEntry) -> Self {
+        let (text, accelerator, checked, has_submenu, enabled) = match entry {
+            MenuEntry::Item(item) => (
+                Some(item.text.clone()),
+                item.accelerator.clone(),
+                item.checked,
+                false,
+                item.enabled,
+            ),
+            MenuEntry::Separator => (None, None, None, false, true),
+            MenuEntry::Submenu(submenu) => (
+                Some(submenu.title.clone()),
+                None,
+                None,
+                true,
+                !submenu.is_empty(),
+            ),
+        };
+        MenuRow {
+            label: text.map(|text| WidgetPod::new(Label::new(text))),
+            accelerator: accelerator.map(|text| WidgetPod::new(Label::new(text))),
+            checked,
+            has_submenu,
+            enabled,
+            highlighted: false,
+            expanded: false,
+        }
+    }
+}
+
+impl MenuRowMut<'_> {
+    fn set_highlighted(&mut self, highlighted: bool) {
+        self.widget.highlighted = highlighted;
+        self.ctx.request_paint();
+    }
+
+    fn set_expanded(&mut self, expanded: bool) {
+        self.widget.expanded = expanded;
+        self.ctx.request_accessibility_update();
+    }
+}
+
+/// `true` if the key press opens a context menu: the context menu key, or Shift+F10.
+fn is_context_menu_key(event: &TextEvent) -> bool {
+    let TextEvent::KeyboardKey(key_event, mods) = event else {
+        return false;
+    };
+    key_event.state == ElementState::Pressed
+        && match key_event.logical_key {
+            Key::Named(NamedKey::ContextMenu) => true,
+            Key::Named(NamedKey::F10) => mods.shift_key(),
+            _ => false,
+        }
+}
+
+/// The text of a standard menu or item in `locale`, from the builtin translations.
+///
+/// Only messages without placeables, like `common-menu-cut = Cut`, can be looked up.
+fn builtin_text(locale: &str, key: &str) -> ArcStr {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let translations = BUILTIN_TRANSLATIONS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(locale))
+        .or_else(|| {
+            BUILTIN_TRANSLATIONS.iter().find(|(name, _)| {
+                name.split('-')
+                    .next()
+                    .map_or(false, |name| name.eq_ignore_ascii_case(language))
+            })
+        });
+    let lookup = |ftl: &str| {
+        ftl.lines().find_map(|line| {
+            let (line_key, value) = line.split_once('=')?;
+            (line_key.trim() == key).then(|| ArcStr::from(value.trim()))
+        })
+    };
+    translations
+        .and_then(|(_, ftl)| lookup(ftl))
+        .or_else(|| lookup(BUILTIN_TRANSLATIONS[0].1))
+        .unwrap_or_else(|| key.into())
+}
+
+/// The id of the item activated in a menu, from the result the menu was closed with.
+fn activated_item(result: &Option<PopupResult>) -> Option<ArcStr> {
+    result.as_ref()?.get::<ArcStr>().cloned()
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for ContextMenu {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+
+        if let PointerEvent::PointerDown(MouseButton::Right, state) = event {
+            if ctx.is_hot() && !ctx.is_disabled() {
+                let pointer_pos = Point::new(state.position.x, state.position.y);
+                self.open(ctx, Rect::from_origin_size(pointer_pos, Size::ZERO), false);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+
+        if !ctx.is_handled() && !ctx.is_disabled() && is_context_menu_key(event) {
+            let anchor = ctx.size().to_rect() + ctx.window_origin().to_vec2();
+            self.open(ctx, anchor, true);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::PopupClosed(popup_id, result) = event {
+            if self.popup == Some(*popup_id) {
+                self.popup = None;
+                if let Some(item_id) = activated_item(result) {
+                    self.menu.toggle_item(&item_id);
+                    ctx.submit_action(Action::MenuItemActivated(item_id));
+                }
+            }
+        }
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ContextMenu")
+    }
+}
+
+impl Widget for MenuBar {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for title in &mut self.titles {
+            title.on_pointer_event(ctx, event);
+        }
+        if let PointerEvent::PointerDown(MouseButton::Left, state) = event {
+            if ctx.is_disabled() {
+                return;
+            }
+            let local_pos =
+                Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+            if let Some(idx) = self.title_at(local_pos) {
+                self.open(ctx, idx, false);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if ctx.is_disabled() || self.menus.is_empty() || self.open_menu.is_some() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        match self.action_for_key(&key_event.logical_key) {
+            Some(MenuBarKeyAction::FocusTitle(idx)) => self.focused_title = idx,
+            Some(MenuBarKeyAction::Open(idx)) => self.open(ctx, idx, true),
+            None => return,
+        }
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action != accesskit::Action::Default || ctx.is_disabled() {
+            return;
+        }
+        // Assistive technologies activate the titles themselves.
+        let target = self
+            .titles
+            .iter()
+            .position(|title| title.id() == event.target);
+        if let Some(idx) = target {
+            self.open(ctx, idx, true);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                if !self.menus.is_empty() {
+                    ctx.register_for_focus();
+                }
+            }
+            LifeCycle::PopupClosed(popup_id, result) => {
+                if let Some((idx, open_popup_id)) = self.open_menu {
+                    if open_popup_id == *popup_id {
+                        self.open_menu = None;
+                        ctx.get_mut(&mut self.titles[idx]).set_open(false);
+                        if let Some(item_id) = activated_item(result) {
+                            self.menus[idx].toggle_item(&item_id);
+                            ctx.submit_action(Action::MenuItemActivated(item_id));
+                        }
+                        ctx.request_paint();
+                    }
+                }
+            }
+            _ => {}
+        }
+        for title in &mut self.titles {
+            title.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let title_bc = bc.loosen();
+        let mut x = 0.0;
+        let mut height: f64 = 0.0;
+        for title in &mut self.titles {
+            let title_size = title.layout(ctx, &title_bc);
+            ctx.place_child(title, Point::new(x, 0.0));
+            x += title_size.width;
+            height = height.max(title_size.height);
+        }
+
+        let size = bc.constrain(Size::new(x, height));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        fill_color(scene, &ctx.size().to_rect(), theme::BACKGROUND_DARK);
+
+        if ctx.is_focused() && self.open_menu.is_none() {
+            if let Some(title) = self.titles.get(self.focused_title) {
+                let rect = title.layout_rect().inset(-1.0);
+                stroke(scene, &rect, theme::PRIMARY_LIGHT, 1.0);
+            }
+        }
+        for title in &mut self.titles {
+            title.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::MenuBar
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for title in &mut self.titles {
+            title.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.titles.iter().map(|title| title.as_dyn()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("MenuBar")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        let open = self.open_menu.map(|(idx, _)| idx);
+        Some(format!("open menu: {open:?}"))
+    }
+}
+
+impl Widget for MenuTitle {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+        // Only the hover state changes; the bar handles clicks.
+        ctx.request_paint();
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let label_size = self.label.layout(ctx, &bc.loosen());
+        ctx.place_child(
+            &mut self.label,
+            Point::new(TITLE_PADDING_HORIZONTAL, TITLE_PADDING_VERTICAL),
+        );
+        bc.constrain(Size::new(
+            label_size.width + 2.0 * TITLE_PADDING_HORIZONTAL,
+            label_size.height + 2.0 * TITLE_PADDING_VERTICAL,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let rect = ctx.size().to_rect();
+        if self.open {
+            fill_color(scene, &rect, theme::SELECTED_TEXT_BACKGROUND_COLOR);
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            fill_color(scene, &rect, theme::BACKGROUND_LIGHT);
+        }
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::MenuItem
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_expanded(self.open);
+        node.set_default_action_verb(DefaultActionVerb::Open);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("MenuTitle")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.label.as_ref().text().to_string())
+    }
+}
+
+impl Widget for MenuList {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for row in &mut self.rows {
+            row.on_pointer_event(ctx, event);
+        }
+        let window_origin = ctx.window_origin().to_vec2();
+        match event {
+            PointerEvent::PointerMove(state) => {
+                let local_pos = Point::new(state.position.x, state.position.y) - window_origin;
+                let Some(idx) = self.row_at(local_pos) else {
+                    return;
+                };
+                if !self.is_interactive(idx) {
+                    self.highlight(ctx, None);
+                    return;
+                }
+                self.highlight(ctx, Some(idx));
+                // Hovering a submenu opens it, and hovering another row closes it.
+                if let MenuEntry::Submenu(_) = self.entries[idx] {
+                    self.open_submenu(ctx, idx, false);
+                } else {
+                    self.close_submenu(ctx);
+                }
+            }
+            PointerEvent::PointerDown(_, _) => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            PointerEvent::PointerUp(_, state) => {
+                if ctx.is_active() && ctx.is_hot() {
+                    let local_pos = Point::new(state.position.x, state.position.y) - window_origin;
+                    if let Some(idx) = self.row_at(local_pos) {
+                        self.activate(ctx, idx);
+                    }
+                }
+                ctx.set_active(false);
+                ctx.set_handled();
+            }
+            PointerEvent::MouseWheel(delta, _) => {
+                let scroll_offset =
+                    (self.scroll_offset + delta.y).clamp(0.0, self.max_scroll_offset);
+                if scroll_offset != self.scroll_offset {
+                    self.scroll_offset = scroll_offset;
+                    // The submenu would be left behind by its row.
+                    self.close_submenu(ctx);
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        match self.action_for_key(&key_event.logical_key) {
+            Some(MenuListKeyAction::Highlight(idx)) => self.highlight_and_reveal(ctx, idx),
+            Some(MenuListKeyAction::Activate(idx)) => self.activate(ctx, idx),
+            Some(MenuListKeyAction::OpenSubmenu(idx)) => self.open_submenu(ctx, idx, true),
+            // The focus goes back to the menu the submenu was opened from.
+            Some(MenuListKeyAction::Close) => ctx.close_popup(ctx.widget_id()),
+            // Escape is left to the popup, which closes without activating anything.
+            None => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action != accesskit::Action::Default {
+            return;
+        }
+        // Assistive technologies activate the rows themselves.
+        let target = self.rows.iter().position(|row| row.id() == event.target);
+        if let Some(idx) = target {
+            self.activate(ctx, idx);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::PopupClosed(popup_id, _) => {
+                if let Some((idx, open_popup_id)) = self.open_submenu {
+                    if open_popup_id == *popup_id {
+                        self.open_submenu = None;
+                        ctx.get_mut(&mut self.rows[idx]).set_expanded(false);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for row in &mut self.rows {
+            row.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        // The rows are as wide as the widest one, so we lay them out twice.
+        let row_bc = bc.loosen();
+        let row_sizes: Vec<Size> = self
+            .rows
+            .iter_mut()
+            .map(|row| row.layout(ctx, &row_bc))
+            .collect();
+        let width = row_sizes
+            .iter()
+            .map(|size| size.width)
+            .fold(self.min_width, f64::max)
+            .min(bc.max().width);
+
+        let mut row_tops = Vec::with_capacity(self.rows.len() + 1);
+        let mut y = LIST_PADDING;
+        for (row, row_size) in self.rows.iter_mut().zip(row_sizes) {
+            let row_size = row.layout(
+                ctx,
+                &BoxConstraints::tight(Size::new(width, row_size.height)),
+            );
+            row_tops.push(y);
+            y += row_size.height;
+        }
+        row_tops.push(y);
+        let content_height = y + LIST_PADDING;
+        let size = bc.constrain(Size::new(width, content_height));
+
+        // The menu is as tall as the window at most, and scrolls past that.
+        self.max_scroll_offset = (content_height - size.height).max(0.0);
+        if std::mem::take(&mut self.reveal_highlighted) {
+            if let Some(idx) = self.highlighted {
+                let row_top = row_tops[idx] - LIST_PADDING;
+                let row_bottom = row_tops[idx + 1] + LIST_PADDING;
+                if row_top < self.scroll_offset {
+                    self.scroll_offset = row_top;
+                } else if row_bottom > self.scroll_offset + size.height {
+                    self.scroll_offset = row_bottom - size.height;
+                }
+            }
+        }
+        self.scroll_offset = self.scroll_offset.clamp(0.0, self.max_scroll_offset);
+
+        for (row, row_top) in self.rows.iter_mut().zip(row_tops) {
+            ctx.place_child(row, Point::new(0.0, row_top - self.scroll_offset));
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let rounded_rect = ctx
+            .size()
+            .to_rect()
+            .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        fill_color(scene, &rounded_rect, theme::BACKGROUND_DARK);
+        stroke(scene, &rounded_rect, theme::BORDER_LIGHT, 1.0);
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &rounded_rect);
+        for row in &mut self.rows {
+            row.paint(ctx, scene);
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Menu
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if let Some(row) = self.highlighted.and_then(|idx| self.rows.get(idx)) {
+            ctx.current_node()
+                .set_active_descendant(NodeId::from(row.id()));
+        }
+        for row in &mut self.rows {
+            row.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.rows.iter().map(|row| row.as_dyn()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("MenuList")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("highlighted: {:?}", self.highlighted))
+    }
+}
+
+impl Widget for MenuRow {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for label in self.label.iter_mut().chain(&mut self.accelerator) {
+            label.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for label in self.label.iter_mut().chain(&mut self.accelerator) {
+            label.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for label in self.label.iter_mut().chain(&mut self.accelerator) {
+            label.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            if !self.enabled {
+                ctx.set_disabled(true);
+            }
+        }
+        if let Some(label) = &mut self.label {
+            label.lifecycle(ctx, event);
+        }
+        if let Some(accelerator) = &mut self.accelerator {
+            accelerator.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let Some(label) = &mut self.label else {
+            return bc.constrain(Size::new(0.0, SEPARATOR_HEIGHT));
+        };
+
+        let label_size = label.layout(ctx, &bc.loosen());
+        let accelerator_size = match &mut self.accelerator {
+            Some(accelerator) => accelerator.layout(ctx, &bc.loosen()),
+            None => Size::ZERO,
+        };
+        let accelerator_width = if self.accelerator.is_some() {
+            ACCELERATOR_SPACING + accelerator_size.width
+        } else {
+            0.0
+        };
+        let size = bc.constrain(Size::new(
+            2.0 * ROW_PADDING_HORIZONTAL
+                + CHECK_COLUMN_WIDTH
+                + label_size.width
+                + accelerator_width
+                + ARROW_COLUMN_WIDTH,
+            label_size.height.max(theme::BASIC_WIDGET_HEIGHT) + 2.0 * ROW_PADDING_VERTICAL,
+        ));
+
+        let label_y = (size.height - label_size.height) / 2.0;
+        ctx.place_child(
+            label,
+            Point::new(ROW_PADDING_HORIZONTAL + CHECK_COLUMN_WIDTH, label_y),
+        );
+        if let Some(accelerator) = &mut self.accelerator {
+            let x =
+                size.width - ROW_PADDING_HORIZONTAL - ARROW_COLUMN_WIDTH - accelerator_size.width;
+            let y = (size.height - accelerator_size.height) / 2.0;
+            ctx.place_child(accelerator, Point::new(x, y));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let center_y = size.height / 2.0;
+        let Some(label) = &mut self.label else {
+            let line = Line::new(
+                (ROW_PADDING_HORIZONTAL, center_y),
+                (size.width - ROW_PADDING_HORIZONTAL, center_y),
+            );
+            stroke(scene, &line, theme::BORDER_LIGHT, 1.0);
+            return;
+        };
+
+        if self.highlighted && !ctx.is_disabled() {
+            fill_color(
+                scene,
+                &size.to_rect(),
+                theme::SELECTED_TEXT_BACKGROUND_COLOR,
+            );
+        }
+        let mark_color = if ctx.is_disabled() {
+            theme::DISABLED_TEXT_COLOR
+        } else {
+            theme::TEXT_COLOR
+        };
+
+        if self.checked == Some(true) {
+            let x = ROW_PADDING_HORIZONTAL;
+            let mut check = BezPath::new();
+            check.move_to((x + 2.0, center_y));
+            check.line_to((x + 5.0, center_y + 3.0));
+            check.line_to((x + 11.0, center_y - 4.0));
+            stroke(scene, &check, mark_color, 2.0);
+        }
+        if self.has_submenu {
+            let x = size.width - ROW_PADDING_HORIZONTAL - ARROW_COLUMN_WIDTH / 2.0;
+            let mut arrow = BezPath::new();
+            arrow.move_to((x - 2.0, center_y - 4.0));
+            arrow.line_to((x + 2.0, center_y));
+            arrow.line_to((x - 2.0, center_y + 4.0));
+            arrow.close_path();
+            fill_color(scene, &arrow, mark_color);
+        }
+
+        label.paint(ctx, scene);
+        if let Some(accelerator) = &mut self.accelerator {
+            accelerator.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        if self.label.is_none() {
+            Role::Splitter
+        } else if self.checked.is_some() {
+            Role::MenuItemCheckBox
+        } else {
+            Role::MenuItem
+        }
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let Some(label) = &mut self.label else {
+            return;
+        };
+        let node = ctx.current_node();
+        if let Some(checked) = self.checked {
+            node.set_checked(if checked {
+                Checked::True
+            } else {
+                Checked::False
+            });
+        }
+        if self.has_submenu {
+            node.set_expanded(self.expanded);
+        }
+        if let Some(accelerator) = &self.accelerator {
+            node.set_keyboard_shortcut(accelerator.as_ref().text().to_string());
+        }
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        label.accessibility(ctx);
+        if let Some(accelerator) = &mut self.accelerator {
+            accelerator.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.label
+            .iter()
+            .chain(&self.accelerator)
+            .map(|label| label.as_dyn())
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("MenuRow")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        let text = match &self.label {
+            Some(label) => label.as_ref().text().to_string(),
+            None => "---".to_string(),
+        };
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use winit::keyboard::ModifiersState;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, SizedBox};
+    use crate::Vec2;
+
+    fn edit_menu() -> Menu {
+        Menu::new("Edit")
+            .with_item(MenuItem::new("cut", "Cut").with_accelerator("Ctrl+X"))
+            .with_item(MenuItem::new("paste", "Paste").with_enabled(false))
+            .with_separator()
+            .with_item(MenuItem::new("word-wrap", "Word wrap").with_checked(false))
+            .with_submenu(
+                Menu::new("Case")
+                    .with_item(MenuItem::new("upper", "Upper case"))
+                    .with_item(MenuItem::new("lower", "Lower case")),
+            )
+    }
+
+    /// The ids of the rows of the open menu at `depth`, 0 being the first one opened.
+    fn row_ids(harness: &TestHarness, depth: usize) -> Vec<WidgetId> {
+        harness.popups()[depth]
+            .children()
+            .iter()
+            .map(|row| row.id())
+            .collect()
+    }
+
+    fn right_click(harness: &mut TestHarness, pos: Point) {
+        harness.mouse_move(pos);
+        harness.mouse_button_press(MouseButton::Right);
+        harness.mouse_button_release(MouseButton::Right);
+    }
+
+    fn context_menu_harness() -> TestHarness {
+        let widget = ContextMenu::new(SizedBox::empty().width(200.0).height(100.0), edit_menu());
+        TestHarness::create(widget)
+    }
+
+    #[test]
+    fn context_menu_activates_item() {
+        let mut harness = context_menu_harness();
+        let context_menu_id = harness.root_widget().id();
+
+        right_click(&mut harness, Point::new(50.0, 50.0));
+        assert_eq!(harness.popups().len(), 1);
+        // The menu opens at the pointer.
+        let menu_rect = harness.popups()[0].state().window_layout_rect();
+        assert_eq!(menu_rect.origin(), Point::new(50.0, 50.0));
+
+        harness.mouse_click_on(row_ids(&harness, 0)[0]);
+        assert!(harness.popups().is_empty());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::MenuItemActivated("cut".into()), context_menu_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn escape_closes_without_activating() {
+        let mut harness = context_menu_harness();
+        right_click(&mut harness, Point::new(50.0, 50.0));
+
+        harness.keyboard_press_unused_key(Key::Named(NamedKey::Escape), ModifiersState::empty());
+        assert!(harness.popups().is_empty());
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn disabled_items_and_separators_do_nothing() {
+        let mut harness = context_menu_harness();
+        right_click(&mut harness, Point::new(50.0, 50.0));
+
+        let rows = row_ids(&harness, 0);
+        harness.mouse_click_on(rows[1]);
+        harness.mouse_click_on(rows[2]);
+        assert_eq!(harness.popups().len(), 1);
+        assert_eq!(harness.pop_action(), None);
+        assert!(harness.get_widget(rows[1]).state().is_disabled());
+    }
+
+    #[test]
+    fn checkable_item_is_toggled() {
+        let mut harness = context_menu_harness();
+        let context_menu_id = harness.root_widget().id();
+
+        right_click(&mut harness, Point::new(50.0, 50.0));
+        harness.mouse_click_on(row_ids(&harness, 0)[3]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::MenuItemActivated("word-wrap".into()),
+                context_menu_id
+            ))
+        );
+
+        let context_menu = harness.root_widget();
+        let context_menu = context_menu.downcast::<ContextMenu>().unwrap();
+        let item = context_menu.menu().find_item("word-wrap").unwrap();
+        assert_eq!(item.checked(), Some(true));
+    }
+
+    #[test]
+    fn submenu_opens_on_hover() {
+        let mut harness = context_menu_harness();
+        let context_menu_id = harness.root_widget().id();
+        right_click(&mut harness, Point::new(50.0, 50.0));
+
+        let rows = row_ids(&harness, 0);
+        harness.mouse_move_to(rows[4]);
+        assert_eq!(harness.popups().len(), 2);
+        // The submenu opens on the right of its row.
+        let row_rect = harness.get_widget(rows[4]).state().window_layout_rect();
+        let submenu_rect = harness.popups()[1].state().window_layout_rect();
+        assert_eq!(submenu_rect.origin(), Point::new(row_rect.x1, row_rect.y0));
+
+        // Hovering another row closes it.
+        harness.mouse_move_to(rows[0]);
+        assert_eq!(harness.popups().len(), 1);
+
+        harness.mouse_move_to(rows[4]);
+        harness.mouse_click_on(row_ids(&harness, 1)[1]);
+        assert!(harness.popups().is_empty());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::MenuItemActivated("lower".into()), context_menu_id))
+        );
+    }
+
+    #[test]
+    fn menu_bar_opens_menu_below_title() {
+        let [bar_id] = widget_ids();
+        let bar = MenuBar::new()
+            .with_menu(Menu::new("File").with_item(MenuItem::new("new", "New")))
+            .with_menu(edit_menu());
+        let widget = Flex::column().with_child_id(bar, bar_id);
+
+        let mut harness = TestHarness::create(widget);
+        let title_ids: Vec<_> = harness
+            .get_widget(bar_id)
+            .children()
+            .iter()
+            .map(|title| title.id())
+            .collect();
+
+        harness.mouse_click_on(title_ids[1]);
+        assert_eq!(harness.popups().len(), 1);
+        let title_rect = harness
+            .get_widget(title_ids[1])
+            .state()
+            .window_layout_rect();
+        let menu_rect = harness.popups()[0].state().window_layout_rect();
+        assert_eq!(menu_rect.origin(), Point::new(title_rect.x0, title_rect.y1));
+        assert_eq!(
+            harness.focused_widget().map(|w| w.id()),
+            Some(harness.popups()[0].id())
+        );
+
+        harness.mouse_click_on(row_ids(&harness, 0)[0]);
+        assert!(harness.popups().is_empty());
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::MenuItemActivated("cut".into()), bar_id))
+        );
+        // The focus goes back to the bar.
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(bar_id));
+    }
+
+    #[test]
+    fn long_menu_scrolls() {
+        let mut menu = Menu::new("Long");
+        for idx in 0..40 {
+            menu = menu.with_item(MenuItem::new(format!("item-{idx}"), format!("Item {idx}")));
+        }
+        let widget = ContextMenu::new(SizedBox::empty().width(300.0).height(300.0), menu);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(300.0, 300.0));
+        let context_menu_id = harness.root_widget().id();
+        right_click(&mut harness, Point::new(10.0, 10.0));
+        // The menu is kept inside the window.
+        let menu_rect = harness.popups()[0].state().window_layout_rect();
+        assert!(menu_rect.height() <= 300.0);
+
+        // Clicking a scrolled row activates it.
+        harness.mouse_wheel(Vec2::new(0.0, 10_000.0));
+        let row_id = row_ids(&harness, 0)[38];
+        harness.mouse_click_on(row_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::MenuItemActivated("item-38".into()), context_menu_id))
+        );
+    }
+
+    #[test]
+    fn access_tree() {
+        let menu = Menu::new("Edit")
+            .with_item(MenuItem::new("cut", "Cut").with_accelerator("Ctrl+X"))
+            .with_separator()
+            .with_item(MenuItem::new("word-wrap", "Word wrap").with_checked(true));
+        let bar = MenuBar::new().with_menu(menu);
+
+        let mut harness = TestHarness::create(bar);
+        let title_id = harness.root_widget().children()[0].id();
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  MenuBar
+    MenuItem
+      StaticText \"Edit\"
+"
+        );
+
+        harness.mouse_click_on(title_id);
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  MenuBar
+    MenuItem
+      StaticText \"Edit\"
+  Menu
+    MenuItem
+      StaticText \"Cut\"
+      StaticText \"Ctrl+X\"
+    Splitter
+    MenuItemCheckBox checked=True
+      StaticText \"Word wrap\"
+"
+        );
+    }
+
+    #[test]
+    fn menu_bar_keys() {
+        let named = Key::Named;
+        let bar = MenuBar::new()
+            .with_menu(Menu::new("File").with_item(MenuItem::new("new", "New")))
+            .with_menu(edit_menu());
+        assert_eq!(
+            bar.action_for_key(&named(NamedKey::ArrowRight)),
+            Some(MenuBarKeyAction::FocusTitle(1))
+        );
+        // The arrows wrap around.
+        assert_eq!(
+            bar.action_for_key(&named(NamedKey::ArrowLeft)),
+            Some(MenuBarKeyAction::FocusTitle(1))
+        );
+        for key in [NamedKey::Enter, NamedKey::Space, NamedKey::ArrowDown] {
+            assert_eq!(
+                bar.action_for_key(&named(key)),
+                Some(MenuBarKeyAction::Open(0))
+            );
+        }
+        assert_eq!(bar.action_for_key(&named(NamedKey::ArrowUp)), None);
+        assert_eq!(MenuBar::new().action_for_key(&named(NamedKey::Enter)), None);
+    }
+
+    #[test]
+    fn menu_list_keys() {
+        let named = Key::Named;
+        // Rows: cut, disabled paste, separator, word wrap, case submenu.
+        let mut list = MenuList::new(&edit_menu(), 0.0, None, true);
+        assert_eq!(list.highlighted, Some(0));
+        // The arrows skip the disabled item and the separator, and wrap around.
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::ArrowDown)),
+            Some(MenuListKeyAction::Highlight(Some(3)))
+        );
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::ArrowUp)),
+            Some(MenuListKeyAction::Highlight(Some(4)))
+        );
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::Home)),
+            Some(MenuListKeyAction::Highlight(Some(0)))
+        );
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::End)),
+            Some(MenuListKeyAction::Highlight(Some(4)))
+        );
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::Enter)),
+            Some(MenuListKeyAction::Activate(0))
+        );
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::Space)),
+            Some(MenuListKeyAction::Activate(0))
+        );
+        // Only submenus open with the right arrow, and the first menu doesn't close
+        // with the left arrow.
+        assert_eq!(list.action_for_key(&named(NamedKey::ArrowRight)), None);
+        assert_eq!(list.action_for_key(&named(NamedKey::ArrowLeft)), None);
+        list.highlighted = Some(4);
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::ArrowRight)),
+            Some(MenuListKeyAction::OpenSubmenu(4))
+        );
+        // Escape is left to the popup.
+        assert_eq!(list.action_for_key(&named(NamedKey::Escape)), None);
+
+        let submenu = Menu::new("Case").with_item(MenuItem::new("upper", "Upper case"));
+        let list = MenuList::new(&submenu, 0.0, Some(WidgetId::next()), false);
+        assert_eq!(list.action_for_key(&named(NamedKey::Enter)), None);
+        assert_eq!(
+            list.action_for_key(&named(NamedKey::ArrowLeft)),
+            Some(MenuListKeyAction::Close)
+        );
+    }
+
+    #[test]
+    fn standard_menus() {
+        let edit = Menu::standard_edit("de-DE");
+        assert_eq!(&*edit.title(), "Bearbeiten");
+        assert_eq!(
+            &*edit.find_item("common-menu-cut").unwrap().text(),
+            "Ausschneiden"
+        );
+
+        // Locales are matched by language, and fall back to English.
+        let file = Menu::standard_file("fr");
+        assert_eq!(
+            &*file.find_item("common-menu-file-open").unwrap().text(),
+            "Ouvrir..."
+        );
+        let file = Menu::standard_file("ja-JP");
+        assert_eq!(&*file.title(), "File");
+        assert_eq!(
+            &*file.find_item("common-menu-file-save-as").unwrap().text(),
+            "Save As..."
+        );
+
+        let mut edit = Menu::standard_edit("en-US");
+        edit.set_title("Édition");
+        assert!(edit.set_item_text("common-menu-undo", "Annuler"));
+        assert_eq!(&*edit.title(), "Édition");
+        assert_eq!(
+            &*edit.find_item("common-menu-undo").unwrap().text(),
+            "Annuler"
+        );
+        assert!(!edit.set_item_text("missing", "Missing"));
+    }
+
+    #[test]
+    fn find_and_check_items() {
+        let mut menu = edit_menu();
+        assert_eq!(
+            menu.find_item("upper").unwrap().text().as_ref(),
+            "Upper case"
+        );
+        assert!(menu.find_item("missing").is_none());
+
+        assert!(menu.set_item_checked("upper", true));
+        assert_eq!(menu.find_item("upper").unwrap().checked(), Some(true));
+        assert!(!menu.set_item_checked("missing", true));
+    }
+}
@@ -17,30 +17,62 @@ mod tests;
 mod align;
 mod button;
 mod checkbox;
+mod collapsible;
+mod dialog;
+mod dropdown;
 mod flex;
+mod grid;
 mod image;
 mod label;
+mod menu;
 mod portal;
+mod progress_bar;
+mod radio_group;
 mod scroll_bar;
 mod sized_box;
+mod slider;
 mod spinner;
 mod split;
+mod switch;
+mod table;
+mod tabs;
+mod text_editor;
+mod textbox;
+mod tooltip;
+mod virtual_list;
+mod zstack;
 
 pub use self::image::Image;
 pub use align::Align;
 pub use button::Button;
 pub use checkbox::Checkbox;
+pub use collapsible::Collapsible;
+pub use dialog::Dialog;
+pub use dropdown::Dropdown;
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use grid::{Grid, GridParams, GridTrack};
 pub use label::{Label, LineBreaking};
+pub use menu::{ContextMenu, Menu, MenuBar, MenuItem};
 pub use portal::Portal;
+pub use progress_bar::ProgressBar;
+pub use radio_group::RadioGroup;
 pub use scroll_bar::ScrollBar;
 pub use sized_box::SizedBox;
+pub use slider::Slider;
 pub use spinner::Spinner;
 pub use split::Split;
+pub use switch::Switch;
+pub use table::{SortOrder, Table};
+pub use tabs::Tabs;
+pub use text_editor::TextEditor;
+pub use textbox::TextBox;
+pub use tooltip::Tooltip;
+pub use virtual_list::VirtualList;
 pub use widget_mut::WidgetMut;
 pub use widget_pod::WidgetPod;
 pub use widget_ref::WidgetRef;
 pub use widget_state::WidgetState;
+pub use zstack::ZStack;
 
 pub use sized_box::BackgroundBrush;
 pub use widget::StoreInWidgetMut;
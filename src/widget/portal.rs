@@ -6,6 +6,7 @@
 
 use std::ops::Range;
 
+use accesskit::{ActionData, Role};
 use kurbo::Affine;
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace_span, Span};
@@ -15,8 +16,8 @@ use vello::Scene;
 use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::widget::{Axis, ScrollBar, StoreInWidgetMut, WidgetMut, WidgetRef};
 use crate::{
-    BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent,
-    StatusChange, TextEvent, Widget, WidgetPod,
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
 };
 
 // TODO - refactor - see issue #15
@@ -30,6 +31,9 @@ pub struct Portal<W: Widget> {
     // on re-layouts
     // TODO - rename
     viewport_pos: Point,
+    /// A rect requested by a descendant, in child coordinates, which we'll pan to
+    /// once the child's new size is known.
+    pan_target: Option<Rect>,
     // TODO - test how it looks like
     constrain_horizontal: bool,
     constrain_vertical: bool,
@@ -47,6 +51,7 @@ impl<W: Widget> Portal<W> {
         Portal {
             child: WidgetPod::new(child),
             viewport_pos: Point::ORIGIN,
+            pan_target: None,
             constrain_horizontal: false,
             constrain_vertical: false,
             must_fill: false,
@@ -148,6 +153,41 @@ impl<W: Widget> Portal<W> {
             false
         }
     }
+
+    /// The viewport position which shows as much of `target` as possible,
+    /// moving the viewport as little as possible.
+    fn pan_target_pos(&self, portal_size: Size, target: Rect) -> Point {
+        let viewport = Rect::from_origin_size(self.viewport_pos, portal_size);
+
+        let new_pos_x = compute_pan_range(
+            viewport.min_x()..viewport.max_x(),
+            target.min_x()..target.max_x(),
+        )
+        .start;
+        let new_pos_y = compute_pan_range(
+            viewport.min_y()..viewport.max_y(),
+            target.min_y()..target.max_y(),
+        )
+        .start;
+
+        Point::new(new_pos_x, new_pos_y)
+    }
+
+    /// Move the viewport to `pos`, and update the scrollbars to match.
+    fn scroll_to(&mut self, ctx: &mut EventCtx, pos: Point) {
+        let portal_size = ctx.size();
+        let content_size = self.child.layout_rect().size();
+
+        if self.set_viewport_pos_raw(portal_size, content_size, pos) {
+            let progress_x = self.viewport_pos.x / (content_size - portal_size).width;
+            ctx.get_mut(&mut self.scrollbar_horizontal)
+                .set_cursor_progress(progress_x);
+            let progress_y = self.viewport_pos.y / (content_size - portal_size).height;
+            ctx.get_mut(&mut self.scrollbar_vertical)
+                .set_cursor_progress(progress_y);
+            ctx.request_layout();
+        }
+    }
 }
 
 impl<'a, W: Widget> PortalMut<'a, W> {
@@ -223,20 +263,10 @@ impl<'a, W: Widget> PortalMut<'a, W> {
 
     // Note - Rect is in child coordinates
     pub fn pan_viewport_to(&mut self, target: Rect) -> bool {
-        let viewport = Rect::from_origin_size(self.widget.viewport_pos, self.ctx.widget_state.size);
-
-        let new_pos_x = compute_pan_range(
-            viewport.min_x()..viewport.max_x(),
-            target.min_x()..target.max_x(),
-        )
-        .start;
-        let new_pos_y = compute_pan_range(
-            viewport.min_y()..viewport.max_y(),
-            target.min_y()..target.max_y(),
-        )
-        .start;
-
-        self.set_viewport_pos(Point::new(new_pos_x, new_pos_y))
+        let new_pos = self
+            .widget
+            .pan_target_pos(self.ctx.widget_state.size, target);
+        self.set_viewport_pos(new_pos)
     }
 }
 
@@ -295,6 +325,34 @@ impl<W: Widget> Widget for Portal<W> {
         self.scrollbar_vertical.on_text_event(ctx, event);
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id() {
+            // Scroll by one page at a time.
+            let page_height = ctx.size().height;
+            let new_pos = match (event.action, &event.data) {
+                (accesskit::Action::ScrollUp, _) => {
+                    Some(self.viewport_pos - Vec2::new(0.0, page_height))
+                }
+                (accesskit::Action::ScrollDown, _) => {
+                    Some(self.viewport_pos + Vec2::new(0.0, page_height))
+                }
+                (accesskit::Action::SetScrollOffset, Some(ActionData::SetScrollOffset(offset))) => {
+                    Some(Point::new(offset.x, offset.y))
+                }
+                _ => None,
+            };
+            if let Some(new_pos) = new_pos {
+                self.scroll_to(ctx, new_pos);
+                ctx.request_accessibility_update();
+                ctx.set_handled();
+            }
+        }
+
+        self.child.on_access_event(ctx, event);
+        self.scrollbar_horizontal.on_access_event(ctx, event);
+        self.scrollbar_vertical.on_access_event(ctx, event);
+    }
+
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
@@ -302,8 +360,13 @@ impl<W: Widget> Widget for Portal<W> {
             LifeCycle::WidgetAdded => {
                 ctx.register_as_portal();
             }
-            //TODO
-            //LifeCycle::RequestPanToChild(target_rect) => {}
+            LifeCycle::RequestPanToChild(target_rect) => {
+                // The child may not have been laid out with the content it wants
+                // us to show yet, so we wait for layout to pan.
+                let child_origin = self.child.layout_rect().origin().to_vec2();
+                self.pan_target = Some(*target_rect - child_origin);
+                ctx.request_layout();
+            }
             _ => {}
         }
 
@@ -329,8 +392,16 @@ impl<W: Widget> Widget for Portal<W> {
 
         // TODO - document better
         // Recompute the portal offset for the new layout
-        self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos);
-        // TODO - recompute portal progress
+        let viewport_pos = match self.pan_target.take() {
+            Some(target) => self.pan_target_pos(portal_size, target),
+            None => self.viewport_pos,
+        };
+        self.set_viewport_pos_raw(portal_size, content_size, viewport_pos);
+        // TODO - recompute portal progress in the general case
+        if content_size.height > portal_size.height {
+            self.scrollbar_vertical.widget_mut().cursor_progress =
+                self.viewport_pos.y / (content_size.height - portal_size.height);
+        }
 
         ctx.place_child(&mut self.child, Point::new(0.0, -self.viewport_pos.y));
 
@@ -385,10 +456,67 @@ impl<W: Widget> Widget for Portal<W> {
         }
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::ScrollView
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let portal_size = ctx.size();
+        let content_size = self.child.layout_rect().size();
+        let max_pos = (content_size - portal_size).clamp(Size::ZERO, content_size);
+        let scale_factor = ctx.scale_factor();
+
+        let node = ctx.current_node();
+        node.set_clips_children();
+        node.set_scroll_x(self.viewport_pos.x * scale_factor);
+        node.set_scroll_x_min(0.0);
+        node.set_scroll_x_max(max_pos.width * scale_factor);
+        node.set_scroll_y(self.viewport_pos.y * scale_factor);
+        node.set_scroll_y_min(0.0);
+        node.set_scroll_y_max(max_pos.height * scale_factor);
+        node.add_action(accesskit::Action::ScrollUp);
+        node.add_action(accesskit::Action::ScrollDown);
+        node.add_action(accesskit::Action::SetScrollOffset);
+
+        self.child.accessibility(ctx);
+
+        if self.scrollbar_horizontal_visible {
+            ctx.add_extra_child(&mut self.scrollbar_horizontal);
+        } else {
+            ctx.skip_child(&mut self.scrollbar_horizontal);
+        }
+        if self.scrollbar_vertical_visible {
+            ctx.add_extra_child(&mut self.scrollbar_vertical);
+        } else {
+            ctx.skip_child(&mut self.scrollbar_vertical);
+        }
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         smallvec![self.child.as_dyn()]
     }
 
+    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
+        // The scrollbars are painted over the child, so they take the pointer first.
+        let scrollbars = [
+            (
+                self.scrollbar_horizontal_visible,
+                &self.scrollbar_horizontal,
+            ),
+            (self.scrollbar_vertical_visible, &self.scrollbar_vertical),
+        ];
+        for (visible, scrollbar) in scrollbars {
+            if visible && scrollbar.layout_rect().contains(pos) {
+                return Some(scrollbar.as_dyn());
+            }
+        }
+        if self.child.layout_rect().contains(pos) {
+            Some(self.child.as_dyn())
+        } else {
+            None
+        }
+    }
+
     fn make_trace_span(&self) -> Span {
         trace_span!("Portal")
     }
@@ -397,10 +525,11 @@ impl<W: Widget> Widget for Portal<W> {
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
+    use winit::event::MouseButton;
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::{widget_ids, TestHarness};
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::widget::{Button, Flex, SizedBox};
 
     fn button(text: &str) -> impl Widget {
@@ -584,4 +713,27 @@ mod tests {
 
         assert_eq!(compute_pan_range(initial_range, target_range), result_range);
     }
+
+    #[test]
+    fn scrollbar_over_child_gets_pointer() {
+        let [content_id] = widget_ids();
+        let widget = Portal::new(
+            SizedBox::empty()
+                .width(400.0)
+                .height(1000.0)
+                .with_id(content_id),
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        let content_rect = harness.get_widget(content_id).state().layout_rect();
+        assert_eq!(content_rect.y0, 0.0);
+
+        // Click on the scrollbar track, below the cursor.
+        harness.mouse_move((395.0, 300.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+
+        let content_rect = harness.get_widget(content_id).state().layout_rect();
+        assert!(content_rect.y0 < 0.0);
+    }
 }
@@ -0,0 +1,329 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A progress bar widget.
+
+use accesskit::Role;
+use kurbo::{Affine, Rect};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::WidgetRef;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget,
+};
+
+/// How long the indeterminate animation takes to cross the bar, in seconds.
+const INDETERMINATE_PERIOD: f64 = 1.5;
+/// The width of the moving segment of an indeterminate bar, as a fraction of the bar.
+const INDETERMINATE_WIDTH: f64 = 0.3;
+
+/// A progress bar, displaying a fraction of a task that is done.
+///
+/// If the progress is unknown, the bar shows an animation instead.
+pub struct ProgressBar {
+    /// A value in the range `[0, 1]`, or `None` if the progress is unknown.
+    progress: Option<f64>,
+    /// How far the indeterminate animation is through its cycle, from 0 to 1.
+    anim_t: f64,
+}
+
+crate::declare_widget!(ProgressBarMut, ProgressBar);
+
+// --- METHODS ---
+
+impl ProgressBar {
+    /// Create a new progress bar.
+    ///
+    /// `progress` is clamped to `[0, 1]`. If it's `None`, the bar is indeterminate.
+    pub fn new(progress: Option<f64>) -> Self {
+        ProgressBar {
+            progress: clamp_progress(progress),
+            anim_t: 0.0,
+        }
+    }
+
+    /// Return the current progress, or `None` if the bar is indeterminate.
+    pub fn progress(&self) -> Option<f64> {
+        self.progress
+    }
+}
+
+fn clamp_progress(progress: Option<f64>) -> Option<f64> {
+    progress.map(|progress| progress.clamp(0.0, 1.0))
+}
+
+/// Move the indeterminate animation forward by `interval` nanoseconds, wrapping
+/// around at the end of the cycle.
+fn animation_step(anim_t: f64, interval: u64) -> f64 {
+    let elapsed = (interval as f64) * 1e-9;
+    (anim_t + elapsed / INDETERMINATE_PERIOD).fract()
+}
+
+impl ProgressBarMut<'_> {
+    /// Set the progress.
+    ///
+    /// `progress` is clamped to `[0, 1]`. If it's `None`, the bar is indeterminate.
+    pub fn set_progress(&mut self, progress: Option<f64>) {
+        let progress = clamp_progress(progress);
+        if progress.is_none() && self.widget.progress.is_some() {
+            self.widget.anim_t = 0.0;
+            self.ctx.request_anim_frame();
+        }
+        self.widget.progress = progress;
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for ProgressBar {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                if self.progress.is_none() {
+                    ctx.request_anim_frame();
+                }
+            }
+            LifeCycle::AnimFrame(interval) => {
+                // The animation stops once the progress is known.
+                if self.progress.is_none() {
+                    self.anim_t = animation_step(self.anim_t, *interval);
+                    ctx.request_anim_frame();
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            theme::WIDE_WIDGET_WIDTH
+        };
+        let size = bc.constrain(Size::new(width, theme::BASIC_WIDGET_HEIGHT));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let border_width = 1.;
+        let size = ctx.size();
+
+        let background_rect = size
+            .to_rect()
+            .inset(-border_width / 2.)
+            .to_rounded_rect(theme::PROGRESS_BAR_RADIUS);
+        fill_lin_gradient(
+            scene,
+            &background_rect,
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_DARK],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        let inner_width = size.width - border_width;
+        let (x0, x1) = match self.progress {
+            Some(progress) => (0.0, inner_width * progress),
+            None => {
+                // The segment enters from the left and leaves on the right.
+                let segment_width = inner_width * INDETERMINATE_WIDTH;
+                let x1 = self.anim_t * (inner_width + segment_width);
+                (x1 - segment_width, x1)
+            }
+        };
+        if x1 > x0 {
+            let bar_rect = Rect::new(
+                border_width / 2. + x0.max(0.0),
+                border_width / 2.,
+                border_width / 2. + x1.min(inner_width),
+                size.height - border_width / 2.,
+            );
+            // Clip so the ends of the bar follow the rounded background.
+            scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &background_rect);
+            fill_lin_gradient(
+                scene,
+                &bar_rect,
+                [theme::PRIMARY_LIGHT, theme::PRIMARY_DARK],
+                UnitPoint::TOP,
+                UnitPoint::BOTTOM,
+            );
+            scene.pop_layer();
+        }
+
+        stroke(scene, &background_rect, theme::BORDER_DARK, border_width);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ProgressIndicator
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        // An indeterminate progress indicator has no value.
+        if let Some(progress) = self.progress {
+            let node = ctx.current_node();
+            node.set_numeric_value(progress * 100.0);
+            node.set_min_numeric_value(0.0);
+            node.set_max_numeric_value(100.0);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ProgressBar")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(match self.progress {
+            Some(progress) => format!("{:.0}%", progress * 100.0),
+            None => "indeterminate".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::event::WindowEvent;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    #[test]
+    fn simple_progress_bar() {
+        for (progress, name) in [
+            (Some(0.0), "progress_0"),
+            (Some(0.5), "progress_50"),
+            (Some(1.0), "progress_100"),
+            (None, "indeterminate"),
+        ] {
+            let widget = ProgressBar::new(progress);
+            let mut harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+            assert_render_snapshot!(harness, name);
+        }
+    }
+
+    #[test]
+    fn animation_steps() {
+        // A 750ms frame is half of the cycle.
+        assert_eq!(animation_step(0.0, 750_000_000), 0.5);
+        assert_eq!(animation_step(0.5, 375_000_000), 0.75);
+        // The segment starts over once it has left the bar.
+        assert_eq!(animation_step(0.75, 750_000_000), 0.25);
+    }
+
+    #[test]
+    fn indeterminate_animation() {
+        let widget = ProgressBar::new(None);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+        let anim_t = |harness: &TestHarness| {
+            let progress_bar = harness.root_widget();
+            progress_bar.downcast::<ProgressBar>().unwrap().anim_t
+        };
+
+        // The first frame only starts the clock.
+        let image_1 = harness.render();
+        harness.process_window_event(WindowEvent::AnimFrame);
+        assert_eq!(anim_t(&harness), 0.0);
+
+        std::thread::sleep(Duration::from_millis(50));
+        harness.process_window_event(WindowEvent::AnimFrame);
+        assert!(anim_t(&harness) > 0.0);
+        let image_2 = harness.render();
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 != image_2);
+
+        // The animation stops once the progress is known.
+        harness.edit_root_widget(|mut progress_bar| {
+            let mut progress_bar = progress_bar.downcast::<ProgressBar>().unwrap();
+            progress_bar.set_progress(Some(0.5));
+        });
+        let stopped_at = anim_t(&harness);
+        std::thread::sleep(Duration::from_millis(50));
+        harness.process_window_event(WindowEvent::AnimFrame);
+        assert_eq!(anim_t(&harness), stopped_at);
+    }
+
+    #[test]
+    fn indeterminate_progress_bar() {
+        let [progress_bar_id] = widget_ids();
+        let widget = ProgressBar::new(None).with_id(progress_bar_id);
+
+        let harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+
+        let progress_bar = harness.get_widget(progress_bar_id);
+        let progress_bar = progress_bar.downcast::<ProgressBar>().unwrap();
+        assert_eq!(progress_bar.progress(), None);
+        assert_eq!(
+            progress_bar.get_debug_text().as_deref(),
+            Some("indeterminate")
+        );
+    }
+
+    #[test]
+    fn determinate_progress_bar() {
+        let render = |progress| {
+            let widget = ProgressBar::new(progress);
+            let mut harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+            harness.render()
+        };
+
+        let empty = render(Some(0.0));
+        let quarter = render(Some(0.25));
+        let full = render(Some(1.0));
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(empty != quarter);
+        assert!(quarter != full);
+
+        // Out-of-range values are clamped.
+        assert!(render(Some(1.5)) == full);
+        assert!(render(Some(-0.5)) == empty);
+    }
+
+    #[test]
+    fn edit_progress_bar() {
+        let image_1 = {
+            let widget = ProgressBar::new(Some(0.5));
+
+            let mut harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+            harness.render()
+        };
+
+        let image_2 = {
+            let widget = ProgressBar::new(None);
+
+            let mut harness = TestHarness::create_with_size(widget, Size::new(150.0, 60.0));
+
+            harness.edit_root_widget(|mut progress_bar| {
+                let mut progress_bar = progress_bar.downcast::<ProgressBar>().unwrap();
+                progress_bar.set_progress(Some(0.5));
+            });
+
+            harness.render()
+        };
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 == image_2);
+    }
+}
@@ -0,0 +1,607 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A group of mutually exclusive options.
+
+use accesskit::{Checked, DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Circle, Point, Size};
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+/// The vertical space between two options.
+const OPTION_SPACING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+
+/// A group of labelled options, of which exactly one is selected.
+///
+/// The group is a single stop in the focus chain; when it's focused, the arrow
+/// keys move the selection between options.
+///
+/// Emits [`Action::RadioGroupSelected`] with the index of the option when the
+/// user selects one.
+pub struct RadioGroup {
+    options: Vec<WidgetPod<RadioOption>>,
+    selected: usize,
+    /// The option the pointer was pressed on.
+    pressed: Option<usize>,
+}
+
+crate::declare_widget!(RadioGroupMut, RadioGroup);
+
+/// A single option of a [`RadioGroup`].
+pub(crate) struct RadioOption {
+    selected: bool,
+    label: WidgetPod<Label>,
+}
+
+crate::declare_widget!(RadioOptionMut, RadioOption);
+
+// --- METHODS ---
+
+impl RadioGroup {
+    /// Create a new radio group with the given option labels.
+    ///
+    /// `selected` is clamped to the options.
+    pub fn new<T: Into<ArcStr>>(options: impl IntoIterator<Item = T>, selected: usize) -> Self {
+        let mut options: Vec<_> = options
+            .into_iter()
+            .map(|text| RadioOption::new(text.into()))
+            .collect();
+        let selected = selected.min(options.len().saturating_sub(1));
+        if let Some(option) = options.get_mut(selected) {
+            option.selected = true;
+        }
+        RadioGroup {
+            options: options.into_iter().map(WidgetPod::new).collect(),
+            selected,
+            pressed: None,
+        }
+    }
+
+    /// Return the index of the selected option.
+    ///
+    /// If the group has no options, this is 0.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Return the number of options.
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Return `true` if the group has no options.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Return the label of the option at `idx`.
+    pub fn option_text(&self, idx: usize) -> Option<ArcStr> {
+        self.options
+            .get(idx)
+            .map(|option| option.as_ref().widget().label.as_ref().text())
+    }
+
+    /// The index of the option under `pos`, in local coordinates.
+    fn option_at(&self, pos: Point) -> Option<usize> {
+        self.options
+            .iter()
+            .position(|option| option.layout_rect().contains(pos))
+    }
+
+    /// The option a key press selects, or `None` if the key doesn't change the selection.
+    ///
+    /// The arrow keys wrap around.
+    fn option_for_key(&self, key: &Key) -> Option<usize> {
+        let len = self.options.len();
+        if len == 0 {
+            return None;
+        }
+        match key {
+            Key::Named(NamedKey::ArrowDown | NamedKey::ArrowRight) => {
+                Some((self.selected + 1) % len)
+            }
+            Key::Named(NamedKey::ArrowUp | NamedKey::ArrowLeft) => {
+                Some((self.selected + len - 1) % len)
+            }
+            Key::Named(NamedKey::Home) => Some(0),
+            Key::Named(NamedKey::End) => Some(len - 1),
+            _ => None,
+        }
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx == self.selected || idx >= self.options.len() {
+            return;
+        }
+        ctx.get_mut(&mut self.options[self.selected])
+            .set_selected(false);
+        ctx.get_mut(&mut self.options[idx]).set_selected(true);
+        self.selected = idx;
+        trace!("RadioGroup {:?} selected option {}", ctx.widget_id(), idx);
+        ctx.submit_action(Action::RadioGroupSelected(idx));
+        ctx.request_paint();
+    }
+}
+
+impl RadioOption {
+    fn new(text: ArcStr) -> Self {
+        RadioOption {
+            selected: false,
+            label: WidgetPod::new(Label::new(text)),
+        }
+    }
+}
+
+impl RadioGroupMut<'_> {
+    /// Add an option at the end of the group.
+    pub fn add_option(&mut self, text: impl Into<ArcStr>) {
+        let mut option = RadioOption::new(text.into());
+        option.selected = self.widget.options.is_empty();
+        self.widget.options.push(WidgetPod::new(option));
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Remove the option at `idx`.
+    ///
+    /// If it was selected, the option before it is selected instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_option(&mut self, idx: usize) {
+        self.widget.options.remove(idx);
+        let selected = self.widget.selected;
+        if idx <= selected && selected > 0 {
+            self.widget.selected -= 1;
+        }
+        if idx == selected && !self.widget.options.is_empty() {
+            self.option_mut(self.widget.selected).set_selected(true);
+        }
+        self.widget.pressed = None;
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the label of the option at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_option_text(&mut self, idx: usize, text: impl Into<ArcStr>) {
+        let text = text.into();
+        let mut option = self.option_mut(idx);
+        option.ctx.request_accessibility_update();
+        option.label_mut().set_text(text);
+    }
+
+    /// Select the option at `idx`.
+    ///
+    /// This doesn't emit [`Action::RadioGroupSelected`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_selected(&mut self, idx: usize) {
+        assert!(
+            idx < self.widget.options.len(),
+            "option index {idx} out of bounds"
+        );
+        let old_selected = self.widget.selected;
+        if idx != old_selected {
+            self.option_mut(old_selected).set_selected(false);
+            self.option_mut(idx).set_selected(true);
+            self.widget.selected = idx;
+            self.ctx.request_paint();
+        }
+    }
+
+    fn option_mut(&mut self, idx: usize) -> WidgetMut<'_, RadioOption> {
+        self.ctx.get_mut(&mut self.widget.options[idx])
+    }
+}
+
+impl RadioOptionMut<'_> {
+    fn set_selected(&mut self, selected: bool) {
+        self.widget.selected = selected;
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    fn label_mut(&mut self) -> WidgetMut<'_, Label> {
+        self.ctx.get_mut(&mut self.widget.label)
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for RadioGroup {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for option in &mut self.options {
+            option.on_pointer_event(ctx, event);
+        }
+        match event {
+            PointerEvent::PointerDown(_, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                let local_pos =
+                    Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+                self.pressed = self.option_at(local_pos);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerUp(_, state) => {
+                if let (true, Some(pressed)) = (ctx.is_active(), self.pressed.take()) {
+                    let local_pos = Point::new(state.position.x, state.position.y)
+                        - ctx.window_origin().to_vec2();
+                    if !ctx.is_disabled() && self.option_at(local_pos) == Some(pressed) {
+                        ctx.request_focus();
+                        self.select(ctx, pressed);
+                    }
+                    ctx.request_paint();
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for option in &mut self.options {
+            option.on_text_event(ctx, event);
+        }
+        if ctx.is_disabled() || self.options.is_empty() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        let Some(idx) = self.option_for_key(&key_event.logical_key) else {
+            return;
+        };
+        self.select(ctx, idx);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for option in &mut self.options {
+            option.on_access_event(ctx, event);
+        }
+        if event.action != accesskit::Action::Default || ctx.is_disabled() {
+            return;
+        }
+        // Assistive technologies activate the options themselves.
+        let target = self
+            .options
+            .iter()
+            .position(|option| option.id() == event.target);
+        if let Some(idx) = target {
+            self.select(ctx, idx);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            if !self.options.is_empty() {
+                ctx.register_for_focus();
+            }
+        }
+        for option in &mut self.options {
+            option.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let option_bc = bc.loosen();
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        for (idx, option) in self.options.iter_mut().enumerate() {
+            if idx > 0 {
+                y += OPTION_SPACING;
+            }
+            let option_size = option.layout(ctx, &option_bc);
+            ctx.place_child(option, Point::new(0.0, y));
+            width = width.max(option_size.width);
+            y += option_size.height;
+        }
+
+        let size = bc.constrain(Size::new(width, y));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for option in &mut self.options {
+            option.paint(ctx, scene);
+        }
+
+        // The focus ring goes around the selected option's circle.
+        if ctx.is_focused() && !ctx.is_disabled() {
+            if let Some(option) = self.options.get(self.selected) {
+                let origin = option.layout_rect().origin();
+                let radius = theme::BASIC_WIDGET_HEIGHT / 2.0;
+                let ring = Circle::new(origin + (radius, radius), radius + 1.0);
+                stroke(scene, &ring, theme::PRIMARY_LIGHT, 1.0);
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::RadioGroup
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for option in &mut self.options {
+            option.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.options.iter().map(|option| option.as_dyn()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("RadioGroup")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("selected: {}", self.selected))
+    }
+}
+
+impl Widget for RadioOption {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+        // Only the hover state changes; the group handles clicks.
+        ctx.request_paint();
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let x_padding = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+        let circle_size = theme::BASIC_WIDGET_HEIGHT;
+
+        let label_size = self.label.layout(ctx, bc);
+        ctx.place_child(&mut self.label, (circle_size + x_padding, 0.0).into());
+
+        let desired_size = Size::new(
+            circle_size + x_padding + label_size.width,
+            circle_size.max(label_size.height),
+        );
+        let our_size = bc.constrain(desired_size);
+        let baseline = self.label.baseline_offset() + (our_size.height - label_size.height);
+        ctx.set_baseline_offset(baseline);
+        our_size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let radius = theme::BASIC_WIDGET_HEIGHT / 2.0;
+        let border_width = 1.;
+        let center = Point::new(radius, radius);
+
+        let circle = Circle::new(center, radius - border_width / 2.);
+        fill_lin_gradient(
+            scene,
+            &circle,
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_DARK],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &circle, border_color, border_width);
+
+        if self.selected {
+            let dot_color = if ctx.is_disabled() {
+                theme::DISABLED_TEXT_COLOR
+            } else {
+                theme::TEXT_COLOR
+            };
+            fill_color(scene, &Circle::new(center, radius / 2.), dot_color);
+        }
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::RadioButton
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_checked(if self.selected {
+            Checked::True
+        } else {
+            Checked::False
+        });
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("RadioOption")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!(
+            "({}) {}",
+            if self.selected { "*" } else { " " },
+            self.label.as_ref().text()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Flex;
+
+    fn selected_of(harness: &TestHarness, id: crate::WidgetId) -> usize {
+        harness
+            .get_widget(id)
+            .downcast::<RadioGroup>()
+            .unwrap()
+            .selected()
+    }
+
+    #[test]
+    fn keys() {
+        let named = Key::Named;
+        let group = RadioGroup::new(["Small", "Medium", "Large"], 0);
+        assert_eq!(group.option_for_key(&named(NamedKey::ArrowDown)), Some(1));
+        assert_eq!(group.option_for_key(&named(NamedKey::ArrowRight)), Some(1));
+        assert_eq!(group.option_for_key(&named(NamedKey::ArrowUp)), Some(2));
+        assert_eq!(group.option_for_key(&named(NamedKey::ArrowLeft)), Some(2));
+        assert_eq!(group.option_for_key(&named(NamedKey::Home)), Some(0));
+        assert_eq!(group.option_for_key(&named(NamedKey::End)), Some(2));
+        assert_eq!(group.option_for_key(&named(NamedKey::Space)), None);
+
+        let group = RadioGroup::new(["Small", "Medium", "Large"], 2);
+        assert_eq!(group.option_for_key(&named(NamedKey::ArrowDown)), Some(0));
+
+        let empty = RadioGroup::new(Vec::<ArcStr>::new(), 0);
+        assert_eq!(empty.option_for_key(&named(NamedKey::ArrowDown)), None);
+    }
+
+    #[test]
+    fn click_option() {
+        let [group_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(RadioGroup::new(["Small", "Medium", "Large"], 0), group_id);
+
+        let mut harness = TestHarness::create(widget);
+        let option_ids: Vec<_> = harness
+            .get_widget(group_id)
+            .children()
+            .iter()
+            .map(|option| option.id())
+            .collect();
+
+        harness.mouse_click_on(option_ids[2]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioGroupSelected(2), group_id))
+        );
+        assert_eq!(selected_of(&harness, group_id), 2);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(group_id));
+
+        // Clicking the selected option doesn't emit an action.
+        harness.mouse_click_on(option_ids[2]);
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn access_tree() {
+        let widget = RadioGroup::new(["Yes", "No"], 1);
+
+        let harness = TestHarness::create(widget);
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  RadioGroup
+    RadioButton checked=False
+      StaticText \"Yes\"
+    RadioButton checked=True
+      StaticText \"No\"
+"
+        );
+    }
+
+    #[test]
+    fn select_from_assistive_technology() {
+        let [group_id] = widget_ids();
+        let widget = RadioGroup::new(["Yes", "No"], 1).with_id(group_id);
+
+        let mut harness = TestHarness::create(widget);
+        let yes_id = harness.get_widget(group_id).children()[0].id();
+        harness.process_access_event(AccessEvent {
+            target: yes_id,
+            action: accesskit::Action::Default,
+            data: None,
+        });
+
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioGroupSelected(0), group_id))
+        );
+        assert_eq!(selected_of(&harness, group_id), 0);
+    }
+
+    #[test]
+    fn edit_radio_group() {
+        let widget = RadioGroup::new(["A", "B", "C"], 2);
+
+        let mut harness = TestHarness::create(widget);
+        let group_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut group = root.downcast::<RadioGroup>().unwrap();
+            group.remove_option(2);
+            group.add_option("D");
+            group.set_option_text(0, "Z");
+        });
+        assert_eq!(harness.pop_action(), None);
+
+        let group = harness.get_widget(group_id);
+        let group = group.downcast::<RadioGroup>().unwrap();
+        // Removing the selected option selects the one before it.
+        assert_eq!(group.selected(), 1);
+        assert_eq!(group.len(), 3);
+        assert_eq!(group.option_text(0).as_deref(), Some("Z"));
+        assert_eq!(group.option_text(2).as_deref(), Some("D"));
+
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<RadioGroup>().unwrap().set_selected(2);
+        });
+        assert_eq!(selected_of(&harness, group_id), 2);
+    }
+}
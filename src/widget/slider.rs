@@ -0,0 +1,612 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A slider widget.
+
+use accesskit::{ActionData, Orientation, Role};
+use kurbo::Circle;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Axis, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+const TRACK_THICKNESS: f64 = 4.0;
+const THUMB_DIAMETER: f64 = theme::BASIC_WIDGET_HEIGHT;
+const THUMB_BORDER_WIDTH: f64 = 2.0;
+/// How many steps PageUp and PageDown move by.
+const PAGE_STEPS: f64 = 10.0;
+/// The fraction of the range the keyboard moves by when there is no step.
+const DEFAULT_KEYBOARD_STEP: f64 = 0.01;
+
+/// A slider, allowing interactive selection of a value in a range.
+///
+/// Emits [`Action::SliderValueChanged`] when the user moves it.
+pub struct Slider {
+    value: f64,
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    axis: Axis,
+    /// While the thumb is dragged, where the pointer grabbed it, relative to its center.
+    grab_offset: Option<f64>,
+}
+
+crate::declare_widget!(SliderMut, Slider);
+
+// --- METHODS ---
+
+impl Slider {
+    /// Create a new horizontal slider, for values between `min` and `max`.
+    pub fn new(min: f64, max: f64, value: f64) -> Self {
+        let mut slider = Slider {
+            value,
+            min,
+            max,
+            step: None,
+            axis: Axis::Horizontal,
+            grab_offset: None,
+        };
+        slider.value = slider.constrain(value);
+        slider
+    }
+
+    /// Builder-style method for setting the step between values.
+    ///
+    /// The value is always `min` plus a multiple of the step. If the range isn't a
+    /// multiple of the step, the largest value is the last one below `max`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = Some(step).filter(|step| *step > 0.0);
+        self.value = self.constrain(self.value);
+        self
+    }
+
+    /// Builder-style method for setting the orientation.
+    ///
+    /// Vertical sliders have their minimum at the bottom.
+    pub fn with_axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Return the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Return the range of values, as `(min, max)`.
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+
+    /// Return the step between values, if any.
+    pub fn step(&self) -> Option<f64> {
+        self.step
+    }
+
+    /// Return the orientation.
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// Clamp `value` to the range, and round it to the nearest step within it.
+    fn constrain(&self, value: f64) -> f64 {
+        let max = self.max.max(self.min);
+        let value = value.clamp(self.min, max);
+        match self.step {
+            Some(step) => {
+                // The tolerance keeps eg 0.3 / 0.1 from landing just below 3 steps.
+                let last_step = ((max - self.min) / step + 1e-9).floor();
+                let steps = ((value - self.min) / step).round().min(last_step);
+                self.min + steps * step
+            }
+            None => value,
+        }
+    }
+
+    /// How far along the track the value is, from 0.0 to 1.0.
+    fn progress(&self) -> f64 {
+        let range = self.max - self.min;
+        if range > 0.0 {
+            ((self.value - self.min) / range).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The length the center of the thumb can travel along.
+    fn track_length(&self, size: Size) -> f64 {
+        (self.axis.major(size) - THUMB_DIAMETER).max(0.0)
+    }
+
+    /// The position of the center of the thumb along the major axis.
+    fn thumb_major_pos(&self, size: Size) -> f64 {
+        let progress = match self.axis {
+            Axis::Horizontal => self.progress(),
+            Axis::Vertical => 1.0 - self.progress(),
+        };
+        THUMB_DIAMETER / 2.0 + progress * self.track_length(size)
+    }
+
+    fn thumb_center(&self, size: Size) -> Point {
+        self.axis
+            .pack(self.thumb_major_pos(size), self.axis.minor(size) / 2.0)
+            .into()
+    }
+
+    /// The value for a thumb centered at `major_pos`.
+    fn value_for_major_pos(&self, size: Size, major_pos: f64) -> f64 {
+        let track_length = self.track_length(size);
+        if track_length <= 0.0 {
+            return self.value;
+        }
+        let progress = ((major_pos - THUMB_DIAMETER / 2.0) / track_length).clamp(0.0, 1.0);
+        let progress = match self.axis {
+            Axis::Horizontal => progress,
+            Axis::Vertical => 1.0 - progress,
+        };
+        self.min + progress * (self.max - self.min)
+    }
+
+    /// How much the arrow keys move the value by.
+    fn keyboard_step(&self) -> f64 {
+        self.step
+            .unwrap_or((self.max - self.min) * DEFAULT_KEYBOARD_STEP)
+    }
+
+    /// The value a key press moves the slider to, or `None` if the key doesn't move it.
+    fn value_for_key(&self, key: &Key) -> Option<f64> {
+        let step = self.keyboard_step();
+        let new_value = match key {
+            Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => self.value + step,
+            Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => self.value - step,
+            Key::Named(NamedKey::PageUp) => self.value + step * PAGE_STEPS,
+            Key::Named(NamedKey::PageDown) => self.value - step * PAGE_STEPS,
+            Key::Named(NamedKey::Home) => self.min,
+            Key::Named(NamedKey::End) => self.max,
+            _ => return None,
+        };
+        Some(self.constrain(new_value))
+    }
+
+    fn set_value(&mut self, ctx: &mut EventCtx, value: f64) {
+        let value = self.constrain(value);
+        if value != self.value {
+            self.value = value;
+            trace!("Slider {:?} moved to {}", ctx.widget_id(), value);
+            ctx.submit_action(Action::SliderValueChanged(value));
+            ctx.request_paint();
+            ctx.request_accessibility_update();
+        }
+    }
+}
+
+impl SliderMut<'_> {
+    /// Set the value.
+    ///
+    /// The value is clamped to the range and rounded to the step. This doesn't
+    /// emit [`Action::SliderValueChanged`].
+    pub fn set_value(&mut self, value: f64) {
+        self.widget.value = self.widget.constrain(value);
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the range of values.
+    pub fn set_range(&mut self, min: f64, max: f64) {
+        self.widget.min = min;
+        self.widget.max = max;
+        self.widget.value = self.widget.constrain(self.widget.value);
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the step between values, or remove it.
+    pub fn set_step(&mut self, step: Option<f64>) {
+        self.widget.step = step.filter(|step| *step > 0.0);
+        self.widget.value = self.widget.constrain(self.widget.value);
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the orientation.
+    pub fn set_axis(&mut self, axis: Axis) {
+        self.widget.axis = axis;
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Slider {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let size = ctx.size();
+        match event {
+            PointerEvent::PointerDown(_, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                ctx.request_focus();
+                ctx.set_active(true);
+
+                let local_pos =
+                    Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+                let thumb_center = self.thumb_center(size);
+                let grab_offset = if (local_pos - thumb_center).hypot() <= THUMB_DIAMETER / 2.0 {
+                    self.axis.major_vec(local_pos - thumb_center)
+                } else {
+                    // Clicking the track moves the thumb to the pointer.
+                    0.0
+                };
+                self.grab_offset = Some(grab_offset);
+
+                let major_pos = self.axis.major_pos(local_pos) - grab_offset;
+                self.set_value(ctx, self.value_for_major_pos(size, major_pos));
+                ctx.request_paint();
+            }
+            PointerEvent::PointerMove(state) if ctx.is_active() => {
+                if let Some(grab_offset) = self.grab_offset {
+                    let local_pos = Point::new(state.position.x, state.position.y)
+                        - ctx.window_origin().to_vec2();
+                    let major_pos = self.axis.major_pos(local_pos) - grab_offset;
+                    self.set_value(ctx, self.value_for_major_pos(size, major_pos));
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                self.grab_offset = None;
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if ctx.is_disabled() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        let Some(new_value) = self.value_for_key(&key_event.logical_key) else {
+            return;
+        };
+        self.set_value(ctx, new_value);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target != ctx.widget_id() || ctx.is_disabled() {
+            return;
+        }
+        let new_value = match (event.action, &event.data) {
+            (accesskit::Action::Increment, _) => self.value + self.keyboard_step(),
+            (accesskit::Action::Decrement, _) => self.value - self.keyboard_step(),
+            (accesskit::Action::SetValue, Some(ActionData::NumericValue(value))) => *value,
+            _ => return,
+        };
+        self.set_value(ctx, new_value);
+        ctx.set_handled();
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::DisabledChanged(_) => {
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let major = self.axis.major(bc.max());
+        let major = if major.is_finite() {
+            major
+        } else {
+            theme::WIDE_WIDGET_WIDTH
+        };
+        let size = bc.constrain(Size::from(self.axis.pack(major, THUMB_DIAMETER)));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let is_disabled = ctx.is_disabled();
+        let minor_center = self.axis.minor(size) / 2.0;
+        let thumb_pos = self.thumb_major_pos(size);
+
+        // The track spans between the centers of the thumb at each end.
+        let track_start = THUMB_DIAMETER / 2.0;
+        let track_end = self.axis.major(size) - THUMB_DIAMETER / 2.0;
+        let track_rect = |start: f64, end: f64| {
+            let p0 = self.axis.pack(start, minor_center - TRACK_THICKNESS / 2.0);
+            let p1 = self.axis.pack(end, minor_center + TRACK_THICKNESS / 2.0);
+            Rect::from_points(p0, p1).to_rounded_rect(TRACK_THICKNESS / 2.0)
+        };
+
+        fill_color(
+            scene,
+            &track_rect(track_start, track_end),
+            theme::BACKGROUND_LIGHT,
+        );
+
+        // Fill the track from the minimum to the thumb.
+        let filled_track = match self.axis {
+            Axis::Horizontal => track_rect(track_start, thumb_pos),
+            Axis::Vertical => track_rect(thumb_pos, track_end),
+        };
+        let fill = if is_disabled {
+            theme::DISABLED_FOREGROUND_DARK
+        } else {
+            theme::PRIMARY_DARK
+        };
+        fill_color(scene, &filled_track, fill);
+
+        let thumb = Circle::new(
+            self.thumb_center(size),
+            (THUMB_DIAMETER - THUMB_BORDER_WIDTH) / 2.0,
+        );
+        let (light, dark) = if is_disabled {
+            (
+                theme::DISABLED_FOREGROUND_LIGHT,
+                theme::DISABLED_FOREGROUND_DARK,
+            )
+        } else if ctx.is_active() {
+            (theme::FOREGROUND_DARK, theme::FOREGROUND_LIGHT)
+        } else {
+            (theme::FOREGROUND_LIGHT, theme::FOREGROUND_DARK)
+        };
+        fill_lin_gradient(
+            scene,
+            &thumb,
+            [light, dark],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        let border_color = if is_disabled {
+            theme::BORDER_DARK
+        } else if ctx.is_focused() {
+            theme::PRIMARY_LIGHT
+        } else if ctx.is_hot() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &thumb, border_color, THUMB_BORDER_WIDTH);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_numeric_value(self.value);
+        node.set_min_numeric_value(self.min);
+        node.set_max_numeric_value(self.max);
+        if let Some(step) = self.step {
+            node.set_numeric_value_step(step);
+        }
+        node.set_orientation(match self.axis {
+            Axis::Horizontal => Orientation::Horizontal,
+            Axis::Vertical => Orientation::Vertical,
+        });
+        node.add_action(accesskit::Action::Increment);
+        node.add_action(accesskit::Action::Decrement);
+        node.add_action(accesskit::Action::SetValue);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Slider")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    // The harness window is 400 wide, so the thumb's center travels from 9 to 391.
+    const TRACK_START: f64 = THUMB_DIAMETER / 2.0;
+    const TRACK_LENGTH: f64 = 400.0 - THUMB_DIAMETER;
+
+    fn value_of(harness: &TestHarness, id: crate::WidgetId) -> f64 {
+        harness.get_widget(id).downcast::<Slider>().unwrap().value()
+    }
+
+    #[test]
+    fn click_on_track() {
+        let [slider_id] = widget_ids();
+        let widget = Slider::new(0.0, 100.0, 0.0).with_id(slider_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(slider_id);
+
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::SliderValueChanged(50.0), slider_id))
+        );
+        assert_eq!(value_of(&harness, slider_id), 50.0);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(slider_id));
+    }
+
+    #[test]
+    fn drag_thumb() {
+        let [slider_id] = widget_ids();
+        let widget = Slider::new(0.0, 100.0, 0.0)
+            .with_step(5.0)
+            .with_id(slider_id);
+
+        let mut harness = TestHarness::create(widget);
+
+        // Grab the thumb a little right of its center.
+        harness.mouse_move((TRACK_START + 2.0, 200.0));
+        harness.mouse_button_press(winit::event::MouseButton::Left);
+        assert_eq!(value_of(&harness, slider_id), 0.0);
+
+        harness.mouse_move((TRACK_START + 2.0 + TRACK_LENGTH * 0.26, 200.0));
+        assert_eq!(value_of(&harness, slider_id), 25.0);
+
+        // Dragging past the end clamps the value.
+        harness.mouse_move((1000.0, 200.0));
+        assert_eq!(value_of(&harness, slider_id), 100.0);
+
+        harness.mouse_button_release(winit::event::MouseButton::Left);
+        harness.mouse_move((TRACK_START, 200.0));
+        assert_eq!(value_of(&harness, slider_id), 100.0);
+    }
+
+    #[test]
+    fn vertical_slider() {
+        let [slider_id] = widget_ids();
+        let widget = Slider::new(0.0, 1.0, 0.5)
+            .with_axis(Axis::Vertical)
+            .with_id(slider_id);
+
+        let mut harness = TestHarness::create(widget);
+
+        // The minimum is at the bottom.
+        harness.mouse_move((200.0, TRACK_START + TRACK_LENGTH * 0.75));
+        harness.mouse_button_press(winit::event::MouseButton::Left);
+        harness.mouse_button_release(winit::event::MouseButton::Left);
+        assert_eq!(value_of(&harness, slider_id), 0.25);
+    }
+
+    #[test]
+    fn access_actions() {
+        let [slider_id] = widget_ids();
+        let widget = Slider::new(0.0, 10.0, 5.0)
+            .with_step(1.0)
+            .with_id(slider_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.process_access_event(AccessEvent {
+            target: slider_id,
+            action: accesskit::Action::Increment,
+            data: None,
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::SliderValueChanged(6.0), slider_id))
+        );
+
+        harness.process_access_event(AccessEvent {
+            target: slider_id,
+            action: accesskit::Action::SetValue,
+            data: Some(ActionData::NumericValue(2.4)),
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::SliderValueChanged(2.0), slider_id))
+        );
+    }
+
+    #[test]
+    fn disabled_slider() {
+        let widget = Slider::new(0.0, 100.0, 0.0);
+
+        let mut harness = TestHarness::create(widget);
+        let slider_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut slider = root.downcast::<Slider>().unwrap();
+            slider.ctx.set_disabled(true);
+        });
+
+        harness.mouse_click_on(slider_id);
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(value_of(&harness, slider_id), 0.0);
+    }
+
+    #[test]
+    fn edit_slider() {
+        let widget = Slider::new(0.0, 100.0, 50.0);
+
+        let mut harness = TestHarness::create(widget);
+        let slider_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut slider = root.downcast::<Slider>().unwrap();
+            slider.set_range(0.0, 10.0);
+            slider.set_step(Some(0.5));
+            slider.set_value(3.3);
+        });
+
+        // Setting the value programmatically doesn't emit an action.
+        assert_eq!(harness.pop_action(), None);
+        let slider = harness.get_widget(slider_id);
+        let slider = slider.downcast::<Slider>().unwrap();
+        assert_eq!(slider.range(), (0.0, 10.0));
+        assert_eq!(slider.value(), 3.5);
+    }
+
+    #[test]
+    fn range_not_multiple_of_step() {
+        let slider = Slider::new(0.0, 10.0, 10.0).with_step(3.0);
+        // The largest value on the grid is 9, not 10 or 12.
+        assert_eq!(slider.value(), 9.0);
+        assert_eq!(slider.constrain(10.0), 9.0);
+        assert_eq!(slider.constrain(11.0), 9.0);
+        assert_eq!(slider.constrain(4.0), 3.0);
+        assert_eq!(slider.constrain(-1.0), 0.0);
+
+        let slider = Slider::new(0.0, 0.3, 0.3).with_step(0.1);
+        assert!((slider.value() - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keys() {
+        let named = Key::Named;
+        let slider = Slider::new(0.0, 10.0, 3.0).with_step(3.0);
+        assert_eq!(
+            slider.value_for_key(&named(NamedKey::ArrowRight)),
+            Some(6.0)
+        );
+        assert_eq!(slider.value_for_key(&named(NamedKey::ArrowUp)), Some(6.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::ArrowLeft)), Some(0.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::ArrowDown)), Some(0.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::PageUp)), Some(9.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::PageDown)), Some(0.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::Home)), Some(0.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::End)), Some(9.0));
+        assert_eq!(slider.value_for_key(&named(NamedKey::Enter)), None);
+        assert_eq!(slider.value_for_key(&Key::Character("a".into())), None);
+
+        // Without a step, the arrows move by a hundredth of the range.
+        let slider = Slider::new(0.0, 200.0, 100.0);
+        assert_eq!(
+            slider.value_for_key(&named(NamedKey::ArrowRight)),
+            Some(102.0)
+        );
+        assert_eq!(slider.value_for_key(&named(NamedKey::PageDown)), Some(80.0));
+    }
+}
@@ -4,10 +4,11 @@
 
 //! A widget which splits an area in two, with a settable ratio, and optional draggable resizing.
 
+use accesskit::Role;
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, warn, Span};
 use vello::Scene;
-use winit::dpi::PhysicalPosition;
+use winit::dpi::LogicalPosition;
 use winit::event::MouseButton;
 use winit::window::CursorIcon;
 
@@ -16,12 +17,15 @@ use crate::paint_scene_helpers::{fill_color, stroke};
 use crate::widget::flex::Axis;
 use crate::widget::{WidgetPod, WidgetRef};
 use crate::{
-    theme, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
-    PointerEvent, Rect, Size, StatusChange, TextEvent, Widget,
+    theme, AccessCtx, AccessEvent, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget,
 };
 
 // TODO - Have child widget type as generic argument
 
+/// How much the split point moves when an assistive technology increments or decrements it.
+const SPLIT_POINT_ACCESS_STEP: f64 = 0.05;
+
 /// A container containing two other widgets, splitting the area either horizontally or vertically.
 pub struct Split {
     split_axis: Axis,
@@ -32,19 +36,87 @@ pub struct Split {
     min_bar_area: f64,    // Integers only
     solid: bool,
     draggable: bool,
-    /// The split bar is hovered by the mouse. This state is locked to `true` if the
+    bar_drag: BarDrag,
+    child1: WidgetPod<Box<dyn Widget>>,
+    child2: WidgetPod<Box<dyn Widget>>,
+}
+crate::declare_widget!(SplitMut, Split);
+
+/// The state of a bar which can be dragged along an axis to resize something.
+///
+/// This holds the logic shared by widgets with draggable dividers, like [`Split`]
+/// and [`Table`](super::Table). The widget is responsible for hit-testing its bars
+/// and for deciding what a new bar position means.
+#[derive(Default)]
+pub(crate) struct BarDrag {
+    /// The bar is hovered by the mouse. This state is locked to `true` if the
     /// widget is active (the bar is being dragged) to avoid cursor and painting jitter
     /// if the mouse moves faster than the layout and temporarily gets outside of the
     /// bar area while still being dragged.
-    is_bar_hover: bool,
-    /// Offset from the split point (bar center) to the actual mouse position when the
-    /// bar was clicked. This is used to ensure a click without mouse move is a no-op,
+    is_hover: bool,
+    /// Offset from the bar center to the actual mouse position when the bar was
+    /// clicked. This is used to ensure a click without mouse move is a no-op,
     /// instead of re-centering the bar on the mouse.
     click_offset: f64,
-    child1: WidgetPod<Box<dyn Widget>>,
-    child2: WidgetPod<Box<dyn Widget>>,
 }
-crate::declare_widget!(SplitMut, Split);
+
+impl BarDrag {
+    fn set_cursor(ctx: &mut EventCtx, axis: Axis) {
+        match axis {
+            Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
+            Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
+        };
+    }
+
+    /// Start dragging a bar whose center is at `bar_position` along `axis`.
+    pub(crate) fn start(
+        &mut self,
+        ctx: &mut EventCtx,
+        axis: Axis,
+        mouse_pos: Point,
+        bar_position: f64,
+    ) {
+        ctx.set_handled();
+        ctx.set_active(true);
+        // Save the delta between the mouse click position and the bar center
+        self.click_offset = axis.major_pos(mouse_pos) - bar_position;
+        // If not already hovering, force and change cursor appropriately
+        if !self.is_hover {
+            self.is_hover = true;
+            Self::set_cursor(ctx, axis);
+        }
+    }
+
+    /// Stop dragging. `over_bar` is whether the mouse is still over a bar.
+    pub(crate) fn end(&mut self, ctx: &mut EventCtx, over_bar: bool) {
+        ctx.set_handled();
+        ctx.set_active(false);
+        // Dependending on where the mouse cursor is when the button is released,
+        // the cursor might or might not need to be changed
+        self.is_hover = ctx.is_hot() && over_bar;
+        if !self.is_hover {
+            ctx.clear_cursor();
+        }
+    }
+
+    /// Return the position along `axis` the bar center is dragged to.
+    pub(crate) fn dragged_position(&self, axis: Axis, mouse_pos: Point) -> f64 {
+        axis.major_pos(mouse_pos) - self.click_offset
+    }
+
+    /// Update the cursor when the mouse moves while no bar is dragged.
+    pub(crate) fn hover(&mut self, ctx: &mut EventCtx, axis: Axis, over_bar: bool) {
+        let hover = ctx.is_hot() && over_bar;
+        if self.is_hover != hover {
+            self.is_hover = hover;
+            if hover {
+                Self::set_cursor(ctx, axis);
+            } else {
+                ctx.clear_cursor();
+            }
+        }
+    }
+}
 
 impl Split {
     /// Create a new split panel, with the specified axis being split in two.
@@ -61,8 +133,7 @@ impl Split {
             min_bar_area: 6.0,
             solid: false,
             draggable: false,
-            is_bar_hover: false,
-            click_offset: 0.0,
+            bar_drag: BarDrag::default(),
             child1: WidgetPod::new(child1).boxed(),
             child2: WidgetPod::new(child2).boxed(),
         }
@@ -197,7 +268,7 @@ impl Split {
     }
 
     /// Returns true if the provided mouse position is inside the splitter bar area.
-    fn bar_hit_test(&self, size: Size, mouse_pos: PhysicalPosition<f64>) -> bool {
+    fn bar_hit_test(&self, size: Size, mouse_pos: LogicalPosition<f64>) -> bool {
         let (edge1, edge2) = self.bar_edges(size);
         match self.split_axis {
             Axis::Horizontal => mouse_pos.x >= edge1 && mouse_pos.x <= edge2,
@@ -220,13 +291,11 @@ impl Split {
         (min_limit, max_limit)
     }
 
-    /// Set a new chosen split point.
-    fn update_split_point(&mut self, size: Size, mouse_pos: Point) {
+    /// Set a new chosen split point, from a position along the split axis.
+    fn update_split_point(&mut self, size: Size, split_pos: f64) {
         let (min_limit, max_limit) = self.split_side_limits(size);
-        self.split_point_chosen = match self.split_axis {
-            Axis::Horizontal => mouse_pos.x.clamp(min_limit, max_limit) / size.width,
-            Axis::Vertical => mouse_pos.y.clamp(min_limit, max_limit) / size.height,
-        }
+        self.split_point_chosen =
+            split_pos.clamp(min_limit, max_limit) / self.split_axis.major(size);
     }
 
     /// Returns the color of the splitter bar.
@@ -369,63 +438,29 @@ impl Widget for Split {
             match event {
                 PointerEvent::PointerDown(MouseButton::Left, state) => {
                     if self.bar_hit_test(ctx.size(), state.position) {
-                        ctx.set_handled();
-                        ctx.set_active(true);
-                        // Save the delta between the mouse click position and the split point
-                        self.click_offset = match self.split_axis {
-                            Axis::Horizontal => state.position.x,
-                            Axis::Vertical => state.position.y,
-                        } - self.bar_position(ctx.size());
-                        // If not already hovering, force and change cursor appropriately
-                        if !self.is_bar_hover {
-                            self.is_bar_hover = true;
-                            match self.split_axis {
-                                Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
-                                Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
-                            };
-                        }
+                        let bar_position = self.bar_position(ctx.size());
+                        let mouse_pos = Point::new(state.position.x, state.position.y);
+                        self.bar_drag
+                            .start(ctx, self.split_axis, mouse_pos, bar_position);
                     }
                 }
                 PointerEvent::PointerUp(MouseButton::Left, state) => {
                     if ctx.is_active() {
-                        ctx.set_handled();
-                        ctx.set_active(false);
-                        // Dependending on where the mouse cursor is when the button is released,
-                        // the cursor might or might not need to be changed
-                        self.is_bar_hover =
-                            ctx.is_hot() && self.bar_hit_test(ctx.size(), state.position);
-                        if !self.is_bar_hover {
-                            ctx.clear_cursor()
-                        }
+                        let over_bar = self.bar_hit_test(ctx.size(), state.position);
+                        self.bar_drag.end(ctx, over_bar);
                     }
                 }
                 PointerEvent::PointerMove(state) => {
                     if ctx.is_active() {
                         // If active, assume always hover/hot
-                        let effective_pos = match self.split_axis {
-                            Axis::Horizontal => {
-                                Point::new(state.position.x - self.click_offset, state.position.y)
-                            }
-                            Axis::Vertical => {
-                                Point::new(state.position.x, state.position.y - self.click_offset)
-                            }
-                        };
-                        self.update_split_point(ctx.size(), effective_pos);
+                        let mouse_pos = Point::new(state.position.x, state.position.y);
+                        let split_pos = self.bar_drag.dragged_position(self.split_axis, mouse_pos);
+                        self.update_split_point(ctx.size(), split_pos);
                         ctx.request_layout();
                     } else {
                         // If not active, set cursor when hovering state changes
-                        let hover = ctx.is_hot() && self.bar_hit_test(ctx.size(), state.position);
-                        if self.is_bar_hover != hover {
-                            self.is_bar_hover = hover;
-                            if hover {
-                                match self.split_axis {
-                                    Axis::Horizontal => ctx.set_cursor(&CursorIcon::EwResize),
-                                    Axis::Vertical => ctx.set_cursor(&CursorIcon::NsResize),
-                                };
-                            } else {
-                                ctx.clear_cursor();
-                            }
-                        }
+                        let over_bar = self.bar_hit_test(ctx.size(), state.position);
+                        self.bar_drag.hover(ctx, self.split_axis, over_bar);
                     }
                 }
                 _ => {}
@@ -445,6 +480,23 @@ impl Widget for Split {
         self.child2.on_text_event(ctx, event);
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id() && self.draggable {
+            let step = match event.action {
+                accesskit::Action::Increment => Some(SPLIT_POINT_ACCESS_STEP),
+                accesskit::Action::Decrement => Some(-SPLIT_POINT_ACCESS_STEP),
+                _ => None,
+            };
+            if let Some(step) = step {
+                self.split_point_chosen = (self.split_point_effective + step).clamp(0.0, 1.0);
+                ctx.request_layout();
+                ctx.set_handled();
+            }
+        }
+        self.child1.on_access_event(ctx, event);
+        self.child2.on_access_event(ctx, event);
+    }
+
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
@@ -560,6 +612,25 @@ impl Widget for Split {
         self.child2.paint(ctx, scene);
     }
 
+    fn accessibility_role(&self) -> Role {
+        Role::Splitter
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_numeric_value(self.split_point_effective);
+        node.set_min_numeric_value(0.0);
+        node.set_max_numeric_value(1.0);
+        node.set_numeric_value_step(SPLIT_POINT_ACCESS_STEP);
+        if self.draggable {
+            node.add_action(accesskit::Action::Increment);
+            node.add_action(accesskit::Action::Decrement);
+        }
+
+        self.child1.accessibility(ctx);
+        self.child2.accessibility(ctx);
+    }
+
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
         smallvec![self.child1.as_dyn(), self.child2.as_dyn()]
     }
@@ -0,0 +1,398 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! An on/off switch widget.
+
+use accesskit::{Checked, DefaultActionVerb, Role};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Circle, Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::WidgetRef;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+/// How long the thumb takes to slide from one end to the other, in seconds.
+const TOGGLE_DURATION: f64 = 0.15;
+const SWITCH_HEIGHT: f64 = theme::BASIC_WIDGET_HEIGHT;
+const SWITCH_WIDTH: f64 = SWITCH_HEIGHT * 2.0;
+
+/// A switch that can be turned on and off.
+///
+/// The thumb slides to its new position when the switch is toggled.
+///
+/// Emits [`Action::CheckboxChecked`] with the new state when the user toggles it.
+pub struct Switch {
+    on: bool,
+    /// The position of the thumb, from 0 (off) to 1 (on).
+    thumb_t: f64,
+}
+
+crate::declare_widget!(SwitchMut, Switch);
+
+// --- METHODS ---
+
+impl Switch {
+    /// Create a new switch.
+    pub fn new(on: bool) -> Switch {
+        Switch {
+            on,
+            thumb_t: if on { 1.0 } else { 0.0 },
+        }
+    }
+
+    /// Return `true` if the switch is on.
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    fn target_t(&self) -> f64 {
+        if self.on {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn toggle(&mut self, ctx: &mut EventCtx) {
+        self.on = !self.on;
+        trace!("Switch {:?} turned {}", ctx.widget_id(), self.on);
+        ctx.submit_action(Action::CheckboxChecked(self.on));
+        ctx.request_anim_frame();
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+}
+
+/// How the switch reacts to a key event: `None` if it ignores it, otherwise whether
+/// the switch toggles.
+///
+/// Holding Space down toggles the switch once; the repeats are swallowed.
+fn toggle_for_key(key: &Key, state: ElementState, repeat: bool) -> Option<bool> {
+    if state != ElementState::Pressed || *key != Key::Named(NamedKey::Space) {
+        return None;
+    }
+    Some(!repeat)
+}
+
+impl SwitchMut<'_> {
+    /// Turn the switch on or off.
+    ///
+    /// The thumb jumps to its new position without animating, and no action is emitted.
+    pub fn set_on(&mut self, on: bool) {
+        self.widget.on = on;
+        self.widget.thumb_t = self.widget.target_t();
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Switch {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(_, _) => {
+                if !ctx.is_disabled() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                    trace!("Switch {:?} pressed", ctx.widget_id());
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if ctx.is_active() && !ctx.is_disabled() {
+                    if ctx.is_hot() {
+                        self.toggle(ctx);
+                        trace!("Switch {:?} released", ctx.widget_id());
+                    }
+                    ctx.request_paint();
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if ctx.is_disabled() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        let Some(toggles) =
+            toggle_for_key(&key_event.logical_key, key_event.state, key_event.repeat)
+        else {
+            return;
+        };
+        if toggles {
+            self.toggle(ctx);
+        }
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::Default
+            && !ctx.is_disabled()
+        {
+            self.toggle(ctx);
+            trace!("Switch {:?} activated", ctx.widget_id());
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::DisabledChanged(_) => {
+                ctx.request_paint();
+            }
+            LifeCycle::AnimFrame(interval) => {
+                let target = self.target_t();
+                if self.thumb_t != target {
+                    let step = (*interval as f64) * 1e-9 / TOGGLE_DURATION;
+                    self.thumb_t = if target > self.thumb_t {
+                        (self.thumb_t + step).min(target)
+                    } else {
+                        (self.thumb_t - step).max(target)
+                    };
+                    if self.thumb_t != target {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.constrain(Size::new(SWITCH_WIDTH, SWITCH_HEIGHT));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let is_active = ctx.is_active() && !ctx.is_disabled();
+        let border_width = 1.;
+        let size = ctx.size();
+        let radius = size.height / 2.0;
+
+        let track = size
+            .to_rect()
+            .inset(-border_width / 2.)
+            .to_rounded_rect(radius);
+        fill_lin_gradient(
+            scene,
+            &track,
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_DARK],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        // The part of the track behind and to the left of the thumb is filled.
+        let thumb_center = Point::new(radius + self.thumb_t * (size.width - 2.0 * radius), radius);
+        if self.thumb_t > 0.0 {
+            let fill_colors = if ctx.is_disabled() {
+                [
+                    theme::DISABLED_FOREGROUND_DARK,
+                    theme::DISABLED_FOREGROUND_DARK,
+                ]
+            } else {
+                [theme::PRIMARY_LIGHT, theme::PRIMARY_DARK]
+            };
+            let filled = Rect::new(0.0, 0.0, thumb_center.x + radius, size.height)
+                .inset(-border_width / 2.)
+                .to_rounded_rect(radius);
+            fill_lin_gradient(
+                scene,
+                &filled,
+                fill_colors,
+                UnitPoint::TOP,
+                UnitPoint::BOTTOM,
+            );
+        }
+
+        let border_color = if ctx.is_focused() && !ctx.is_disabled() {
+            theme::PRIMARY_LIGHT
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &track, border_color, border_width);
+
+        let thumb = Circle::new(thumb_center, radius - 2.0);
+        let thumb_colors = if ctx.is_disabled() {
+            [
+                theme::DISABLED_FOREGROUND_LIGHT,
+                theme::DISABLED_FOREGROUND_DARK,
+            ]
+        } else if is_active {
+            [theme::FOREGROUND_DARK, theme::FOREGROUND_LIGHT]
+        } else {
+            [theme::FOREGROUND_LIGHT, theme::FOREGROUND_DARK]
+        };
+        fill_lin_gradient(
+            scene,
+            &thumb,
+            thumb_colors,
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Switch
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_checked(if self.on {
+            Checked::True
+        } else {
+            Checked::False
+        });
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Switch")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(if self.on { "on" } else { "off" }.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    #[test]
+    fn keys() {
+        let space = Key::Named(NamedKey::Space);
+        assert_eq!(
+            toggle_for_key(&space, ElementState::Pressed, false),
+            Some(true)
+        );
+        assert_eq!(
+            toggle_for_key(&space, ElementState::Pressed, true),
+            Some(false)
+        );
+        assert_eq!(toggle_for_key(&space, ElementState::Released, false), None);
+        let enter = Key::Named(NamedKey::Enter);
+        assert_eq!(toggle_for_key(&enter, ElementState::Pressed, false), None);
+    }
+
+    #[test]
+    fn click_switch() {
+        let [switch_id] = widget_ids();
+        let widget = Switch::new(false).with_id(switch_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_click_on(switch_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CheckboxChecked(true), switch_id))
+        );
+
+        harness.mouse_click_on(switch_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CheckboxChecked(false), switch_id))
+        );
+    }
+
+    #[test]
+    fn switch_access() {
+        let [switch_id] = widget_ids();
+        let widget = Switch::new(false).with_id(switch_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "Window\n  Switch checked=False\n"
+        );
+
+        harness.process_access_event(AccessEvent {
+            target: switch_id,
+            action: accesskit::Action::Default,
+            data: None,
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CheckboxChecked(true), switch_id))
+        );
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "Window\n  Switch checked=True\n"
+        );
+    }
+
+    #[test]
+    fn disabled_switch() {
+        let widget = Switch::new(false);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+        let switch_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut switch = root.downcast::<Switch>().unwrap();
+            switch.ctx.set_disabled(true);
+        });
+
+        harness.mouse_click_on(switch_id);
+        assert_eq!(harness.pop_action(), None);
+        let switch = harness.get_widget(switch_id);
+        assert!(!switch.downcast::<Switch>().unwrap().is_on());
+    }
+
+    #[test]
+    fn edit_switch() {
+        let image_1 = {
+            let widget = Switch::new(true);
+
+            let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+            harness.render()
+        };
+
+        let image_2 = {
+            let widget = Switch::new(false);
+
+            let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+
+            harness.edit_root_widget(|mut switch| {
+                let mut switch = switch.downcast::<Switch>().unwrap();
+                switch.set_on(true);
+            });
+
+            harness.render()
+        };
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 == image_2);
+    }
+}
@@ -0,0 +1,1113 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A table with a fixed header row and a scrolling body.
+
+use accesskit::{ActionData, NodeBuilder, NodeId, Role};
+use kurbo::{Affine, BezPath, Line};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, warn, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::dpi::LogicalPosition;
+use winit::event::{ElementState, MouseButton};
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_color, fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::split::BarDrag;
+use crate::widget::{Axis, Label, ScrollBar, WidgetMut, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+const DEFAULT_COLUMN_WIDTH: f64 = theme::WIDE_WIDGET_WIDTH;
+const MIN_COLUMN_WIDTH: f64 = 20.0;
+const CELL_PADDING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+/// The width of the area around a column divider where it can be grabbed.
+const DIVIDER_AREA: f64 = 6.0;
+/// The width of the space kept in each header for the sort indicator.
+const SORT_INDICATOR_WIDTH: f64 = 10.0;
+
+/// The order rows are sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest values first.
+    Ascending,
+    /// Largest values first.
+    Descending,
+}
+
+/// A table of widgets, with a header row.
+///
+/// The header stays in place while the rows below it scroll. Columns can be resized
+/// by dragging the dividers between their headers.
+///
+/// The table doesn't know anything about the data its cells display. Clicking a
+/// header emits [`Action::TableSortRequested`] with the column and the order the
+/// user asked for; sorting the rows is up to the app, eg by replacing them.
+///
+/// Clicking a row, or moving the selection with the arrow keys while the table is
+/// focused, selects it and emits [`Action::TableRowSelected`].
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Row>,
+    /// The id of the accessibility node grouping the headers.
+    header_row_node_id: NodeId,
+    /// The top of each row in the body, plus the bottom of the last row,
+    /// as of the last layout.
+    row_edges: Vec<f64>,
+    header_height: f64,
+    body_height: f64,
+    viewport_pos: f64,
+    selected_row: Option<usize>,
+    sort: Option<(usize, SortOrder)>,
+    pressed_header: Option<usize>,
+    /// The column whose right divider is being dragged.
+    resized_column: Option<usize>,
+    bar_drag: BarDrag,
+    scrollbar: WidgetPod<ScrollBar>,
+    scrollbar_visible: bool,
+}
+
+crate::declare_widget!(TableMut, Table);
+
+struct Column {
+    header: WidgetPod<Label>,
+    width: f64,
+}
+
+struct Row {
+    cells: Vec<WidgetPod<Box<dyn Widget>>>,
+    /// The id of the accessibility node grouping the cells.
+    ///
+    /// Rows aren't widgets, so it's taken from the widget id counter to avoid clashes.
+    node_id: NodeId,
+}
+
+// --- METHODS ---
+
+impl SortOrder {
+    /// Return the opposite order.
+    pub fn reversed(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+}
+
+impl Table {
+    /// Create a new table without rows, with a column for each header.
+    pub fn new<T: Into<ArcStr>>(headers: impl IntoIterator<Item = T>) -> Self {
+        Table {
+            columns: headers
+                .into_iter()
+                .map(|text| Column {
+                    header: WidgetPod::new(Label::new(text)),
+                    width: DEFAULT_COLUMN_WIDTH,
+                })
+                .collect(),
+            rows: Vec::new(),
+            header_row_node_id: NodeId::from(WidgetId::next()),
+            row_edges: vec![0.0],
+            header_height: 0.0,
+            body_height: 0.0,
+            viewport_pos: 0.0,
+            selected_row: None,
+            sort: None,
+            pressed_header: None,
+            resized_column: None,
+            bar_drag: BarDrag::default(),
+            scrollbar: WidgetPod::new(ScrollBar::new(Axis::Vertical, 1.0, 1.0)),
+            scrollbar_visible: false,
+        }
+    }
+
+    /// Builder-style method to set the width of a column.
+    ///
+    /// The default width is 100 pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` is out of bounds.
+    pub fn with_column_width(mut self, column: usize, width: f64) -> Self {
+        self.columns[column].width = width.max(MIN_COLUMN_WIDTH);
+        self
+    }
+
+    /// Builder-style method to add a row of cells.
+    ///
+    /// If there are fewer cells than columns, the last columns of the row are left empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more cells than columns.
+    pub fn with_row(mut self, cells: impl IntoIterator<Item = Box<dyn Widget>>) -> Self {
+        let row = self.make_row(cells);
+        self.rows.push(row);
+        self
+    }
+
+    /// Builder-style method to add a row of labels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more cells than columns.
+    pub fn with_text_row<T: Into<ArcStr>>(self, cells: impl IntoIterator<Item = T>) -> Self {
+        self.with_row(text_cells(cells))
+    }
+
+    /// Builder-style method to set the sort indicator.
+    ///
+    /// This only changes which header shows an indicator; rows are not reordered.
+    pub fn with_sort(mut self, column: usize, order: SortOrder) -> Self {
+        self.sort = Some((column, order));
+        self
+    }
+
+    /// Return the number of columns.
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Return the width of a column, or `None` if it's out of bounds.
+    pub fn column_width(&self, column: usize) -> Option<f64> {
+        self.columns.get(column).map(|column| column.width)
+    }
+
+    /// Return the number of rows, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Return the index of the selected row, if any.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected_row
+    }
+
+    /// Return the column the sort indicator is shown on, and its order.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    fn make_row(&self, cells: impl IntoIterator<Item = Box<dyn Widget>>) -> Row {
+        let cells: Vec<_> = cells.into_iter().map(WidgetPod::new).collect();
+        assert!(
+            cells.len() <= self.columns.len(),
+            "a table row has {} cells, but the table only has {} columns",
+            cells.len(),
+            self.columns.len()
+        );
+        Row {
+            cells,
+            node_id: NodeId::from(WidgetId::next()),
+        }
+    }
+
+    fn column_left(&self, column: usize) -> f64 {
+        self.columns[..column]
+            .iter()
+            .map(|column| column.width)
+            .sum()
+    }
+
+    fn content_height(&self) -> f64 {
+        self.row_edges.last().copied().unwrap_or(0.0)
+    }
+
+    fn max_viewport_pos(&self) -> f64 {
+        (self.content_height() - self.body_height).max(0.0)
+    }
+
+    /// Return the column whose right divider is at `pos`, if any.
+    fn divider_at(&self, pos: Point) -> Option<usize> {
+        if !(0.0..self.header_height).contains(&pos.y) {
+            return None;
+        }
+        let mut right = 0.0;
+        self.columns.iter().position(|column| {
+            right += column.width;
+            (pos.x - right).abs() <= DIVIDER_AREA / 2.0
+        })
+    }
+
+    fn header_at(&self, pos: Point) -> Option<usize> {
+        if !(0.0..self.header_height).contains(&pos.y) || pos.x < 0.0 {
+            return None;
+        }
+        let mut right = 0.0;
+        self.columns.iter().position(|column| {
+            right += column.width;
+            pos.x < right
+        })
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        if pos.y < self.header_height {
+            return None;
+        }
+        let content_y = pos.y - self.header_height + self.viewport_pos;
+        let row = self
+            .row_edges
+            .partition_point(|&top| top <= content_y)
+            .checked_sub(1)?;
+        (row < self.rows.len()).then_some(row)
+    }
+
+    fn set_viewport_pos_raw(&mut self, pos: f64) -> bool {
+        let pos = pos.clamp(0.0, self.max_viewport_pos());
+        if (pos - self.viewport_pos).abs() > 1e-6 {
+            self.viewport_pos = pos;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Scroll the least amount needed for a row to be entirely visible.
+    fn scroll_to_row(&mut self, row: usize) -> bool {
+        // The row may have been added since the last layout.
+        let (Some(&top), Some(&bottom)) = (self.row_edges.get(row), self.row_edges.get(row + 1))
+        else {
+            return false;
+        };
+        if top < self.viewport_pos {
+            self.set_viewport_pos_raw(top)
+        } else if bottom > self.viewport_pos + self.body_height {
+            self.set_viewport_pos_raw(bottom - self.body_height)
+        } else {
+            false
+        }
+    }
+
+    /// The row a key press selects, or `None` if the key doesn't move the selection.
+    ///
+    /// Without a selection, the arrows select the first row.
+    fn row_for_key(&self, key: &Key) -> Option<usize> {
+        let last = self.rows.len().checked_sub(1)?;
+        match (key, self.selected_row) {
+            (Key::Named(NamedKey::ArrowDown), Some(row)) => Some((row + 1).min(last)),
+            (Key::Named(NamedKey::ArrowUp), Some(row)) => Some(row.saturating_sub(1)),
+            (Key::Named(NamedKey::ArrowDown | NamedKey::ArrowUp), None) => Some(0),
+            (Key::Named(NamedKey::Home), _) => Some(0),
+            (Key::Named(NamedKey::End), _) => Some(last),
+            _ => None,
+        }
+    }
+
+    fn select_row(&mut self, ctx: &mut EventCtx, row: usize) {
+        if self.scroll_to_row(row) {
+            ctx.request_layout();
+        }
+        if self.selected_row == Some(row) {
+            return;
+        }
+        self.selected_row = Some(row);
+        trace!("Table {:?} selected row {}", ctx.widget_id(), row);
+        ctx.submit_action(Action::TableRowSelected(row));
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+
+    fn request_sort(&mut self, ctx: &mut EventCtx, column: usize) {
+        let order = match self.sort {
+            Some((sorted_column, order)) if sorted_column == column => order.reversed(),
+            _ => SortOrder::Ascending,
+        };
+        self.sort = Some((column, order));
+        trace!("Table {:?} sorted by column {}", ctx.widget_id(), column);
+        ctx.submit_action(Action::TableSortRequested(column, order));
+        ctx.request_paint();
+    }
+
+    fn paint_sort_indicator(&self, scene: &mut Scene, column: usize, order: SortOrder) {
+        let right = self.column_left(column + 1) - CELL_PADDING;
+        let center_y = self.header_height / 2.0;
+        let half_width = SORT_INDICATOR_WIDTH / 2.0 - 1.0;
+        let half_height = half_width / 2.0;
+        let (tip, base) = match order {
+            SortOrder::Ascending => (center_y - half_height, center_y + half_height),
+            SortOrder::Descending => (center_y + half_height, center_y - half_height),
+        };
+
+        let mut triangle = BezPath::new();
+        triangle.move_to((right - half_width, tip));
+        triangle.line_to((right - 2.0 * half_width, base));
+        triangle.line_to((right, base));
+        triangle.close_path();
+        fill_color(scene, &triangle, theme::FOREGROUND_DARK);
+    }
+}
+
+fn text_cells<T: Into<ArcStr>>(
+    cells: impl IntoIterator<Item = T>,
+) -> impl Iterator<Item = Box<dyn Widget>> {
+    cells
+        .into_iter()
+        .map(|text| Box::new(Label::new(text)) as Box<dyn Widget>)
+}
+
+impl TableMut<'_> {
+    /// Add a row of cells at the bottom.
+    ///
+    /// See also [`with_row`](Table::with_row).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more cells than columns.
+    pub fn add_row(&mut self, cells: impl IntoIterator<Item = Box<dyn Widget>>) {
+        let idx = self.widget.rows.len();
+        self.insert_row(idx, cells);
+    }
+
+    /// Add a row of labels at the bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more cells than columns.
+    pub fn add_text_row<T: Into<ArcStr>>(&mut self, cells: impl IntoIterator<Item = T>) {
+        self.add_row(text_cells(cells));
+    }
+
+    /// Insert a row of cells at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is greater than the number of rows, or if there are more
+    /// cells than columns.
+    pub fn insert_row(&mut self, idx: usize, cells: impl IntoIterator<Item = Box<dyn Widget>>) {
+        let row = self.widget.make_row(cells);
+        self.widget.rows.insert(idx, row);
+        if let Some(selected) = &mut self.widget.selected_row {
+            if *selected >= idx {
+                *selected += 1;
+            }
+        }
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Remove the row at `idx`.
+    ///
+    /// If it was selected, no row is selected afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_row(&mut self, idx: usize) {
+        self.widget.rows.remove(idx);
+        self.widget.selected_row = match self.widget.selected_row {
+            Some(selected) if selected == idx => None,
+            Some(selected) if selected > idx => Some(selected - 1),
+            selected => selected,
+        };
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Remove all rows.
+    pub fn clear_rows(&mut self) {
+        self.widget.rows.clear();
+        self.widget.selected_row = None;
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the width of a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` is out of bounds.
+    pub fn set_column_width(&mut self, column: usize, width: f64) {
+        self.widget.columns[column].width = width.max(MIN_COLUMN_WIDTH);
+        self.ctx.request_layout();
+    }
+
+    /// Select a row, or clear the selection.
+    ///
+    /// Unlike a selection made by the user, this doesn't emit an action.
+    pub fn set_selected_row(&mut self, row: Option<usize>) {
+        self.widget.selected_row = row.filter(|&row| row < self.widget.rows.len());
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the sort indicator, or remove it.
+    ///
+    /// This only changes which header shows an indicator; rows are not reordered.
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.widget.sort = sort;
+        self.ctx.request_paint();
+    }
+
+    // FIXME - Remove Box
+    /// Return a [`WidgetMut`] to the cell at `row` and `column`, if it exists.
+    pub fn cell_mut(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let cell = self.widget.rows.get_mut(row)?.cells.get_mut(column)?;
+        Some(self.ctx.get_mut(cell))
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Table {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for cell in self.rows.iter_mut().flat_map(|row| &mut row.cells) {
+            cell.on_pointer_event(ctx, event);
+        }
+        for column in &mut self.columns {
+            column.header.on_pointer_event(ctx, event);
+        }
+        self.scrollbar.on_pointer_event(ctx, event);
+
+        if self.scrollbar.widget().moved {
+            self.scrollbar.widget_mut().moved = false;
+            let progress = self.scrollbar.widget().cursor_progress;
+            if self.set_viewport_pos_raw(progress * self.max_viewport_pos()) {
+                ctx.request_layout();
+            }
+        }
+        if self.scrollbar.is_active() || ctx.is_disabled() {
+            return;
+        }
+
+        let origin = ctx.window_origin().to_vec2();
+        let local_pos =
+            move |position: LogicalPosition<f64>| Point::new(position.x, position.y) - origin;
+        match event {
+            PointerEvent::PointerDown(MouseButton::Left, state) if !ctx.is_handled() => {
+                let pos = local_pos(state.position);
+                if let Some(column) = self.divider_at(pos) {
+                    self.resized_column = Some(column);
+                    let divider_x = self.column_left(column + 1);
+                    self.bar_drag.start(ctx, Axis::Horizontal, pos, divider_x);
+                    ctx.request_paint();
+                } else if let Some(column) = self.header_at(pos) {
+                    self.pressed_header = Some(column);
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                } else if let Some(row) = self.row_at(pos) {
+                    ctx.request_focus();
+                    self.select_row(ctx, row);
+                }
+            }
+            PointerEvent::PointerUp(MouseButton::Left, state) => {
+                let pos = local_pos(state.position);
+                if self.resized_column.take().is_some() {
+                    let over_divider = self.divider_at(pos).is_some();
+                    self.bar_drag.end(ctx, over_divider);
+                    ctx.request_paint();
+                } else if let Some(column) = self.pressed_header.take() {
+                    ctx.set_active(false);
+                    if ctx.is_hot() && self.header_at(pos) == Some(column) {
+                        self.request_sort(ctx, column);
+                    }
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                let pos = local_pos(state.position);
+                if let Some(column) = self.resized_column {
+                    let divider_x = self.bar_drag.dragged_position(Axis::Horizontal, pos);
+                    let width = (divider_x - self.column_left(column)).max(MIN_COLUMN_WIDTH);
+                    self.columns[column].width = width;
+                    ctx.request_layout();
+                } else if !ctx.is_active() {
+                    let over_divider = self.divider_at(pos).is_some();
+                    self.bar_drag.hover(ctx, Axis::Horizontal, over_divider);
+                }
+            }
+            PointerEvent::PointerLeave(_) => {
+                if !ctx.is_active() {
+                    self.bar_drag.hover(ctx, Axis::Horizontal, false);
+                }
+            }
+            PointerEvent::MouseWheel(delta, _) => {
+                if self.set_viewport_pos_raw(self.viewport_pos + delta.y) {
+                    ctx.request_layout();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for cell in self.rows.iter_mut().flat_map(|row| &mut row.cells) {
+            cell.on_text_event(ctx, event);
+        }
+        if ctx.is_handled() || ctx.is_disabled() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        let Some(row) = self.row_for_key(&key_event.logical_key) else {
+            return;
+        };
+        self.select_row(ctx, row);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for cell in self.rows.iter_mut().flat_map(|row| &mut row.cells) {
+            cell.on_access_event(ctx, event);
+        }
+        self.scrollbar.on_access_event(ctx, event);
+
+        if event.target == ctx.widget_id() {
+            let new_pos = match (event.action, &event.data) {
+                (accesskit::Action::ScrollUp, _) => Some(self.viewport_pos - self.body_height),
+                (accesskit::Action::ScrollDown, _) => Some(self.viewport_pos + self.body_height),
+                (accesskit::Action::SetScrollOffset, Some(ActionData::SetScrollOffset(offset))) => {
+                    Some(offset.y)
+                }
+                _ => None,
+            };
+            if let Some(new_pos) = new_pos {
+                self.set_viewport_pos_raw(new_pos);
+                ctx.request_layout();
+                ctx.request_accessibility_update();
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_as_portal();
+            }
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            _ => {}
+        }
+
+        for column in &mut self.columns {
+            column.header.lifecycle(ctx, event);
+        }
+        for cell in self.rows.iter_mut().flat_map(|row| &mut row.cells) {
+            cell.lifecycle(ctx, event);
+        }
+        self.scrollbar.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max_cell_size = |width: f64| {
+            let width = (width - 2.0 * CELL_PADDING).max(0.0);
+            BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY))
+        };
+
+        // Header row
+        let mut header_height = theme::BASIC_WIDGET_HEIGHT;
+        let mut header_sizes = Vec::with_capacity(self.columns.len());
+        for column in &mut self.columns {
+            let label_bc = max_cell_size(column.width - SORT_INDICATOR_WIDTH);
+            let label_size = column.header.layout(ctx, &label_bc);
+            header_height = header_height.max(label_size.height);
+            header_sizes.push(label_size);
+        }
+        self.header_height = header_height + 2.0 * CELL_PADDING;
+
+        let mut x = 0.0;
+        for (column, label_size) in self.columns.iter_mut().zip(header_sizes) {
+            let y = (self.header_height - label_size.height) / 2.0;
+            ctx.place_child(&mut column.header, Point::new(x + CELL_PADDING, y));
+            x += column.width;
+        }
+        let columns_width = x;
+
+        // Body rows, measured before we know how far they're scrolled
+        self.row_edges.clear();
+        let mut y = 0.0;
+        for row in &mut self.rows {
+            self.row_edges.push(y);
+            let mut row_height = theme::BASIC_WIDGET_HEIGHT;
+            for (cell, column) in row.cells.iter_mut().zip(&self.columns) {
+                let cell_size = cell.layout(ctx, &max_cell_size(column.width));
+                row_height = row_height.max(cell_size.height);
+            }
+            y += row_height + 2.0 * CELL_PADDING;
+        }
+        self.row_edges.push(y);
+        let content_height = y;
+
+        if !bc.is_height_bounded() {
+            warn!("A Table was given an unbounded height, so its body won't scroll.");
+        }
+        let desired_size = Size::new(
+            if bc.is_width_bounded() {
+                bc.max().width
+            } else {
+                columns_width
+            },
+            if bc.is_height_bounded() {
+                bc.max().height
+            } else {
+                self.header_height + content_height
+            },
+        );
+        let size = bc.constrain(desired_size);
+        self.body_height = (size.height - self.header_height).max(0.0);
+        self.viewport_pos = self.viewport_pos.clamp(0.0, self.max_viewport_pos());
+
+        for (row, top) in self.rows.iter_mut().zip(&self.row_edges) {
+            let y = self.header_height + top - self.viewport_pos + CELL_PADDING;
+            let mut x = 0.0;
+            for (cell, column) in row.cells.iter_mut().zip(&self.columns) {
+                ctx.place_child(cell, Point::new(x + CELL_PADDING, y));
+                x += column.width;
+            }
+        }
+
+        self.scrollbar_visible = content_height > self.body_height;
+        if self.scrollbar_visible {
+            let scrollbar = self.scrollbar.widget_mut();
+            scrollbar.portal_size = self.body_height;
+            scrollbar.content_size = content_height;
+            scrollbar.cursor_progress = self.viewport_pos / self.max_viewport_pos();
+            let scrollbar_bc =
+                BoxConstraints::new(Size::ZERO, Size::new(size.width, self.body_height));
+            let scrollbar_size = self.scrollbar.layout(ctx, &scrollbar_bc);
+            ctx.place_child(
+                &mut self.scrollbar,
+                Point::new(size.width - scrollbar_size.width, self.header_height),
+            );
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let body_rect = Rect::new(0.0, self.header_height, size.width, size.height);
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &body_rect);
+        if let Some(row) = self.selected_row {
+            if let (Some(&top), Some(&bottom)) =
+                (self.row_edges.get(row), self.row_edges.get(row + 1))
+            {
+                let offset = self.header_height - self.viewport_pos;
+                let row_rect = Rect::new(0.0, top + offset, size.width, bottom + offset);
+                let color = if ctx.is_focused() {
+                    theme::SELECTED_TEXT_BACKGROUND_COLOR
+                } else {
+                    theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR
+                };
+                fill_color(scene, &row_rect, color);
+            }
+        }
+        for cell in self.rows.iter_mut().flat_map(|row| &mut row.cells) {
+            cell.paint(ctx, scene);
+        }
+        scene.pop_layer();
+
+        let header_rect = Rect::new(0.0, 0.0, size.width, self.header_height);
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &header_rect);
+        fill_lin_gradient(
+            scene,
+            &header_rect,
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_DARK],
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+        if let Some(column) = self.pressed_header {
+            let pressed_rect = Rect::new(
+                self.column_left(column),
+                0.0,
+                self.column_left(column + 1),
+                self.header_height,
+            );
+            fill_color(scene, &pressed_rect, theme::BACKGROUND_DARK);
+        }
+        for column in &mut self.columns {
+            column.header.paint(ctx, scene);
+        }
+        if let Some((column, order)) = self.sort {
+            if column < self.columns.len() {
+                self.paint_sort_indicator(scene, column, order);
+            }
+        }
+
+        let mut divider_x = 0.0;
+        for (idx, column) in self.columns.iter().enumerate() {
+            divider_x += column.width;
+            let color = if self.resized_column == Some(idx) {
+                theme::BORDER_LIGHT
+            } else {
+                theme::BORDER_DARK
+            };
+            let divider = Line::new((divider_x, 0.0), (divider_x, self.header_height));
+            stroke(scene, &divider, color, 1.0);
+        }
+        let bottom = self.header_height - 0.5;
+        stroke(
+            scene,
+            &Line::new((0.0, bottom), (size.width, bottom)),
+            theme::BORDER_DARK,
+            1.0,
+        );
+        scene.pop_layer();
+
+        if self.scrollbar_visible {
+            self.scrollbar.paint(ctx, scene);
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Table
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let scale_factor = ctx.scale_factor();
+
+        let node = ctx.current_node();
+        node.set_clips_children();
+        node.set_scroll_y(self.viewport_pos * scale_factor);
+        node.set_scroll_y_min(0.0);
+        node.set_scroll_y_max(self.max_viewport_pos() * scale_factor);
+        node.add_action(accesskit::Action::ScrollUp);
+        node.add_action(accesskit::Action::ScrollDown);
+        node.add_action(accesskit::Action::SetScrollOffset);
+        // The headers and cells are grouped in rows, which aren't widgets.
+        node.set_children(
+            std::iter::once(self.header_row_node_id)
+                .chain(self.rows.iter().map(|row| row.node_id))
+                .collect::<Vec<_>>(),
+        );
+        if let Some(row) = self.selected_row.and_then(|row| self.rows.get(row)) {
+            node.set_active_descendant(row.node_id);
+        }
+
+        let origin = ctx.window_origin();
+        let width = ctx.size().width;
+        let row_bounds = |y0: f64, y1: f64| accesskit::Rect {
+            x0: origin.x * scale_factor,
+            y0: (origin.y + y0) * scale_factor,
+            x1: (origin.x + width) * scale_factor,
+            y1: (origin.y + y1) * scale_factor,
+        };
+
+        let mut header_row = NodeBuilder::new(Role::Row);
+        header_row.set_bounds(row_bounds(0.0, self.header_height));
+        for column in &mut self.columns {
+            header_row.push_child(column.header.id().into());
+            column.header.accessibility(ctx);
+        }
+        ctx.push_node(self.header_row_node_id, header_row);
+
+        let offset = self.header_height - self.viewport_pos;
+        for (idx, row) in self.rows.iter_mut().enumerate() {
+            let mut row_node = NodeBuilder::new(Role::Row);
+            if let (Some(&top), Some(&bottom)) =
+                (self.row_edges.get(idx), self.row_edges.get(idx + 1))
+            {
+                row_node.set_bounds(row_bounds(top + offset, bottom + offset));
+            }
+            if self.selected_row == Some(idx) {
+                row_node.set_selected(true);
+            }
+            for cell in &mut row.cells {
+                row_node.push_child(cell.id().into());
+                cell.accessibility(ctx);
+            }
+            ctx.push_node(row.node_id, row_node);
+        }
+
+        if self.scrollbar_visible {
+            ctx.add_extra_child(&mut self.scrollbar);
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        // The headers are painted over the cells scrolled under them.
+        let cells = self
+            .rows
+            .iter()
+            .flat_map(|row| &row.cells)
+            .map(|cell| cell.as_dyn());
+        let headers = self.columns.iter().map(|column| column.header.as_dyn());
+        cells.chain(headers).collect()
+    }
+
+    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
+        if self.scrollbar_visible && self.scrollbar.layout_rect().contains(pos) {
+            return Some(self.scrollbar.as_dyn());
+        }
+        // The header band hides the cells scrolled under it, padding included, so
+        // the whole band belongs to the headers. There are no cells past the last
+        // column, so nothing is hidden there.
+        if pos.y < self.header_height {
+            return self
+                .header_at(pos)
+                .map(|column| self.columns[column].header.as_dyn());
+        }
+        self.rows
+            .iter()
+            .flat_map(|row| &row.cells)
+            .find(|cell| cell.layout_rect().contains(pos))
+            .map(|cell| cell.as_dyn())
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Table")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        let headers: Vec<_> = self
+            .columns
+            .iter()
+            .map(|column| column.header.widget().text().to_string())
+            .collect();
+        Some(format!("{} rows of {:?}", self.rows.len(), headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::Vec2;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Button;
+
+    fn fruit_table() -> Table {
+        Table::new(["Name", "Color"])
+            .with_text_row(["Apple", "Red"])
+            .with_text_row(["Banana", "Yellow"])
+            .with_text_row(["Cherry", "Red"])
+    }
+
+    fn table_of(harness: &TestHarness) -> WidgetRef<'_, Table> {
+        harness.root_widget().downcast::<Table>().unwrap()
+    }
+
+    fn click_at(harness: &mut TestHarness, pos: impl Into<Point>) {
+        harness.mouse_move(pos);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+    }
+
+    #[test]
+    fn click_header_to_sort() {
+        let mut harness = TestHarness::create_with_size(fruit_table(), Size::new(200.0, 200.0));
+        let table_id = harness.root_widget().id();
+
+        // Middle of the "Color" header.
+        click_at(&mut harness, (150.0, 5.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TableSortRequested(1, SortOrder::Ascending),
+                table_id
+            ))
+        );
+
+        click_at(&mut harness, (150.0, 5.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TableSortRequested(1, SortOrder::Descending),
+                table_id
+            ))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(table_of(&harness).sort(), Some((1, SortOrder::Descending)));
+    }
+
+    #[test]
+    fn drag_divider_to_resize() {
+        let mut harness = TestHarness::create_with_size(fruit_table(), Size::new(300.0, 200.0));
+        assert_eq!(table_of(&harness).column_width(0), Some(100.0));
+
+        // Grab the divider slightly off-center, to check that a drag moves it by
+        // the distance the mouse moved.
+        harness.mouse_move((101.0, 5.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((141.0, 5.0));
+        harness.mouse_button_release(MouseButton::Left);
+
+        let table = table_of(&harness);
+        assert_eq!(table.column_width(0), Some(140.0));
+        assert_eq!(table.column_width(1), Some(100.0));
+        // Grabbing a divider doesn't sort.
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn select_rows() {
+        let mut harness = TestHarness::create_with_size(fruit_table(), Size::new(200.0, 200.0));
+        let table_id = harness.root_widget().id();
+
+        let header_height = table_of(&harness).header_height;
+        let row_height = table_of(&harness).row_edges[1];
+        click_at(&mut harness, (50.0, header_height + row_height * 1.5));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TableRowSelected(1), table_id))
+        );
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(table_id));
+
+        // Clicking the selected row again doesn't emit anything.
+        click_at(&mut harness, (50.0, header_height + row_height * 1.5));
+        assert_eq!(harness.pop_action(), None);
+
+        harness.edit_root_widget(|mut root| {
+            let mut table = root.downcast::<Table>().unwrap();
+            table.remove_row(0);
+        });
+        assert_eq!(table_of(&harness).selected_row(), Some(0));
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn access_tree() {
+        let mut harness = TestHarness::create_with_size(fruit_table(), Size::new(200.0, 200.0));
+        harness.edit_root_widget(|mut root| {
+            let mut table = root.downcast::<Table>().unwrap();
+            table.set_selected_row(Some(1));
+        });
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  Table
+    Row
+      StaticText \"Name\"
+      StaticText \"Color\"
+    Row
+      StaticText \"Apple\"
+      StaticText \"Red\"
+    Row selected
+      StaticText \"Banana\"
+      StaticText \"Yellow\"
+    Row
+      StaticText \"Cherry\"
+      StaticText \"Red\"
+"
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let named = Key::Named;
+        let mut table = fruit_table();
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowDown)), Some(0));
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowUp)), Some(0));
+        assert_eq!(table.row_for_key(&named(NamedKey::End)), Some(2));
+
+        table.selected_row = Some(1);
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowDown)), Some(2));
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowUp)), Some(0));
+        assert_eq!(table.row_for_key(&named(NamedKey::Home)), Some(0));
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowLeft)), None);
+
+        // The arrows stop at the first and last rows.
+        table.selected_row = Some(2);
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowDown)), Some(2));
+        table.selected_row = Some(0);
+        assert_eq!(table.row_for_key(&named(NamedKey::ArrowUp)), Some(0));
+
+        let empty = Table::new(["Name"]);
+        assert_eq!(empty.row_for_key(&named(NamedKey::ArrowDown)), None);
+    }
+
+    #[test]
+    fn header_stays_fixed() {
+        let [first_cell_id] = widget_ids();
+        let mut table = Table::new(["Number"]);
+        for i in 0..50 {
+            table = table.with_text_row([format!("{i}")]);
+        }
+        let mut harness = TestHarness::create_with_size(table, Size::new(200.0, 200.0));
+        harness.edit_root_widget(|mut root| {
+            let mut table = root.downcast::<Table>().unwrap();
+            table.insert_row(
+                0,
+                [Box::new(Label::new("First").with_id(first_cell_id)) as Box<dyn Widget>],
+            );
+        });
+
+        let header_id = table_of(&harness).columns[0].header.id();
+        let header_rect = harness.get_widget(header_id).state().layout_rect();
+        let cell_rect = harness.get_widget(first_cell_id).state().layout_rect();
+
+        harness.mouse_move((50.0, 100.0));
+        harness.mouse_wheel(Vec2::new(0.0, 100.0));
+
+        assert_eq!(table_of(&harness).viewport_pos, 100.0);
+        assert_eq!(
+            harness.get_widget(header_id).state().layout_rect(),
+            header_rect
+        );
+        assert_eq!(
+            harness.get_widget(first_cell_id).state().layout_rect(),
+            cell_rect - Vec2::new(0.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn header_padding_hides_cells() {
+        let mut table = Table::new(["Buttons"]);
+        for _ in 0..50 {
+            table = table.with_row([Box::new(Button::new("Press")) as Box<dyn Widget>]);
+        }
+        let mut harness = TestHarness::create_with_size(table, Size::new(200.0, 200.0));
+        let table_id = harness.root_widget().id();
+
+        // Scroll the button of the second row up to the top of the header, above
+        // the header's label.
+        let header_height = table_of(&harness).header_height;
+        let row_top = table_of(&harness).row_edges[1];
+        harness.mouse_move((50.0, 100.0));
+        harness.mouse_wheel(Vec2::new(0.0, header_height + row_top + CELL_PADDING - 0.5));
+
+        let pos = Point::new(CELL_PADDING + 5.0, 1.0);
+        let button_id = table_of(&harness).rows[1].cells[0].id();
+        let header_id = table_of(&harness).columns[0].header.id();
+        assert!(harness
+            .get_widget(button_id)
+            .state()
+            .layout_rect()
+            .contains(pos));
+        assert!(!harness
+            .get_widget(header_id)
+            .state()
+            .layout_rect()
+            .contains(pos));
+
+        click_at(&mut harness, pos);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TableSortRequested(0, SortOrder::Ascending),
+                table_id
+            ))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert!(!harness.get_widget(button_id).state().is_hot);
+    }
+}
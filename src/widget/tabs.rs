@@ -0,0 +1,939 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A container with a tab bar, which shows one page at a time.
+
+use accesskit::{DefaultActionVerb, Role};
+use kurbo::Line;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::kurbo::{Point, Rect, Size};
+use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+};
+
+/// The horizontal space between the edges of a tab and its contents.
+const TAB_PADDING_HORIZONTAL: f64 = theme::WIDGET_PADDING_HORIZONTAL;
+/// The vertical space between the edges of a tab and its contents.
+const TAB_PADDING_VERTICAL: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+const CLOSE_BUTTON_SIZE: f64 = 12.0;
+/// The space between the label of a tab and its close button.
+const CLOSE_BUTTON_SPACING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+
+/// A container with a row of tabs, which shows the page of the selected tab.
+///
+/// The pages of the other tabs are kept alive, but stashed: they don't receive
+/// pointer events, aren't laid out and aren't painted.
+///
+/// The tab bar is a single stop in the focus chain; when it's focused, the arrow
+/// keys move the selection between tabs.
+///
+/// Emits [`Action::TabSelected`] when the user selects a tab, and
+/// [`Action::TabCloseRequested`] when they click the close button of a closable
+/// tab. Closing the tab is up to the app, eg with [`TabsMut::remove_tab`].
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    selected: usize,
+    /// The tab the pointer was pressed on, and whether it was pressed on its close button.
+    pressed: Option<(usize, bool)>,
+}
+
+crate::declare_widget!(TabsMut, Tabs);
+
+struct Tab {
+    handle: WidgetPod<TabHandle>,
+    page: WidgetPod<Box<dyn Widget>>,
+}
+
+/// The clickable part of a tab, in the tab bar of [`Tabs`].
+pub(crate) struct TabHandle {
+    selected: bool,
+    closable: bool,
+    label: WidgetPod<Label>,
+}
+
+crate::declare_widget!(TabHandleMut, TabHandle);
+
+/// What a key press does while the tab bar is focused.
+#[derive(Debug, PartialEq)]
+enum KeyAction {
+    Select(usize),
+    RequestClose(usize),
+}
+
+// --- METHODS ---
+
+impl Tabs {
+    /// Create a new container without tabs.
+    pub fn new() -> Self {
+        Tabs {
+            tabs: Vec::new(),
+            selected: 0,
+            pressed: None,
+        }
+    }
+
+    /// Builder-style method to add a tab after the others.
+    pub fn with_tab(mut self, title: impl Into<ArcStr>, page: impl Widget) -> Self {
+        self.push_tab(Tab::new(title.into(), Box::new(page), false));
+        self
+    }
+
+    /// Builder-style method to add a tab with a close button after the others.
+    pub fn with_closable_tab(mut self, title: impl Into<ArcStr>, page: impl Widget) -> Self {
+        self.push_tab(Tab::new(title.into(), Box::new(page), true));
+        self
+    }
+
+    /// Builder-style method to set which tab is selected.
+    ///
+    /// `selected` is clamped to the tabs added so far.
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        if let Some(tab) = self.tabs.get_mut(self.selected) {
+            tab.handle.widget_mut().selected = false;
+        }
+        self.selected = selected.min(self.tabs.len().saturating_sub(1));
+        if let Some(tab) = self.tabs.get_mut(self.selected) {
+            tab.handle.widget_mut().selected = true;
+        }
+        self
+    }
+
+    fn push_tab(&mut self, mut tab: Tab) {
+        tab.handle.widget_mut().selected = self.tabs.is_empty();
+        self.tabs.push(tab);
+    }
+
+    /// Return the number of tabs.
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Return `true` if there are no tabs.
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Return the index of the selected tab.
+    ///
+    /// This is `0` when there are no tabs.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Return the title of the tab at `idx`, if it exists.
+    pub fn tab_title(&self, idx: usize) -> Option<ArcStr> {
+        let tab = self.tabs.get(idx)?;
+        Some(tab.handle.widget().label.widget().text())
+    }
+
+    /// The tab under `pos`, in local coordinates, and whether `pos` is on its close button.
+    fn tab_at(&self, pos: Point) -> Option<(usize, bool)> {
+        self.tabs.iter().enumerate().find_map(|(idx, tab)| {
+            let rect = tab.handle.layout_rect();
+            if !rect.contains(pos) {
+                return None;
+            }
+            let on_close = tab
+                .handle
+                .widget()
+                .close_button_rect(rect.size())
+                .map_or(false, |close_rect| {
+                    close_rect.contains(pos - rect.origin().to_vec2())
+                });
+            Some((idx, on_close))
+        })
+    }
+
+    /// What a key press does while the tab bar is focused, if anything.
+    ///
+    /// The arrow keys wrap around, and Delete asks to close the selected tab if it's closable.
+    fn action_for_key(&self, key: &Key) -> Option<KeyAction> {
+        let len = self.tabs.len();
+        if len == 0 {
+            return None;
+        }
+        match key {
+            Key::Named(NamedKey::ArrowRight) => Some(KeyAction::Select((self.selected + 1) % len)),
+            Key::Named(NamedKey::ArrowLeft) => {
+                Some(KeyAction::Select((self.selected + len - 1) % len))
+            }
+            Key::Named(NamedKey::Home) => Some(KeyAction::Select(0)),
+            Key::Named(NamedKey::End) => Some(KeyAction::Select(len - 1)),
+            Key::Named(NamedKey::Delete) if self.tabs[self.selected].handle.widget().closable => {
+                Some(KeyAction::RequestClose(self.selected))
+            }
+            _ => None,
+        }
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx == self.selected || idx >= self.tabs.len() {
+            return;
+        }
+        let old_tab = &mut self.tabs[self.selected];
+        // A widget in a stashed page can't keep the focus.
+        if old_tab.page.has_focus() {
+            ctx.request_focus();
+        }
+        ctx.get_mut(&mut old_tab.handle).set_selected(false);
+        ctx.set_stashed(&mut old_tab.page, true);
+        let new_tab = &mut self.tabs[idx];
+        ctx.get_mut(&mut new_tab.handle).set_selected(true);
+        ctx.set_stashed(&mut new_tab.page, false);
+        self.selected = idx;
+        trace!("Tabs {:?} selected tab {}", ctx.widget_id(), idx);
+        ctx.submit_action(Action::TabSelected(idx));
+        ctx.request_layout();
+    }
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tab {
+    fn new(title: ArcStr, page: Box<dyn Widget>, closable: bool) -> Self {
+        Tab {
+            handle: WidgetPod::new(TabHandle {
+                selected: false,
+                closable,
+                label: WidgetPod::new(Label::new(title)),
+            }),
+            page: WidgetPod::new(page),
+        }
+    }
+}
+
+impl TabsMut<'_> {
+    /// Add a tab after the others.
+    ///
+    /// See also [`with_tab`](Tabs::with_tab).
+    pub fn add_tab(&mut self, title: impl Into<ArcStr>, page: impl Widget) {
+        let idx = self.widget.tabs.len();
+        self.insert_tab_inner(idx, Tab::new(title.into(), Box::new(page), false));
+    }
+
+    /// Add a tab with a close button after the others.
+    pub fn add_closable_tab(&mut self, title: impl Into<ArcStr>, page: impl Widget) {
+        let idx = self.widget.tabs.len();
+        self.insert_tab_inner(idx, Tab::new(title.into(), Box::new(page), true));
+    }
+
+    /// Insert a tab at `idx`.
+    ///
+    /// The same tab stays selected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is greater than the number of tabs.
+    pub fn insert_tab(&mut self, idx: usize, title: impl Into<ArcStr>, page: impl Widget) {
+        self.insert_tab_inner(idx, Tab::new(title.into(), Box::new(page), false));
+    }
+
+    fn insert_tab_inner(&mut self, idx: usize, mut tab: Tab) {
+        if self.widget.tabs.is_empty() {
+            tab.handle.widget_mut().selected = true;
+        } else {
+            self.ctx.set_stashed(&mut tab.page, true);
+            if idx <= self.widget.selected {
+                self.widget.selected += 1;
+            }
+        }
+        self.widget.tabs.insert(idx, tab);
+        self.widget.pressed = None;
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Remove the tab at `idx`, and its page.
+    ///
+    /// If the tab was selected, the tab after it is selected instead, or the
+    /// one before it if it was the last tab.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_tab(&mut self, idx: usize) {
+        self.widget.tabs.remove(idx);
+        let selected = self.widget.selected;
+        if idx < selected {
+            self.widget.selected -= 1;
+        } else if idx == selected && !self.widget.tabs.is_empty() {
+            let new_selected = selected.min(self.widget.tabs.len() - 1);
+            self.widget.selected = new_selected;
+            self.tab_handle_mut(new_selected).set_selected(true);
+            let page = &mut self.widget.tabs[new_selected].page;
+            self.ctx.set_stashed(page, false);
+        }
+        self.widget.pressed = None;
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Move the tab at `from` so that it ends up at `to`.
+    ///
+    /// The same tab stays selected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let tab = self.widget.tabs.remove(from);
+        self.widget.tabs.insert(to, tab);
+        let selected = self.widget.selected;
+        self.widget.selected = if selected == from {
+            to
+        } else if from < selected && selected <= to {
+            selected - 1
+        } else if to <= selected && selected < from {
+            selected + 1
+        } else {
+            selected
+        };
+        self.widget.pressed = None;
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Select the tab at `idx`.
+    ///
+    /// This doesn't emit [`Action::TabSelected`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_selected(&mut self, idx: usize) {
+        assert!(
+            idx < self.widget.tabs.len(),
+            "tab index {idx} out of bounds"
+        );
+        let old_selected = self.widget.selected;
+        if idx == old_selected {
+            return;
+        }
+        // A widget in a stashed page can't keep the focus.
+        if self.widget.tabs[old_selected].page.has_focus() {
+            self.ctx.request_focus();
+        }
+        self.tab_handle_mut(old_selected).set_selected(false);
+        self.ctx
+            .set_stashed(&mut self.widget.tabs[old_selected].page, true);
+        self.tab_handle_mut(idx).set_selected(true);
+        self.ctx.set_stashed(&mut self.widget.tabs[idx].page, false);
+        self.widget.selected = idx;
+    }
+
+    /// Set the title of the tab at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_tab_title(&mut self, idx: usize, title: impl Into<ArcStr>) {
+        let title = title.into();
+        let mut handle = self.tab_handle_mut(idx);
+        handle.ctx.request_accessibility_update();
+        handle.label_mut().set_text(title);
+    }
+
+    /// Set whether the tab at `idx` has a close button.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_closable(&mut self, idx: usize, closable: bool) {
+        let mut handle = self.tab_handle_mut(idx);
+        handle.widget.closable = closable;
+        handle.ctx.request_layout();
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the page of the tab at `idx`.
+    pub fn page_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let page = &mut self.widget.tabs.get_mut(idx)?.page;
+        Some(self.ctx.get_mut(page))
+    }
+
+    fn tab_handle_mut(&mut self, idx: usize) -> WidgetMut<'_, TabHandle> {
+        self.ctx.get_mut(&mut self.widget.tabs[idx].handle)
+    }
+}
+
+impl TabHandle {
+    /// The close button's rect, in the coordinates of a handle of the given size.
+    fn close_button_rect(&self, size: Size) -> Option<Rect> {
+        if !self.closable {
+            return None;
+        }
+        let origin = Point::new(
+            size.width - TAB_PADDING_HORIZONTAL - CLOSE_BUTTON_SIZE,
+            (size.height - CLOSE_BUTTON_SIZE) / 2.0,
+        );
+        Some(Rect::from_origin_size(
+            origin,
+            (CLOSE_BUTTON_SIZE, CLOSE_BUTTON_SIZE),
+        ))
+    }
+}
+
+impl TabHandleMut<'_> {
+    fn set_selected(&mut self, selected: bool) {
+        self.widget.selected = selected;
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    fn label_mut(&mut self) -> WidgetMut<'_, Label> {
+        self.ctx.get_mut(&mut self.widget.label)
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Tabs {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for tab in &mut self.tabs {
+            tab.handle.on_pointer_event(ctx, event);
+            tab.page.on_pointer_event(ctx, event);
+        }
+        match event {
+            PointerEvent::PointerDown(_, state) => {
+                if ctx.is_disabled() || ctx.is_handled() {
+                    return;
+                }
+                let local_pos =
+                    Point::new(state.position.x, state.position.y) - ctx.window_origin().to_vec2();
+                self.pressed = self.tab_at(local_pos);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerUp(_, state) => {
+                if let (true, Some(pressed)) = (ctx.is_active(), self.pressed.take()) {
+                    let local_pos = Point::new(state.position.x, state.position.y)
+                        - ctx.window_origin().to_vec2();
+                    if !ctx.is_disabled() && self.tab_at(local_pos) == Some(pressed) {
+                        match pressed {
+                            (idx, true) => {
+                                trace!("Tabs {:?} asked to close tab {}", ctx.widget_id(), idx);
+                                ctx.submit_action(Action::TabCloseRequested(idx));
+                            }
+                            (idx, false) => {
+                                ctx.request_focus();
+                                self.select(ctx, idx);
+                            }
+                        }
+                    }
+                    ctx.request_paint();
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for tab in &mut self.tabs {
+            tab.page.on_text_event(ctx, event);
+        }
+        // Only react to keys when the tab bar itself is focused, not a widget in the page.
+        if !ctx.is_focused() || ctx.is_disabled() || self.tabs.is_empty() {
+            return;
+        }
+        let TextEvent::KeyboardKey(key_event, _) = event else {
+            return;
+        };
+        if key_event.state != ElementState::Pressed {
+            return;
+        }
+        match self.action_for_key(&key_event.logical_key) {
+            Some(KeyAction::Select(idx)) => self.select(ctx, idx),
+            Some(KeyAction::RequestClose(idx)) => {
+                ctx.submit_action(Action::TabCloseRequested(idx));
+            }
+            None => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for tab in &mut self.tabs {
+            tab.handle.on_access_event(ctx, event);
+            tab.page.on_access_event(ctx, event);
+        }
+        if event.action != accesskit::Action::Default || ctx.is_disabled() {
+            return;
+        }
+        // Assistive technologies activate the tabs themselves.
+        let target = self
+            .tabs
+            .iter()
+            .position(|tab| tab.handle.id() == event.target);
+        if let Some(idx) = target {
+            self.select(ctx, idx);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                for (idx, tab) in self.tabs.iter_mut().enumerate() {
+                    if idx != self.selected {
+                        ctx.set_stashed(&mut tab.page, true);
+                    }
+                }
+            }
+            LifeCycle::BuildFocusChain => {
+                if !self.tabs.is_empty() {
+                    ctx.register_for_focus();
+                }
+            }
+            _ => {}
+        }
+
+        for tab in &mut self.tabs {
+            tab.handle.lifecycle(ctx, event);
+            tab.page.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let mut x = 0.0;
+        let mut tab_bar_height: f64 = 0.0;
+        for tab in &mut self.tabs {
+            let handle_size = tab.handle.layout(ctx, &bc.loosen());
+            ctx.place_child(&mut tab.handle, Point::new(x, 0.0));
+            x += handle_size.width;
+            tab_bar_height = tab_bar_height.max(handle_size.height);
+        }
+        let tab_bar_width = x;
+
+        let page_size = if let Some(tab) = self.tabs.get_mut(self.selected) {
+            let page_bc = bc.shrink((0.0, tab_bar_height));
+            let page_size = tab.page.layout(ctx, &page_bc);
+            ctx.place_child(&mut tab.page, Point::new(0.0, tab_bar_height));
+            page_size
+        } else {
+            Size::ZERO
+        };
+
+        let size = bc.constrain(Size::new(
+            tab_bar_width.max(page_size.width),
+            tab_bar_height + page_size.height,
+        ));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let Some(tab_bar_height) = self
+            .tabs
+            .first()
+            .map(|tab| tab.handle.layout_rect().height())
+        else {
+            return;
+        };
+        let separator_y = tab_bar_height - 0.5;
+        stroke(
+            scene,
+            &Line::new((0.0, separator_y), (ctx.size().width, separator_y)),
+            theme::BORDER_DARK,
+            1.0,
+        );
+
+        for (idx, tab) in self.tabs.iter_mut().enumerate() {
+            tab.handle.paint(ctx, scene);
+            if idx == self.selected {
+                tab.page.paint(ctx, scene);
+            }
+        }
+
+        if ctx.is_focused() && !ctx.is_disabled() {
+            if let Some(tab) = self.tabs.get(self.selected) {
+                let ring = tab.handle.layout_rect().inset(-1.0);
+                stroke(scene, &ring, theme::PRIMARY_LIGHT, 1.0);
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TabList
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        // Stashed pages are left out of the tree.
+        for tab in &mut self.tabs {
+            tab.handle.accessibility(ctx);
+            tab.page.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.tabs
+            .iter()
+            .flat_map(|tab| [tab.handle.as_dyn(), tab.page.as_dyn()])
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Tabs")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("selected: {}", self.selected))
+    }
+}
+
+impl Widget for TabHandle {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let label_size = self.label.layout(ctx, bc);
+        let content_height = label_size.height.max(theme::BASIC_WIDGET_HEIGHT);
+        let height = content_height + 2.0 * TAB_PADDING_VERTICAL;
+        ctx.place_child(
+            &mut self.label,
+            Point::new(TAB_PADDING_HORIZONTAL, (height - label_size.height) / 2.0),
+        );
+
+        let mut width = label_size.width + 2.0 * TAB_PADDING_HORIZONTAL;
+        if self.closable {
+            width += CLOSE_BUTTON_SPACING + CLOSE_BUTTON_SIZE;
+        }
+        let size = bc.constrain(Size::new(width, height));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let border_width = 1.;
+        let rect = ctx.size().to_rect().inset(-border_width / 2.);
+
+        let colors = if self.selected {
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_LIGHT]
+        } else {
+            [theme::BACKGROUND_LIGHT, theme::BACKGROUND_DARK]
+        };
+        fill_lin_gradient(scene, &rect, colors, UnitPoint::TOP, UnitPoint::BOTTOM);
+
+        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &rect, border_color, border_width);
+
+        if let Some(close_rect) = self.close_button_rect(ctx.size()) {
+            let cross_color = if ctx.is_disabled() {
+                theme::DISABLED_TEXT_COLOR
+            } else {
+                theme::TEXT_COLOR
+            };
+            let cross = close_rect.inset(-2.0);
+            let line_1 = Line::new((cross.x0, cross.y0), (cross.x1, cross.y1));
+            let line_2 = Line::new((cross.x0, cross.y1), (cross.x1, cross.y0));
+            stroke(scene, &line_1, cross_color, 1.5);
+            stroke(scene, &line_2, cross_color, 1.5);
+        }
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Tab
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_selected(self.selected);
+        node.set_default_action_verb(DefaultActionVerb::Click);
+        node.add_action(accesskit::Action::Default);
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TabHandle")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!(
+            "[{}]{}",
+            self.label.as_ref().text(),
+            if self.selected { " *" } else { "" }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::ModifiersState;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Button, Switch};
+    use crate::WidgetId;
+
+    fn tabs_of(harness: &TestHarness) -> WidgetRef<'_, Tabs> {
+        harness.root_widget().downcast::<Tabs>().unwrap()
+    }
+
+    fn handle_ids(harness: &TestHarness) -> Vec<WidgetId> {
+        tabs_of(harness)
+            .tabs
+            .iter()
+            .map(|tab| tab.handle.id())
+            .collect()
+    }
+
+    fn page_is_stashed(harness: &TestHarness, idx: usize) -> bool {
+        tabs_of(harness).tabs[idx].page.state().is_stashed
+    }
+
+    #[test]
+    fn hidden_pages_are_stashed() {
+        let [first_id, second_id] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("First", Button::new("One").with_id(first_id))
+            .with_tab("Second", Button::new("Two").with_id(second_id));
+
+        let mut harness = TestHarness::create(widget);
+        let tabs_id = harness.root_widget().id();
+        assert!(!page_is_stashed(&harness, 0));
+        assert!(page_is_stashed(&harness, 1));
+
+        let handles = handle_ids(&harness);
+        harness.mouse_click_on(handles[1]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabSelected(1), tabs_id))
+        );
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(tabs_id));
+        assert!(page_is_stashed(&harness, 0));
+        assert!(!page_is_stashed(&harness, 1));
+
+        harness.mouse_click_on(second_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, second_id))
+        );
+
+        // The page that was hidden is placed under the tab bar once shown.
+        let tab_bar_height = harness
+            .get_widget(handles[1])
+            .state()
+            .layout_rect()
+            .height();
+        let page_rect = tabs_of(&harness).tabs[1].page.layout_rect();
+        assert_eq!(page_rect.origin(), Point::new(0.0, tab_bar_height));
+
+        // Once the first page is shown again, the stashed second page, which is at
+        // the same position, doesn't take its clicks.
+        harness.mouse_click_on(handles[0]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabSelected(0), tabs_id))
+        );
+        assert!(page_is_stashed(&harness, 1));
+        harness.mouse_click_on(first_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, first_id))
+        );
+    }
+
+    #[test]
+    fn close_button() {
+        let widget = Tabs::new()
+            .with_tab("Fixed", Label::new("Page 1"))
+            .with_closable_tab("Closable", Label::new("Page 2"));
+
+        let mut harness = TestHarness::create(widget);
+        let tabs_id = harness.root_widget().id();
+        let handles = handle_ids(&harness);
+
+        let handle_rect = harness.get_widget(handles[1]).state().window_layout_rect();
+        let close_rect = tabs_of(&harness).tabs[1]
+            .handle
+            .widget()
+            .close_button_rect(handle_rect.size())
+            .unwrap();
+        harness.mouse_move(handle_rect.origin() + close_rect.center().to_vec2());
+        harness.mouse_button_press(winit::event::MouseButton::Left);
+        harness.mouse_button_release(winit::event::MouseButton::Left);
+
+        // Closing a tab doesn't select it.
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TabCloseRequested(1), tabs_id))
+        );
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(tabs_of(&harness).selected(), 0);
+    }
+
+    #[test]
+    fn keys() {
+        let named = Key::Named;
+        let tabs = Tabs::new()
+            .with_tab("A", Label::new("Page A"))
+            .with_closable_tab("B", Label::new("Page B"))
+            .with_tab("C", Label::new("Page C"))
+            .with_selected(1);
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::ArrowRight)),
+            Some(KeyAction::Select(2))
+        );
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::ArrowLeft)),
+            Some(KeyAction::Select(0))
+        );
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::Home)),
+            Some(KeyAction::Select(0))
+        );
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::End)),
+            Some(KeyAction::Select(2))
+        );
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::Delete)),
+            Some(KeyAction::RequestClose(1))
+        );
+        assert_eq!(tabs.action_for_key(&named(NamedKey::ArrowDown)), None);
+
+        // The arrows wrap around, and tabs without a close button can't be closed.
+        let tabs = tabs.with_selected(2);
+        assert_eq!(
+            tabs.action_for_key(&named(NamedKey::ArrowRight)),
+            Some(KeyAction::Select(0))
+        );
+        assert_eq!(tabs.action_for_key(&named(NamedKey::Delete)), None);
+
+        let empty = Tabs::new();
+        assert_eq!(empty.action_for_key(&named(NamedKey::ArrowRight)), None);
+    }
+
+    #[test]
+    fn focus_leaves_hidden_page() {
+        let [first_id, second_id] = widget_ids();
+        let widget = Tabs::new()
+            .with_tab("First", Switch::new(false).with_id(first_id))
+            .with_tab("Second", Switch::new(false).with_id(second_id));
+
+        let mut harness = TestHarness::create(widget);
+        let tabs_id = harness.root_widget().id();
+        harness.process_access_event(AccessEvent {
+            target: first_id,
+            action: accesskit::Action::Focus,
+            data: None,
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(first_id));
+
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<Tabs>().unwrap().set_selected(1);
+        });
+        assert!(page_is_stashed(&harness, 0));
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(tabs_id));
+
+        // The focus chain goes from the tab bar to the shown page only.
+        let tab = Key::Named(NamedKey::Tab);
+        harness.keyboard_press_unused_key(tab.clone(), ModifiersState::empty());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(second_id));
+        harness.keyboard_press_unused_key(tab, ModifiersState::empty());
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(tabs_id));
+    }
+
+    #[test]
+    fn access_tree() {
+        let widget = Tabs::new()
+            .with_tab("A", Label::new("Page A"))
+            .with_tab("B", Label::new("Page B"))
+            .with_selected(1);
+
+        let harness = TestHarness::create(widget);
+        assert_eq!(
+            harness.access_tree_snapshot(),
+            "\
+Window
+  TabList
+    Tab
+      StaticText \"A\"
+    Tab selected
+      StaticText \"B\"
+    StaticText \"Page B\"
+"
+        );
+    }
+
+    #[test]
+    fn edit_tabs() {
+        let widget = Tabs::new()
+            .with_tab("A", Label::new("Page A"))
+            .with_tab("B", Label::new("Page B"))
+            .with_selected(1);
+
+        let mut harness = TestHarness::create(widget);
+        harness.edit_root_widget(|mut root| {
+            let mut tabs = root.downcast::<Tabs>().unwrap();
+            tabs.insert_tab(0, "C", Label::new("Page C"));
+            // C, A, B -> A, B, C
+            tabs.move_tab(0, 2);
+            tabs.set_tab_title(0, "Z");
+        });
+        assert_eq!(harness.pop_action(), None);
+
+        let tabs = tabs_of(&harness);
+        assert_eq!(tabs.len(), 3);
+        assert_eq!(tabs.selected(), 1);
+        assert_eq!(tabs.tab_title(0).as_deref(), Some("Z"));
+        assert_eq!(tabs.tab_title(2).as_deref(), Some("C"));
+        assert!(page_is_stashed(&harness, 2));
+
+        // Removing the selected tab selects the one after it.
+        harness.edit_root_widget(|mut root| {
+            let mut tabs = root.downcast::<Tabs>().unwrap();
+            tabs.remove_tab(1);
+        });
+        assert_eq!(tabs_of(&harness).selected(), 1);
+        assert_eq!(tabs_of(&harness).tab_title(1).as_deref(), Some("C"));
+        assert!(!page_is_stashed(&harness, 1));
+    }
+}
@@ -0,0 +1,156 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use accesskit::ActionData;
+
+use crate::testing::{widget_ids, ModularWidget, Record, Recording, TestHarness, TestWidgetExt};
+use crate::widget::{Button, Checkbox, Flex, Label, Portal, SizedBox};
+use crate::*;
+
+fn access_event(target: WidgetId, action: accesskit::Action) -> AccessEvent {
+    AccessEvent {
+        target,
+        action,
+        data: None,
+    }
+}
+
+#[test]
+fn access_tree_snapshot() {
+    let widget = Flex::column()
+        .with_child(Label::new("Hello"))
+        .with_child(Button::new("Press me"))
+        .with_child(Checkbox::new(true, "Check me"));
+
+    let harness = TestHarness::create(widget);
+
+    assert_eq!(
+        harness.access_tree_snapshot(),
+        "\
+Window
+  GenericContainer
+    StaticText \"Hello\"
+    Button
+      StaticText \"Press me\"
+    CheckBox checked=True
+      StaticText \"Check me\"
+"
+    );
+}
+
+#[test]
+fn access_click_button() {
+    let [button_id] = widget_ids();
+    let widget = Flex::column().with_child_id(Button::new("Press me"), button_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.process_access_event(access_event(button_id, accesskit::Action::Default));
+
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed, button_id))
+    );
+}
+
+#[test]
+fn access_toggle_checkbox() {
+    let [checkbox_id] = widget_ids();
+    let widget = Flex::column().with_child_id(Checkbox::new(false, "Check me"), checkbox_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.process_access_event(access_event(checkbox_id, accesskit::Action::Default));
+
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::CheckboxChecked(true), checkbox_id))
+    );
+    assert_eq!(
+        harness.access_tree_snapshot(),
+        "\
+Window
+  GenericContainer
+    CheckBox checked=True
+      StaticText \"Check me\"
+"
+    );
+}
+
+#[test]
+fn only_changed_nodes_are_rebuilt() {
+    let [checkbox_id] = widget_ids();
+    let label_rec = Recording::default();
+    let widget = Flex::column()
+        .with_child_id(Checkbox::new(false, "Check me"), checkbox_id)
+        .with_child(Label::new("Hello").record(&label_rec));
+
+    let mut harness = TestHarness::create(widget);
+    assert!(label_rec
+        .drain()
+        .iter()
+        .any(|record| matches!(record, Record::Access)));
+
+    harness.process_access_event(access_event(checkbox_id, accesskit::Action::Default));
+    assert!(label_rec
+        .drain()
+        .iter()
+        .all(|record| !matches!(record, Record::Access)));
+}
+
+#[test]
+fn access_focus() {
+    let [focusable_id] = widget_ids();
+    let focusable = ModularWidget::new(())
+        .lifecycle_fn(|_, ctx, event| {
+            if let LifeCycle::BuildFocusChain = event {
+                ctx.register_for_focus();
+            }
+        })
+        .with_id(focusable_id);
+
+    let mut harness = TestHarness::create(Flex::row().with_child(focusable));
+    let root_id = harness.root_widget().id();
+    assert_eq!(harness.access_focus(), Some(root_id));
+
+    harness.process_access_event(access_event(focusable_id, accesskit::Action::Focus));
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(focusable_id));
+    assert_eq!(harness.access_focus(), Some(focusable_id));
+}
+
+#[test]
+fn access_scroll_portal() {
+    let [content_id] = widget_ids();
+    let content = SizedBox::empty()
+        .width(100.0)
+        .height(1000.0)
+        .with_id(content_id);
+
+    let mut harness = TestHarness::create_with_size(Portal::new(content), Size::new(400., 400.));
+    let portal_id = harness.root_widget().id();
+    let content_y = |harness: &TestHarness| {
+        harness
+            .get_widget(content_id)
+            .state()
+            .window_layout_rect()
+            .y0
+    };
+
+    harness.process_access_event(access_event(portal_id, accesskit::Action::ScrollDown));
+    assert_eq!(content_y(&harness), -400.0);
+
+    // The viewport can't go past the end of the content.
+    harness.process_access_event(access_event(portal_id, accesskit::Action::ScrollDown));
+    assert_eq!(content_y(&harness), -600.0);
+
+    harness.process_access_event(access_event(portal_id, accesskit::Action::ScrollUp));
+    assert_eq!(content_y(&harness), -200.0);
+
+    harness.process_access_event(AccessEvent {
+        target: portal_id,
+        action: accesskit::Action::SetScrollOffset,
+        data: Some(ActionData::SetScrollOffset(accesskit::Point::new(
+            0.0, 50.0,
+        ))),
+    });
+    assert_eq!(content_y(&harness), -50.0);
+}
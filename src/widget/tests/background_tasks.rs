@@ -0,0 +1,105 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use winit::event::MouseButton;
+
+use crate::promise::PromiseToken;
+use crate::testing::{
+    widget_ids, ModularWidget, Record, Recording, TestHarness, TestWidgetExt as _,
+};
+use crate::widget::{Flex, SizedBox};
+use crate::*;
+
+/// A widget which computes a value in the background when it's added, and stores the result.
+fn compute_widget(value: i32, result: Rc<RefCell<Option<i32>>>) -> impl Widget {
+    ModularWidget::new((None, result)).lifecycle_fn(
+        move |(token, result), ctx, event| match event {
+            LifeCycle::WidgetAdded => {
+                *token = Some(ctx.compute_in_background(move |_| value * 2));
+            }
+            LifeCycle::PromiseResult(promise_result) => {
+                let token: PromiseToken<i32> = token.unwrap();
+                *result.borrow_mut() = Some(promise_result.get(token));
+            }
+            _ => {}
+        },
+    )
+}
+
+#[test]
+fn compute_in_background_delivers_result() {
+    let result = Rc::new(RefCell::new(None));
+    let widget = compute_widget(21, result.clone());
+
+    let _harness = TestHarness::create(widget);
+    assert_eq!(*result.borrow(), Some(42));
+}
+
+#[test]
+fn promise_is_routed_to_originating_widget() {
+    let [compute_id, sibling_id] = widget_ids();
+    let result = Rc::new(RefCell::new(None));
+    let sibling_rec = Recording::default();
+
+    let widget = Flex::row()
+        .with_child_id(compute_widget(5, result.clone()), compute_id)
+        .with_child_id(SizedBox::empty().record(&sibling_rec), sibling_id);
+
+    let _harness = TestHarness::create(widget);
+
+    assert_eq!(*result.borrow(), Some(10));
+    assert!(sibling_rec
+        .drain()
+        .iter()
+        .all(|record| !matches!(record, Record::L(LifeCycle::PromiseResult(_)))));
+}
+
+#[test]
+fn run_in_background_from_event() {
+    let task_ran = Arc::new(AtomicBool::new(false));
+    let task_ran_clone = task_ran.clone();
+
+    let widget =
+        ModularWidget::new(Some(task_ran_clone)).pointer_event_fn(|task_ran, ctx, event| {
+            if let PointerEvent::PointerDown(_, _) = event {
+                let task_ran = task_ran.take().unwrap();
+                ctx.run_in_background(move |_| task_ran.store(true, Ordering::SeqCst));
+            }
+        });
+
+    let mut harness = TestHarness::create(widget);
+    assert!(!task_ran.load(Ordering::SeqCst));
+
+    harness.mouse_button_press(MouseButton::Left);
+    assert!(task_ran.load(Ordering::SeqCst));
+}
+
+#[test]
+fn worker_resolves_promise_manually() {
+    let result = Rc::new(RefCell::new(None));
+    let result_clone = result.clone();
+
+    let token: PromiseToken<String> = PromiseToken::new();
+    let widget =
+        ModularWidget::new(result_clone).lifecycle_fn(move |result, ctx, event| match event {
+            LifeCycle::WidgetAdded => {
+                let target = ctx.widget_id();
+                ctx.run_in_background(move |worker_ctx| {
+                    worker_ctx.resolve_promise(token.make_result("Hello".to_string()), target);
+                });
+            }
+            LifeCycle::PromiseResult(promise_result) => {
+                *result.borrow_mut() = promise_result.try_get(token);
+            }
+            _ => {}
+        });
+
+    let _harness = TestHarness::create(widget);
+    assert_eq!(result.borrow().as_deref(), Some("Hello"));
+}
@@ -0,0 +1,72 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::promise::PromiseToken;
+use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt as _};
+use crate::widget::Flex;
+use crate::*;
+
+#[test]
+fn promise_from_other_thread() {
+    let [widget_id] = widget_ids();
+    let result = Rc::new(RefCell::new(None));
+    let result_clone = result.clone();
+
+    let token: PromiseToken<u32> = PromiseToken::new();
+    let widget = ModularWidget::new(result_clone).lifecycle_fn(move |result, _ctx, event| {
+        if let LifeCycle::PromiseResult(promise_result) = event {
+            *result.borrow_mut() = promise_result.try_get(token);
+        }
+    });
+
+    let mut harness = TestHarness::create(Flex::row().with_child_id(widget, widget_id));
+
+    let sink = harness.ext_event_sink();
+    std::thread::spawn(move || {
+        sink.resolve_promise(token.make_result(7), widget_id)
+            .unwrap();
+    })
+    .join()
+    .unwrap();
+    assert_eq!(*result.borrow(), None);
+
+    harness.process_ext_events();
+    assert_eq!(*result.borrow(), Some(7));
+}
+
+#[test]
+fn action_and_payload_from_other_thread() {
+    let [widget_id] = widget_ids();
+    let mut harness = TestHarness::create(Flex::row().with_child_id(Flex::row(), widget_id));
+
+    let sink = harness.ext_event_sink();
+    std::thread::spawn(move || {
+        sink.submit_action(Action::ButtonPressed, widget_id)
+            .unwrap();
+        sink.submit_payload(String::from("hello")).unwrap();
+    })
+    .join()
+    .unwrap();
+
+    harness.process_ext_events();
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed, widget_id))
+    );
+    let payload = harness.pop_ext_payload().unwrap();
+    assert_eq!(*payload.downcast::<String>().unwrap(), "hello");
+    assert!(harness.pop_ext_payload().is_none());
+}
+
+#[test]
+fn submit_after_shutdown() {
+    let harness = TestHarness::create(Flex::row());
+    let sink = harness.ext_event_sink();
+    drop(harness);
+
+    assert!(sink.submit_payload(42).is_err());
+}
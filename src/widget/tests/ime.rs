@@ -0,0 +1,124 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use winit::event::Ime;
+
+use crate::testing::{widget_ids, ModularWidget, TestHarness};
+use crate::widget::{CrossAxisAlignment, Flex};
+use crate::*;
+
+const CARET_WIDTH: f64 = 1.0;
+const CHAR_WIDTH: f64 = 10.0;
+
+/// A widget which accepts text input, and logs the IME events it receives.
+///
+/// Its caret moves right with each committed character.
+fn text_field(events: Rc<RefCell<Vec<Ime>>>) -> impl Widget {
+    ModularWidget::new((events, 0.0))
+        .pointer_event_fn(|_, ctx, event| {
+            if let PointerEvent::PointerDown(..) = event {
+                ctx.request_focus();
+            }
+        })
+        .text_event_fn(|(events, caret_x), ctx, event| {
+            if let TextEvent::Ime(ime) = event {
+                if let Ime::Commit(text) = ime {
+                    *caret_x += CHAR_WIDTH * text.chars().count() as f64;
+                    ctx.request_layout();
+                }
+                events.borrow_mut().push(ime.clone());
+                ctx.set_handled();
+            }
+        })
+        .lifecycle_fn(|_, ctx, event| match event {
+            LifeCycle::WidgetAdded => ctx.register_as_text_input(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            _ => {}
+        })
+        .layout_fn(|(_, caret_x), ctx, _| {
+            ctx.set_ime_area(Rect::new(*caret_x, 0., *caret_x + CARET_WIDTH, 20.));
+            Size::new(100., 20.)
+        })
+}
+
+/// A focusable widget which doesn't accept text input.
+fn focus_taker() -> impl Widget {
+    ModularWidget::new(())
+        .pointer_event_fn(|_, ctx, event| {
+            if let PointerEvent::PointerDown(..) = event {
+                ctx.request_focus();
+            }
+        })
+        .lifecycle_fn(|_, ctx, event| {
+            if let LifeCycle::BuildFocusChain = event {
+                ctx.register_for_focus();
+            }
+        })
+        .layout_fn(|_, _, _| Size::new(100., 20.))
+}
+
+#[test]
+fn ime_enabled_while_text_field_focused() {
+    let [text_field_id, other_id] = widget_ids();
+    let widget = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_spacer(50.)
+        .with_child_id(text_field(Default::default()), text_field_id)
+        .with_child_id(focus_taker(), other_id);
+
+    let mut harness = TestHarness::create(widget);
+    assert_eq!(harness.ime_area(), None);
+
+    harness.mouse_click_on(text_field_id);
+    assert_eq!(
+        harness.ime_area(),
+        Some(Rect::new(0., 50., CARET_WIDTH, 70.))
+    );
+
+    harness.mouse_click_on(other_id);
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(other_id));
+    assert_eq!(harness.ime_area(), None);
+}
+
+#[test]
+fn ime_events_reach_focused_text_field() {
+    let [field_1_id, field_2_id] = widget_ids();
+    let events_1 = Rc::new(RefCell::new(Vec::new()));
+    let events_2 = Rc::new(RefCell::new(Vec::new()));
+    let widget = Flex::column()
+        .with_child_id(text_field(events_1.clone()), field_1_id)
+        .with_child_id(text_field(events_2.clone()), field_2_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.mouse_click_on(field_1_id);
+
+    let preedit = Ime::Preedit("か".to_string(), Some((0, 3)));
+    let commit = Ime::Commit("か".to_string());
+    harness.process_text_event(TextEvent::Ime(preedit.clone()));
+    harness.process_text_event(TextEvent::Ime(commit.clone()));
+
+    assert_eq!(*events_1.borrow(), vec![preedit, commit]);
+    assert!(events_2.borrow().is_empty());
+}
+
+#[test]
+fn ime_area_follows_caret() {
+    let [text_field_id] = widget_ids();
+    let widget = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child_id(text_field(Default::default()), text_field_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.mouse_click_on(text_field_id);
+    harness.keyboard_type_chars("abc");
+
+    let caret_x = 3.0 * CHAR_WIDTH;
+    assert_eq!(
+        harness.ime_area(),
+        Some(Rect::new(caret_x, 0., caret_x + CARET_WIDTH, 20.))
+    );
+}
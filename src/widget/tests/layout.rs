@@ -5,7 +5,7 @@
 //! Tests related to layout.
 
 use crate::kurbo::{Insets, Size};
-use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt};
+use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt, HARNESS_DEFAULT_SIZE};
 use crate::widget::{Flex, SizedBox};
 
 #[test]
@@ -72,3 +72,40 @@ fn layout_insets() {
 // TODO - insets + flex
 // TODO - viewport
 // TODO - insets + viewport
+
+#[test]
+fn layout_in_logical_pixels() {
+    let [box_id] = widget_ids();
+
+    let widget = Flex::row()
+        .with_child_id(SizedBox::empty().width(50.).height(50.), box_id)
+        .with_flex_spacer(1.0);
+
+    let mut harness = TestHarness::create(widget);
+    harness.set_scale_factor(2.0);
+
+    // The window keeps its logical size.
+    assert_eq!(
+        harness.root_widget().state().layout_rect().size(),
+        HARNESS_DEFAULT_SIZE
+    );
+    assert_eq!(
+        harness.get_widget(box_id).state().layout_rect().size(),
+        Size::new(50., 50.)
+    );
+
+    // Pointer positions are in logical pixels too.
+    harness.mouse_move((40., 40.));
+    assert!(harness.get_widget(box_id).state().is_hot);
+    harness.mouse_move((60., 60.));
+    assert!(!harness.get_widget(box_id).state().is_hot);
+
+    let image = harness.render();
+    assert_eq!(
+        image.dimensions(),
+        (
+            2 * HARNESS_DEFAULT_SIZE.width as u32,
+            2 * HARNESS_DEFAULT_SIZE.height as u32
+        )
+    );
+}
@@ -239,3 +239,51 @@ fn resign_focus_on_disable() {
     assert_eq!(harness.window().focus_chain(), &[focus_2]);
     assert_eq!(harness.window().focus, None);
 }
+
+/// Check that Tab and Shift+Tab move the focus along the focus chain, in tree order.
+#[test]
+fn tab_moves_focus_through_tree() {
+    use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+    use crate::widget::Switch;
+
+    let [id_1, id_2, id_3] = widget_ids();
+
+    let widget = Flex::column()
+        .with_child_id(Switch::new(false), id_1)
+        .with_child(
+            Flex::row()
+                .with_child_id(Switch::new(false), id_2)
+                .with_child_id(Switch::new(false), id_3),
+        );
+
+    let mut harness = TestHarness::create(widget);
+    let tab = || Key::Named(NamedKey::Tab);
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|w| w.id());
+    assert_eq!(focused(&harness), None);
+
+    // With nothing focused, Tab focuses the first widget of the chain.
+    harness.keyboard_press_unused_key(tab(), ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_1));
+    harness.keyboard_press_unused_key(tab(), ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_2));
+    harness.keyboard_press_unused_key(tab(), ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_3));
+    // The focus wraps around.
+    harness.keyboard_press_unused_key(tab(), ModifiersState::empty());
+    assert_eq!(focused(&harness), Some(id_1));
+
+    harness.keyboard_press_unused_key(tab(), ModifiersState::SHIFT);
+    assert_eq!(focused(&harness), Some(id_3));
+    harness.keyboard_press_unused_key(tab(), ModifiersState::SHIFT);
+    assert_eq!(focused(&harness), Some(id_2));
+
+    // With nothing focused, Shift+Tab focuses the last widget of the chain.
+    let mut harness = TestHarness::create(
+        Flex::column()
+            .with_child_id(Switch::new(false), id_1)
+            .with_child_id(Switch::new(false), id_2),
+    );
+    harness.keyboard_press_unused_key(tab(), ModifiersState::SHIFT);
+    assert_eq!(focused(&harness), Some(id_2));
+}
@@ -4,9 +4,15 @@
 
 // TODO - See https://github.com/PoignardAzur/masonry-rs/issues/58
 
+mod accessibility;
+mod background_tasks;
+mod ext_events;
+mod ime;
 mod layout;
 mod lifecycle_basic;
 mod lifecycle_disable;
 mod lifecycle_focus;
+mod popups;
 mod safety_rails;
 mod status_change;
+mod timers;
@@ -0,0 +1,230 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use winit::event::MouseButton;
+
+use crate::testing::{widget_ids, ModularWidget, TestHarness};
+use crate::widget::{Button, CrossAxisAlignment, Flex};
+use crate::*;
+
+#[derive(Default)]
+struct PopupLog {
+    opened: Option<WidgetId>,
+    closed: Vec<WidgetId>,
+    /// The results of the closed popups, if they were `&str`s.
+    results: Vec<Option<&'static str>>,
+}
+
+/// A widget which opens a popup below itself when clicked, and focuses it.
+fn popup_opener<W: Widget>(
+    log: Rc<RefCell<PopupLog>>,
+    make_popup: impl Fn() -> W + 'static,
+) -> impl Widget {
+    ModularWidget::new(log)
+        .pointer_event_fn(move |log, ctx, event| {
+            if let PointerEvent::PointerDown(_, _) = event {
+                let anchor = ctx.size().to_rect() + ctx.window_origin().to_vec2();
+                let popup_id = ctx.open_popup(make_popup(), anchor);
+                log.borrow_mut().opened = Some(popup_id);
+                ctx.set_focus(popup_id);
+                ctx.set_handled();
+            }
+        })
+        .lifecycle_fn(|log, ctx, event| match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::PopupClosed(popup_id, result) => {
+                let mut log = log.borrow_mut();
+                log.closed.push(*popup_id);
+                log.results.push(
+                    result
+                        .as_ref()
+                        .and_then(|result| result.get::<&'static str>().copied()),
+                );
+            }
+            _ => {}
+        })
+        .layout_fn(|_, _ctx, bc| bc.constrain(Size::new(100.0, 20.0)))
+}
+
+fn open_popup(harness: &mut TestHarness, opener_id: WidgetId, log: &RefCell<PopupLog>) -> WidgetId {
+    harness.mouse_click_on(opener_id);
+    log.borrow().opened.expect("popup wasn't opened")
+}
+
+#[test]
+fn popup_is_placed_below_anchor() {
+    let [opener_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    let widget = Flex::column().with_child_id(
+        popup_opener(log.clone(), || Button::new("Popup button")),
+        opener_id,
+    );
+
+    let mut harness = TestHarness::create(widget);
+    assert!(harness.popups().is_empty());
+
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+    let popups = harness.popups();
+    assert_eq!(popups.len(), 1);
+    assert_eq!(popups[0].id(), popup_id);
+
+    let anchor = harness.get_widget(opener_id).state().window_layout_rect();
+    let popup_rect = harness.get_widget(popup_id).state().window_layout_rect();
+    assert_eq!(popup_rect.origin(), Point::new(anchor.x0, anchor.y1));
+}
+
+#[test]
+fn popup_takes_pointer_from_root() {
+    let [opener_id, covered_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    let widget = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child_id(
+            popup_opener(log.clone(), || Button::new("Popup button")),
+            opener_id,
+        )
+        .with_child_id(Button::new("Covered button"), covered_id);
+
+    let mut harness = TestHarness::create(widget);
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+
+    let popup_rect = harness.get_widget(popup_id).state().window_layout_rect();
+    let covered_rect = harness.get_widget(covered_id).state().window_layout_rect();
+    assert!(popup_rect.contains(covered_rect.origin() + Vec2::new(1.0, 1.0)));
+
+    harness.mouse_click_on(popup_id);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed, popup_id))
+    );
+    assert_eq!(harness.pop_action(), None);
+    assert!(!harness.get_widget(covered_id).state().is_hot);
+}
+
+#[test]
+fn click_outside_closes_popup() {
+    let [opener_id, other_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    let widget = Flex::row()
+        .with_child_id(
+            popup_opener(log.clone(), || Button::new("Popup button")),
+            opener_id,
+        )
+        .with_spacer(200.0)
+        .with_child_id(Button::new("Other button"), other_id);
+
+    let mut harness = TestHarness::create(widget);
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+
+    // The click only closes the popup.
+    harness.mouse_click_on(other_id);
+    assert!(harness.popups().is_empty());
+    assert!(harness.try_get_widget(popup_id).is_none());
+    assert_eq!(log.borrow().closed, vec![popup_id]);
+    assert_eq!(harness.pop_action(), None);
+
+    harness.mouse_click_on(other_id);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed, other_id))
+    );
+}
+
+#[test]
+fn closing_popup_returns_focus_to_owner() {
+    let [opener_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    // The popup closes itself when clicked.
+    let opener = popup_opener(log.clone(), || {
+        ModularWidget::new(())
+            .lifecycle_fn(|_, ctx, event| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .pointer_event_fn(|_, ctx, event| {
+                if let PointerEvent::PointerDown(_, _) = event {
+                    ctx.close_popup(ctx.widget_id());
+                    ctx.set_handled();
+                }
+            })
+            .layout_fn(|_, _ctx, bc| bc.constrain(Size::new(100.0, 50.0)))
+    });
+    let widget = Flex::column().with_child_id(opener, opener_id);
+
+    let mut harness = TestHarness::create(widget);
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(popup_id));
+
+    harness.mouse_click_on(popup_id);
+    assert!(harness.popups().is_empty());
+    assert_eq!(log.borrow().closed, vec![popup_id]);
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(opener_id));
+}
+
+#[test]
+fn popup_result_goes_to_owner() {
+    let [opener_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    // The popup closes itself with a result when clicked.
+    let opener = popup_opener(log.clone(), || {
+        ModularWidget::new(())
+            .lifecycle_fn(|_, ctx, event| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .pointer_event_fn(|_, ctx, event| {
+                if let PointerEvent::PointerDown(_, _) = event {
+                    ctx.close_popup_with_result(ctx.widget_id(), "picked");
+                    ctx.set_handled();
+                }
+            })
+            .layout_fn(|_, _ctx, bc| bc.constrain(Size::new(100.0, 50.0)))
+    });
+    let widget = Flex::column()
+        .with_child_id(opener, opener_id)
+        .with_spacer(200.0);
+
+    let mut harness = TestHarness::create(widget);
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+    harness.mouse_click_on(popup_id);
+    assert_eq!(log.borrow().closed, vec![popup_id]);
+    assert_eq!(log.borrow().results, vec![Some("picked")]);
+
+    // Dismissed popups have no result.
+    open_popup(&mut harness, opener_id, &log);
+    harness.mouse_move((1.0, 200.0));
+    harness.mouse_button_press(MouseButton::Left);
+    harness.mouse_button_release(MouseButton::Left);
+    assert_eq!(log.borrow().results, vec![Some("picked"), None]);
+}
+
+#[test]
+fn removing_owner_closes_popup() {
+    let [opener_id] = widget_ids();
+    let log = Rc::new(RefCell::new(PopupLog::default()));
+    let widget = Flex::column()
+        .with_child_id(
+            popup_opener(log.clone(), || Button::new("Popup button")),
+            opener_id,
+        )
+        .with_child(Button::new("Other button"));
+
+    let mut harness = TestHarness::create(widget);
+    let popup_id = open_popup(&mut harness, opener_id, &log);
+    assert_eq!(harness.focused_widget().map(|w| w.id()), Some(popup_id));
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>().unwrap();
+        flex.remove_child(0);
+    });
+    assert!(harness.popups().is_empty());
+    assert!(harness.try_get_widget(popup_id).is_none());
+    // The popup's focus isn't left dangling.
+    assert_eq!(harness.focused_widget().map(|w| w.id()), None);
+}
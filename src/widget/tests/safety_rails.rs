@@ -17,6 +17,9 @@ fn make_parent_widget<W: Widget>(child: W) -> ModularWidget<WidgetPod<W>> {
         .text_event_fn(move |child, ctx, event| {
             child.on_text_event(ctx, event);
         })
+        .access_event_fn(move |child, ctx, event| {
+            child.on_access_event(ctx, event);
+        })
         .lifecycle_fn(move |child, ctx, event| child.lifecycle(ctx, event))
         .layout_fn(move |child, ctx, bc| {
             let size = child.layout(ctx, bc);
@@ -26,6 +29,9 @@ fn make_parent_widget<W: Widget>(child: W) -> ModularWidget<WidgetPod<W>> {
         .paint_fn(move |child, ctx, scene| {
             child.paint(ctx, scene);
         })
+        .accessibility_fn(move |child, ctx| {
+            child.accessibility(ctx);
+        })
         .children_fn(|child| smallvec![child.as_dyn()])
 }
 
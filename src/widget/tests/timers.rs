@@ -0,0 +1,117 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::testing::{
+    widget_ids, ModularWidget, Record, Recording, TestHarness, TestWidgetExt as _,
+};
+use crate::widget::{Flex, SizedBox};
+use crate::*;
+
+/// A widget which requests a timer when it's added, and logs its delay once the timer fires.
+fn timer_widget(delay: Duration, fired: Rc<RefCell<Vec<Duration>>>) -> impl Widget {
+    ModularWidget::new((None, fired)).lifecycle_fn(move |(token, fired), ctx, event| match event {
+        LifeCycle::WidgetAdded => {
+            *token = Some(ctx.request_timer(delay));
+        }
+        LifeCycle::Timer(received) => {
+            assert_eq!(Some(*received), *token);
+            fired.borrow_mut().push(delay);
+        }
+        _ => {}
+    })
+}
+
+#[test]
+fn timer_fires_after_deadline() {
+    let fired = Rc::new(RefCell::new(Vec::new()));
+    let widget = timer_widget(Duration::from_millis(100), fired.clone());
+
+    let mut harness = TestHarness::create(widget);
+    assert!(fired.borrow().is_empty());
+
+    harness.move_timers_forward(Duration::from_millis(50));
+    assert!(fired.borrow().is_empty());
+
+    harness.move_timers_forward(Duration::from_millis(50));
+    assert_eq!(fired.borrow().len(), 1);
+
+    // Timers only fire once.
+    harness.move_timers_forward(Duration::from_millis(500));
+    assert_eq!(fired.borrow().len(), 1);
+}
+
+#[test]
+fn timer_is_routed_to_requesting_widget() {
+    let [timer_id, sibling_id] = widget_ids();
+    let fired = Rc::new(RefCell::new(Vec::new()));
+    let sibling_rec = Recording::default();
+
+    let widget = Flex::row()
+        .with_child_id(
+            timer_widget(Duration::from_millis(100), fired.clone()),
+            timer_id,
+        )
+        .with_child_id(SizedBox::empty().record(&sibling_rec), sibling_id);
+
+    let mut harness = TestHarness::create(widget);
+    sibling_rec.clear();
+
+    harness.move_timers_forward(Duration::from_millis(100));
+    assert_eq!(fired.borrow().len(), 1);
+    assert!(sibling_rec
+        .drain()
+        .iter()
+        .all(|record| !matches!(record, Record::L(LifeCycle::Timer(_)))));
+}
+
+#[test]
+fn timers_fire_in_deadline_order() {
+    let fired = Rc::new(RefCell::new(Vec::new()));
+
+    let widget = Flex::row()
+        .with_child(timer_widget(Duration::from_millis(300), fired.clone()))
+        .with_child(timer_widget(Duration::from_millis(100), fired.clone()))
+        .with_child(timer_widget(Duration::from_millis(200), fired.clone()));
+
+    let mut harness = TestHarness::create(widget);
+    harness.move_timers_forward(Duration::from_secs(1));
+
+    assert_eq!(
+        *fired.borrow(),
+        [
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(300),
+        ]
+    );
+}
+
+#[test]
+fn repeating_timer() {
+    let ticks = Rc::new(RefCell::new(0));
+    let ticks_clone = ticks.clone();
+
+    let widget = ModularWidget::new(ticks_clone).lifecycle_fn(|ticks, ctx, event| match event {
+        LifeCycle::WidgetAdded => {
+            ctx.request_timer(Duration::from_millis(100));
+        }
+        LifeCycle::Timer(_) => {
+            *ticks.borrow_mut() += 1;
+            ctx.request_timer(Duration::from_millis(100));
+        }
+        _ => {}
+    });
+
+    let mut harness = TestHarness::create(widget);
+
+    harness.move_timers_forward(Duration::from_millis(350));
+    assert_eq!(*ticks.borrow(), 3);
+
+    harness.move_timers_forward(Duration::from_millis(50));
+    assert_eq!(*ticks.borrow(), 4);
+}
@@ -0,0 +1,956 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A multi-line text editing widget.
+
+use std::ops::Range;
+
+use accesskit::{ActionData, Role};
+use kurbo::{Affine, Line, Stroke};
+use parley::layout::Alignment;
+use parley::style::{FontFamily, FontStack, GenericFamily, StyleProperty};
+use parley::{FontContext, Layout};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::{BlendMode, Brush, Fill};
+use vello::Scene;
+use winit::dpi::LogicalPosition;
+use winit::event::ElementState;
+
+use crate::action::Action;
+use crate::paint_scene_helpers::stroke;
+use crate::text_helpers::{
+    caret_rect, caret_x, edit_command, line_index, line_rect, move_selection, movement_offset,
+    offset_for_point, prev_char_offset, selection_rects, word_range, EditCommand, ImeChangeSignal,
+    ImeTextField, Movement, Preedit, Selection,
+};
+use crate::widget::{LineBreaking, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget,
+};
+
+const CARET_WIDTH: f64 = 1.0;
+/// How many lines PageUp and PageDown move the caret by.
+const PAGE_LINES: isize = 20;
+
+/// A widget for editing multiple lines of text.
+///
+/// Lines are wrapped according to its [`LineBreaking`] mode, which defaults to
+/// [`LineBreaking::WordWrap`]. The editor grows to fit its text; put it in a
+/// [`Portal`](crate::widget::Portal) to scroll it. The caret is kept in view as it moves.
+///
+/// Edits can be undone and redone, with consecutive typing undone in one step.
+///
+/// Emits [`Action::TextChanged`] when the user edits the text.
+pub struct TextEditor {
+    text: String,
+    selection: Selection,
+    preedit: Option<Preedit>,
+    /// The horizontal position that vertical caret movement tries to keep.
+    ///
+    /// It's reset whenever the caret moves in any other way.
+    preferred_x: Option<f64>,
+    history: History,
+    text_layout: Option<Layout<Brush>>,
+    /// The width lines are wrapped at, from the last layout.
+    max_advance: Option<f32>,
+    text_size: f32,
+    font_family: FontFamily<'static>,
+    line_break_mode: LineBreaking,
+    disabled: bool,
+}
+
+crate::declare_widget!(TextEditorMut, TextEditor);
+
+/// A change to the text, which can be undone and redone.
+#[derive(Debug, Clone)]
+struct Edit {
+    /// The offset where the change starts.
+    start: usize,
+    /// The text that was replaced.
+    old_text: String,
+    /// The text that replaced it.
+    new_text: String,
+    selection_before: Selection,
+    selection_after: Selection,
+}
+
+#[derive(Debug, Default)]
+struct History {
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    /// Whether the next typed text can be merged into the last edit.
+    is_typing: bool,
+}
+
+// --- METHODS ---
+
+impl History {
+    fn record(&mut self, edit: Edit, is_typing: bool) {
+        self.redo_stack.clear();
+        if let Some(last) = self.undo_stack.last_mut() {
+            let follows_last = last.start + last.new_text.len() == edit.start;
+            if is_typing && self.is_typing && follows_last && edit.old_text.is_empty() {
+                last.new_text.push_str(&edit.new_text);
+                last.selection_after = edit.selection_after;
+                return;
+            }
+        }
+        self.undo_stack.push(edit);
+        self.is_typing = is_typing;
+    }
+
+    /// Stop merging typed text into the last edit.
+    fn break_group(&mut self) {
+        self.is_typing = false;
+    }
+
+    /// Revert the last edit, and return the selection from before it.
+    fn undo(&mut self, text: &mut String) -> Option<Selection> {
+        let edit = self.undo_stack.pop()?;
+        text.replace_range(edit.start..edit.start + edit.new_text.len(), &edit.old_text);
+        let selection = edit.selection_before;
+        self.redo_stack.push(edit);
+        self.is_typing = false;
+        Some(selection)
+    }
+
+    /// Reapply the last undone edit, and return the selection from after it.
+    fn redo(&mut self, text: &mut String) -> Option<Selection> {
+        let edit = self.redo_stack.pop()?;
+        text.replace_range(edit.start..edit.start + edit.old_text.len(), &edit.new_text);
+        let selection = edit.selection_after;
+        self.undo_stack.push(edit);
+        self.is_typing = false;
+        Some(selection)
+    }
+}
+
+impl TextEditor {
+    /// Create a new text editor, with the caret at the end of `text`.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = normalize_newlines(&text.into());
+        Self {
+            selection: Selection::caret(text.len()),
+            text,
+            preedit: None,
+            preferred_x: None,
+            history: History::default(),
+            text_layout: None,
+            max_advance: None,
+            text_size: theme::TEXT_SIZE_NORMAL as f32,
+            font_family: FontFamily::Generic(GenericFamily::SystemUi),
+            line_break_mode: LineBreaking::WordWrap,
+            disabled: false,
+        }
+    }
+
+    /// Create a text editor with empty text.
+    pub fn empty() -> Self {
+        Self::new("")
+    }
+
+    /// Builder-style method for setting the text size.
+    pub fn with_text_size(mut self, size: impl Into<f32>) -> Self {
+        self.text_size = size.into();
+        self
+    }
+
+    /// Builder-style method for setting the font.
+    pub fn with_font_family(mut self, font_family: impl Into<FontFamily<'static>>) -> Self {
+        self.font_family = font_family.into();
+        self
+    }
+
+    /// Builder-style method to set the [`LineBreaking`] behaviour.
+    pub fn with_line_break_mode(mut self, mode: LineBreaking) -> Self {
+        self.line_break_mode = mode;
+        self
+    }
+
+    /// Return the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Return the current selection.
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Whether there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.history.undo_stack.is_empty()
+    }
+
+    /// Whether there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.history.redo_stack.is_empty()
+    }
+
+    fn rebuild_layout(&mut self, font_cx: &mut FontContext) {
+        if self.text_layout.is_some() {
+            return;
+        }
+        let color = if self.disabled {
+            theme::DISABLED_TEXT_COLOR
+        } else {
+            theme::TEXT_COLOR
+        };
+        // A trailing newline doesn't start a new line in the layout, so we add a
+        // space to give the caret somewhere to go.
+        let mut text = self.display_text();
+        if text.is_empty() || text.ends_with('\n') {
+            text.push(' ');
+        }
+
+        let mut lcx = parley::LayoutContext::new();
+        let mut layout_builder = lcx.ranged_builder(font_cx, &text, 1.0);
+        layout_builder.push_default(&StyleProperty::FontStack(FontStack::Single(
+            self.font_family,
+        )));
+        layout_builder.push_default(&StyleProperty::FontSize(self.text_size));
+        layout_builder.push_default(&StyleProperty::Brush(Brush::Solid(color)));
+
+        let mut layout = layout_builder.build();
+        layout.break_all_lines(self.max_advance, Alignment::Start);
+        self.text_layout = Some(layout);
+    }
+
+    /// The origin of the text layout, in local coordinates.
+    fn text_origin(&self) -> Point {
+        let insets = theme::TEXTBOX_INSETS;
+        Point::new(insets.x0, insets.y0)
+    }
+
+    /// The offset in the text closest to the given window position.
+    fn offset_for_position(&self, ctx: &EventCtx, position: LogicalPosition<f64>) -> usize {
+        let Some(text_layout) = &self.text_layout else {
+            return self.selection.active;
+        };
+        let local_pos = Point::new(position.x, position.y) - ctx.window_origin().to_vec2();
+        let text_pos = local_pos - self.text_origin().to_vec2();
+        // The layout holds the displayed text, with the preedit text.
+        self.committed_offset(offset_for_point(text_layout, text_pos))
+            .min(self.text.len())
+    }
+
+    /// The start and end of the line holding `offset`, not counting the character
+    /// the line breaks at.
+    fn line_bounds(&self, layout: &Layout<Brush>, offset: usize) -> Range<usize> {
+        let index = line_index(layout, offset);
+        let Some(line) = layout.get(index) else {
+            return 0..self.text.len();
+        };
+        let range = line.text_range();
+        let end = range.end.min(self.text.len());
+        let start = range.start.min(end);
+        let line_text = &self.text[start..end];
+        let is_last_line = index + 1 == layout.len();
+        let breaks_at_last_char = line_text.ends_with('\n')
+            || (!is_last_line && line_text.ends_with(char::is_whitespace));
+        if breaks_at_last_char {
+            start..prev_char_offset(&self.text, end)
+        } else {
+            start..end
+        }
+    }
+
+    /// The offset `lines` lines below the caret (or above if negative), as close as
+    /// possible to the preferred horizontal position.
+    fn offset_for_line_move(&mut self, lines: isize) -> usize {
+        let text_layout = self.text_layout.as_ref().unwrap();
+        let caret = self.selection.active;
+        let x = *self
+            .preferred_x
+            .get_or_insert_with(|| caret_x(text_layout, caret));
+        let target = line_index(text_layout, caret) as isize + lines;
+        if target < 0 {
+            0
+        } else if target >= text_layout.len() as isize {
+            self.text.len()
+        } else {
+            let line = line_rect(text_layout, target as usize);
+            offset_for_point(text_layout, Point::new(x, line.center().y)).min(self.text.len())
+        }
+    }
+
+    /// Ask parent portals to scroll the caret into view.
+    fn pan_to_caret(&mut self, ctx: &mut EventCtx) {
+        self.rebuild_layout(ctx.font_ctx());
+        let text_layout = self.text_layout.as_ref().unwrap();
+        let caret = caret_rect(text_layout, self.display_caret(), CARET_WIDTH);
+        ctx.request_pan_to_rect(caret + self.text_origin().to_vec2());
+    }
+
+    fn set_selection(&mut self, ctx: &mut EventCtx, selection: Selection) {
+        self.preferred_x = None;
+        self.history.break_group();
+        if selection != self.selection {
+            self.selection = selection;
+            // Layout moves the IME area.
+            ctx.request_layout();
+            self.pan_to_caret(ctx);
+        }
+    }
+
+    /// Replace `range` with `new_text`, put the caret after it, and record the edit.
+    ///
+    /// If `is_typing` is true, the edit may be merged with the previous one in the
+    /// undo history.
+    fn replace(
+        &mut self,
+        ctx: &mut EventCtx,
+        range: Range<usize>,
+        new_text: &str,
+        is_typing: bool,
+    ) {
+        let edit = Edit {
+            start: range.start,
+            old_text: self.text[range.clone()].to_string(),
+            new_text: new_text.to_string(),
+            selection_before: self.selection,
+            selection_after: Selection::caret(range.start + new_text.len()),
+        };
+        self.text.replace_range(range, new_text);
+        self.selection = edit.selection_after;
+        self.preferred_x = None;
+        self.history.record(edit, is_typing);
+        self.text_changed(ctx);
+    }
+
+    /// Replace the selected text with `new_text`, and put the caret after it.
+    fn insert(&mut self, ctx: &mut EventCtx, new_text: &str) {
+        let new_text: String = normalize_newlines(new_text)
+            .chars()
+            .filter(|c| *c == '\n' || !c.is_control())
+            .collect();
+        if new_text.is_empty() && self.selection.is_caret() {
+            return;
+        }
+        // Each new line starts a new undo step.
+        let is_typing = !new_text.contains('\n');
+        self.replace(ctx, self.selection.range(), &new_text, is_typing);
+    }
+
+    /// Delete the selected text, or if the selection is empty, the text between the caret and `offset`.
+    fn delete_towards(&mut self, ctx: &mut EventCtx, offset: usize) {
+        let range = if self.selection.is_caret() {
+            let caret = self.selection.active;
+            caret.min(offset)..caret.max(offset)
+        } else {
+            self.selection.range()
+        };
+        if range.is_empty() {
+            return;
+        }
+        self.replace(ctx, range, "", false);
+    }
+
+    fn undo(&mut self, ctx: &mut EventCtx) {
+        if let Some(selection) = self.history.undo(&mut self.text) {
+            self.selection = selection;
+            self.preferred_x = None;
+            self.text_changed(ctx);
+        }
+    }
+
+    fn redo(&mut self, ctx: &mut EventCtx) {
+        if let Some(selection) = self.history.redo(&mut self.text) {
+            self.selection = selection;
+            self.preferred_x = None;
+            self.text_changed(ctx);
+        }
+    }
+
+    fn text_changed(&mut self, ctx: &mut EventCtx) {
+        trace!(
+            "TextEditor {:?} text changed to {:?}",
+            ctx.widget_id(),
+            self.text
+        );
+        self.text_layout = None;
+        ctx.submit_action(Action::TextChanged(self.text.clone()));
+        ctx.request_layout();
+        ctx.request_accessibility_update();
+        self.pan_to_caret(ctx);
+    }
+
+    /// Move the caret to another line, or to the start or end of its line.
+    fn move_in_layout(&mut self, ctx: &mut EventCtx, movement: Movement, extend: bool) {
+        let lines = match movement {
+            Movement::LineUp => -1,
+            Movement::LineDown => 1,
+            Movement::PageUp => -PAGE_LINES,
+            Movement::PageDown => PAGE_LINES,
+            _ => {
+                let text_layout = self.text_layout.as_ref().unwrap();
+                let line = self.line_bounds(text_layout, self.selection.active);
+                let offset = if movement == Movement::LineStart {
+                    line.start
+                } else {
+                    line.end
+                };
+                self.set_selection(ctx, self.selection.move_to(offset, extend));
+                return;
+            }
+        };
+        let offset = self.offset_for_line_move(lines);
+        let preferred_x = self.preferred_x;
+        self.set_selection(ctx, self.selection.move_to(offset, extend));
+        self.preferred_x = preferred_x;
+    }
+}
+
+/// Convert Windows and old Mac line endings to `\n`.
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+impl TextEditorMut<'_> {
+    /// Set the text, and put the caret at its end.
+    ///
+    /// This clears the undo history, and doesn't emit [`Action::TextChanged`].
+    pub fn set_text(&mut self, new_text: impl Into<String>) {
+        self.widget.text = normalize_newlines(&new_text.into());
+        self.widget.selection = Selection::caret(self.widget.text.len());
+        self.widget.preedit = None;
+        self.widget.preferred_x = None;
+        self.widget.history = History::default();
+        self.widget.text_layout = None;
+        self.ctx.invalidate_text_input(ImeChangeSignal::Reset);
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the selection.
+    ///
+    /// The offsets are clamped to the text, and to `char` boundaries.
+    pub fn set_selection(&mut self, selection: Selection) {
+        self.widget.selection = selection.constrained(&self.widget.text);
+        self.widget.preferred_x = None;
+        self.widget.history.break_group();
+        self.ctx
+            .invalidate_text_input(ImeChangeSignal::SelectionChanged);
+        self.ctx.request_layout();
+    }
+
+    /// Set the text size.
+    pub fn set_text_size(&mut self, size: impl Into<f32>) {
+        self.widget.text_size = size.into();
+        self.widget.text_layout = None;
+        self.ctx.request_layout();
+    }
+
+    /// Set the font.
+    pub fn set_font_family(&mut self, font_family: impl Into<FontFamily<'static>>) {
+        self.widget.font_family = font_family.into();
+        self.widget.text_layout = None;
+        self.ctx.request_layout();
+    }
+
+    /// Set the [`LineBreaking`] behaviour.
+    pub fn set_line_break_mode(&mut self, mode: LineBreaking) {
+        self.widget.line_break_mode = mode;
+        self.widget.text_layout = None;
+        self.ctx.request_layout();
+    }
+
+    /// Undo the last edit. Returns `false` if there was nothing to undo.
+    ///
+    /// This doesn't emit [`Action::TextChanged`].
+    pub fn undo(&mut self) -> bool {
+        let Some(selection) = self.widget.history.undo(&mut self.widget.text) else {
+            return false;
+        };
+        self.widget.selection = selection;
+        self.text_changed();
+        true
+    }
+
+    /// Redo the last undone edit. Returns `false` if there was nothing to redo.
+    ///
+    /// This doesn't emit [`Action::TextChanged`].
+    pub fn redo(&mut self) -> bool {
+        let Some(selection) = self.widget.history.redo(&mut self.widget.text) else {
+            return false;
+        };
+        self.widget.selection = selection;
+        self.text_changed();
+        true
+    }
+
+    fn text_changed(&mut self) {
+        self.widget.preedit = None;
+        self.widget.preferred_x = None;
+        self.widget.text_layout = None;
+        self.ctx.invalidate_text_input(ImeChangeSignal::Reset);
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl ImeTextField for TextEditor {
+    fn committed_text(&self) -> &str {
+        &self.text
+    }
+
+    fn caret(&self) -> usize {
+        self.selection.active
+    }
+
+    fn preedit(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    fn preedit_mut(&mut self) -> &mut Option<Preedit> {
+        &mut self.preedit
+    }
+
+    fn delete_selection(&mut self, ctx: &mut EventCtx) {
+        self.delete_towards(ctx, self.selection.active);
+    }
+
+    fn insert_committed(&mut self, ctx: &mut EventCtx, text: &str) {
+        self.insert(ctx, text);
+    }
+
+    fn preedit_changed(&mut self, ctx: &mut EventCtx) {
+        self.text_layout = None;
+        ctx.request_layout();
+        self.pan_to_caret(ctx);
+    }
+}
+
+impl Widget for TextEditor {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(_, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                ctx.request_focus();
+                ctx.set_active(true);
+                // This needs the text layout, which is dropped when the preedit text is.
+                let offset = self.offset_for_position(ctx, state.position);
+                // Clicking abandons the current composition.
+                if self.preedit.is_some() {
+                    self.set_preedit(ctx, None);
+                    ctx.invalidate_text_input(ImeChangeSignal::Reset);
+                }
+
+                let selection = match state.count {
+                    2 => {
+                        let word = word_range(&self.text, offset);
+                        Selection::new(word.start, word.end)
+                    }
+                    3.. => {
+                        // Select the paragraph, up to its newline.
+                        let start = self.text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+                        let end = self.text[offset..]
+                            .find('\n')
+                            .map_or(self.text.len(), |idx| offset + idx);
+                        Selection::new(start, end)
+                    }
+                    _ => self
+                        .selection
+                        .move_to(offset, state.mods.state().shift_key()),
+                };
+                self.set_selection(ctx, selection);
+            }
+            PointerEvent::PointerMove(state) => {
+                if ctx.is_active() {
+                    let offset = self.offset_for_position(ctx, state.position);
+                    self.set_selection(ctx, self.selection.move_to(offset, true));
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                ctx.set_active(false);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if ctx.is_disabled() {
+            return;
+        }
+        match event {
+            TextEvent::KeyboardKey(key_event, mods) if key_event.state == ElementState::Pressed => {
+                let Some(command) = edit_command(key_event, *mods) else {
+                    return;
+                };
+                // Line movement needs an up-to-date layout.
+                self.rebuild_layout(ctx.font_ctx());
+                match command {
+                    EditCommand::Move(movement, extend) => {
+                        match move_selection(&self.text, self.selection, movement, extend) {
+                            Some(selection) => self.set_selection(ctx, selection),
+                            None => self.move_in_layout(ctx, movement, extend),
+                        }
+                    }
+                    EditCommand::Delete(movement) => {
+                        let Some(offset) =
+                            movement_offset(&self.text, self.selection.active, movement)
+                        else {
+                            return;
+                        };
+                        self.delete_towards(ctx, offset);
+                    }
+                    EditCommand::SelectAll => {
+                        self.set_selection(ctx, Selection::new(0, self.text.len()));
+                    }
+                    EditCommand::Undo => self.undo(ctx),
+                    EditCommand::Redo => self.redo(ctx),
+                    EditCommand::Enter => self.insert(ctx, "\n"),
+                    EditCommand::Insert(new_text) => self.insert(ctx, new_text),
+                }
+                ctx.set_handled();
+            }
+            TextEvent::Ime(ime) => {
+                if self.handle_ime(ctx, ime) {
+                    ctx.set_handled();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::SetValue
+            && !ctx.is_disabled()
+        {
+            if let Some(ActionData::Value(value)) = &event.data {
+                self.preedit = None;
+                let value = normalize_newlines(value);
+                self.replace(ctx, 0..self.text.len(), &value, false);
+                ctx.invalidate_text_input(ImeChangeSignal::Reset);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::FocusChanged(false) = event {
+            // The IME session ends with the focus.
+            if self.preedit.take().is_some() {
+                self.text_layout = None;
+                ctx.request_layout();
+            }
+        }
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_as_text_input();
+            }
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::DisabledChanged(disabled) => {
+                self.disabled = *disabled;
+                self.text_layout = None;
+                ctx.request_layout();
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let insets = theme::TEXTBOX_INSETS;
+
+        // Compute max_advance from box constraints
+        let max_advance = if self.line_break_mode != LineBreaking::WordWrap {
+            None
+        } else if bc.max().width.is_finite() {
+            Some((bc.max().width - insets.x_value() - CARET_WIDTH).max(0.0) as f32)
+        } else {
+            None
+        };
+        if max_advance != self.max_advance {
+            self.max_advance = max_advance;
+            self.text_layout = None;
+        }
+
+        self.rebuild_layout(ctx.font_ctx());
+        let text_layout = self.text_layout.as_ref().unwrap();
+        let text_size = Size::new(
+            text_layout.width() as f64 + CARET_WIDTH,
+            text_layout.height() as f64,
+        );
+
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            (text_size.width + insets.x_value()).max(theme::WIDE_WIDGET_WIDTH)
+        };
+        let height = (text_size.height + insets.y_value()).max(theme::BORDERED_WIDGET_HEIGHT);
+        let size = bc.constrain(Size::new(width, height));
+
+        let caret = caret_rect(text_layout, self.display_caret(), CARET_WIDTH);
+        ctx.set_ime_area(caret + self.text_origin().to_vec2());
+
+        trace!("Computed layout: max={:?}, size={}", self.max_advance, size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let border_width = theme::TEXTBOX_BORDER_WIDTH;
+        let is_focused = ctx.is_focused() && !ctx.is_disabled();
+
+        let background_rect = size
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(theme::TEXTBOX_BORDER_RADIUS);
+        let border_color = if is_focused {
+            theme::PRIMARY_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            theme::BACKGROUND_LIGHT,
+            None,
+            &background_rect,
+        );
+        stroke(scene, &background_rect, border_color, border_width);
+
+        let Some(text_layout) = &self.text_layout else {
+            return;
+        };
+
+        let clip = self.line_break_mode != LineBreaking::Overflow;
+        if clip {
+            let clip_rect = size.to_rect().inset(-border_width);
+            scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        }
+
+        let transform = Affine::translate(self.text_origin().to_vec2());
+        if !self.selection.is_caret() {
+            let selection_color = if is_focused {
+                theme::SELECTED_TEXT_BACKGROUND_COLOR
+            } else {
+                theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR
+            };
+            let range = self.display_offset(self.selection.min())
+                ..self.display_offset(self.selection.max());
+            for rect in selection_rects(text_layout, range) {
+                scene.fill(Fill::NonZero, transform, selection_color, None, &rect);
+            }
+        }
+
+        crate::text_helpers::render_text(scene, transform, text_layout);
+
+        if let Some((preedit, _)) = &self.preedit {
+            let start = self.selection.active;
+            for rect in selection_rects(text_layout, start..start + preedit.len()) {
+                let underline = Line::new((rect.x0, rect.y1), (rect.x1, rect.y1));
+                scene.stroke(
+                    &Stroke::new(1.0),
+                    transform,
+                    theme::TEXT_COLOR,
+                    None,
+                    &underline,
+                );
+            }
+        }
+
+        let hide_caret = matches!(self.preedit, Some((_, None)));
+        if is_focused && !hide_caret {
+            let caret = caret_rect(text_layout, self.display_caret(), CARET_WIDTH);
+            let x = caret.x0 + CARET_WIDTH / 2.0;
+            let caret = Line::new((x, caret.y0), (x, caret.y1));
+            scene.stroke(
+                &Stroke::new(CARET_WIDTH),
+                transform,
+                theme::CURSOR_COLOR,
+                None,
+                &caret,
+            );
+        }
+
+        if clip {
+            scene.pop_layer();
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::MultilineTextInput
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_value(self.text.clone());
+        node.add_action(accesskit::Action::SetValue);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TextEditor")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Portal;
+
+    fn text_of(harness: &TestHarness, id: crate::WidgetId) -> String {
+        harness
+            .get_widget(id)
+            .downcast::<TextEditor>()
+            .unwrap()
+            .text()
+            .to_string()
+    }
+
+    #[test]
+    fn type_multiple_lines() {
+        let [editor_id] = widget_ids();
+        let widget = TextEditor::empty().with_id(editor_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(editor_id);
+        harness.keyboard_type_chars("a\r\nb");
+
+        assert_eq!(text_of(&harness, editor_id), "a\n\nb");
+        assert_eq!(
+            harness
+                .get_widget(editor_id)
+                .downcast::<TextEditor>()
+                .unwrap()
+                .selection(),
+            Selection::caret(4)
+        );
+        let mut last_action = None;
+        while let Some(action) = harness.pop_action() {
+            last_action = Some(action);
+        }
+        assert_eq!(
+            last_action,
+            Some((Action::TextChanged("a\n\nb".to_string()), editor_id))
+        );
+    }
+
+    #[test]
+    fn undo_coalesces_typing() {
+        let widget = TextEditor::empty();
+
+        let mut harness = TestHarness::create(widget);
+        let editor_id = harness.root_widget().id();
+        harness.mouse_click_on(editor_id);
+        harness.keyboard_type_chars("one two\nthree");
+
+        let undo = |harness: &mut TestHarness| {
+            let mut undone = false;
+            harness.edit_root_widget(|mut root| {
+                undone = root.downcast::<TextEditor>().unwrap().undo();
+            });
+            undone
+        };
+
+        assert!(undo(&mut harness));
+        assert_eq!(text_of(&harness, editor_id), "one two\n");
+        assert!(undo(&mut harness));
+        assert_eq!(text_of(&harness, editor_id), "one two");
+        assert!(undo(&mut harness));
+        assert_eq!(text_of(&harness, editor_id), "");
+        assert!(!undo(&mut harness));
+
+        harness.edit_root_widget(|mut root| {
+            let mut editor = root.downcast::<TextEditor>().unwrap();
+            assert!(editor.redo());
+            assert!(editor.redo());
+        });
+        assert_eq!(text_of(&harness, editor_id), "one two\n");
+
+        // A new edit discards the undone ones.
+        harness.keyboard_type_chars("!");
+        let editor = harness.get_widget(editor_id);
+        let editor = editor.downcast::<TextEditor>().unwrap();
+        assert_eq!(editor.text(), "one two\n!");
+        assert!(!editor.can_redo());
+    }
+
+    #[test]
+    fn caret_scrolls_into_view() {
+        let [editor_id] = widget_ids();
+        let widget = Portal::new(TextEditor::empty().with_id(editor_id)).constrain_horizontal(true);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 100.));
+        harness.mouse_click_on(editor_id);
+        harness.keyboard_type_chars(&"line\n".repeat(20));
+
+        let editor_rect = harness.get_widget(editor_id).state().window_layout_rect();
+        assert!(editor_rect.height() > 100.);
+        // The caret is on the last line, so the portal scrolled down until only the
+        // editor's bottom inset is hidden.
+        let hidden = editor_rect.y1 - 100.;
+        assert!(hidden >= 0. && hidden <= theme::TEXTBOX_INSETS.y1 + 1e-6);
+    }
+
+    #[test]
+    fn set_value_from_assistive_technology() {
+        let widget = TextEditor::new("Hello");
+
+        let mut harness = TestHarness::create(widget);
+        let editor_id = harness.root_widget().id();
+        harness.process_access_event(AccessEvent {
+            target: editor_id,
+            action: accesskit::Action::SetValue,
+            data: Some(ActionData::Value("Goodbye\nWorld".into())),
+        });
+
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("Goodbye\nWorld".to_string()), editor_id))
+        );
+        assert_eq!(text_of(&harness, editor_id), "Goodbye\nWorld");
+
+        // Assistive technology edits can be undone too.
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<TextEditor>().unwrap().undo();
+        });
+        assert_eq!(text_of(&harness, editor_id), "Hello");
+    }
+
+    #[test]
+    fn edit_text_editor() {
+        let widget = TextEditor::new("Hello");
+
+        let mut harness = TestHarness::create(widget);
+        let editor_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut editor = root.downcast::<TextEditor>().unwrap();
+            editor.set_text("Good\r\nbye");
+            editor.set_line_break_mode(LineBreaking::Clip);
+            editor.set_selection(Selection::new(0, 100));
+        });
+
+        // Setting the text programmatically doesn't emit an action, and can't be undone.
+        assert_eq!(harness.pop_action(), None);
+
+        let editor = harness.get_widget(editor_id);
+        let editor = editor.downcast::<TextEditor>().unwrap();
+        assert_eq!(editor.text(), "Good\nbye");
+        assert_eq!(editor.selection(), Selection::new(0, 8));
+        assert!(!editor.can_undo());
+    }
+}
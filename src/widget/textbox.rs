@@ -0,0 +1,727 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A single-line text input widget.
+
+use accesskit::{ActionData, Role};
+use kurbo::{Affine, Line, Stroke};
+use parley::layout::Alignment;
+use parley::style::{FontFamily, FontStack, GenericFamily, StyleProperty};
+use parley::{FontContext, Layout};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::{BlendMode, Brush, Fill};
+use vello::Scene;
+use winit::dpi::LogicalPosition;
+use winit::event::ElementState;
+
+use crate::action::Action;
+use crate::paint_scene_helpers::stroke;
+use crate::text_helpers::{
+    caret_x, edit_command, move_selection, movement_offset, offset_for_point, word_range,
+    EditCommand, ImeChangeSignal, ImeTextField, Movement, Preedit, Selection,
+};
+use crate::widget::WidgetRef;
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget,
+};
+
+const CARET_WIDTH: f64 = 1.0;
+
+/// A widget for editing a single line of text.
+///
+/// Emits [`Action::TextChanged`] when the user edits the text, and
+/// [`Action::TextEntered`] when they press Enter.
+pub struct TextBox {
+    text: String,
+    placeholder: ArcStr,
+    selection: Selection,
+    preedit: Option<Preedit>,
+    text_layout: Option<Layout<Brush>>,
+    placeholder_layout: Option<Layout<Brush>>,
+    text_size: f32,
+    font_family: FontFamily<'static>,
+    disabled: bool,
+    /// The height of a line of text, used for the caret and the selection.
+    line_height: f64,
+    /// How far the text is scrolled to the left, to keep the caret visible.
+    scroll_offset: f64,
+}
+
+crate::declare_widget!(TextBoxMut, TextBox);
+
+// --- METHODS ---
+
+impl TextBox {
+    /// Create a new text box, with the caret at the end of `text`.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            selection: Selection::caret(text.len()),
+            text,
+            placeholder: "".into(),
+            preedit: None,
+            text_layout: None,
+            placeholder_layout: None,
+            text_size: theme::TEXT_SIZE_NORMAL as f32,
+            font_family: FontFamily::Generic(GenericFamily::SystemUi),
+            disabled: false,
+            line_height: 0.0,
+            scroll_offset: 0.0,
+        }
+    }
+
+    /// Create a text box with empty text.
+    pub fn empty() -> Self {
+        Self::new("")
+    }
+
+    /// Builder-style method for setting the text shown while the text box is empty.
+    pub fn with_placeholder(mut self, placeholder: impl Into<ArcStr>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Builder-style method for setting the text size.
+    pub fn with_text_size(mut self, size: impl Into<f32>) -> Self {
+        self.text_size = size.into();
+        self
+    }
+
+    /// Builder-style method for setting the font.
+    pub fn with_font_family(mut self, font_family: impl Into<FontFamily<'static>>) -> Self {
+        self.font_family = font_family.into();
+        self
+    }
+
+    /// Return the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Return the text shown while the text box is empty.
+    pub fn placeholder(&self) -> ArcStr {
+        self.placeholder.clone()
+    }
+
+    /// Return the current selection.
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    fn build_layout(&self, font_cx: &mut FontContext, text: &str, color: Color) -> Layout<Brush> {
+        let mut lcx = parley::LayoutContext::new();
+        let mut layout_builder = lcx.ranged_builder(font_cx, text, 1.0);
+
+        layout_builder.push_default(&StyleProperty::FontStack(FontStack::Single(
+            self.font_family,
+        )));
+        layout_builder.push_default(&StyleProperty::FontSize(self.text_size));
+        layout_builder.push_default(&StyleProperty::Brush(Brush::Solid(color)));
+
+        let mut layout = layout_builder.build();
+        layout.break_all_lines(None, Alignment::Start);
+        layout
+    }
+
+    fn rebuild_layouts(&mut self, font_cx: &mut FontContext) {
+        if self.text_layout.is_none() {
+            let color = if self.disabled {
+                theme::DISABLED_TEXT_COLOR
+            } else {
+                theme::TEXT_COLOR
+            };
+            // Empty text has no lines, so we lay out a space to get the caret's height.
+            let text = self.display_text();
+            let text = if text.is_empty() { " " } else { &text };
+            self.text_layout = Some(self.build_layout(font_cx, text, color));
+        }
+        if self.placeholder_layout.is_none() {
+            let placeholder = self.placeholder.clone();
+            self.placeholder_layout =
+                Some(self.build_layout(font_cx, &placeholder, theme::PLACEHOLDER_COLOR));
+        }
+    }
+
+    /// The origin of the text layout, in local coordinates.
+    fn text_origin(&self, size: Size) -> Point {
+        Point::new(
+            theme::TEXTBOX_INSETS.x0 - self.scroll_offset,
+            (size.height - self.line_height) / 2.0,
+        )
+    }
+
+    /// The offset in the text closest to the given window position.
+    fn offset_for_position(&self, ctx: &EventCtx, position: LogicalPosition<f64>) -> usize {
+        let Some(text_layout) = &self.text_layout else {
+            return self.selection.active;
+        };
+        let local_pos = Point::new(position.x, position.y) - ctx.window_origin().to_vec2();
+        let text_pos = local_pos - self.text_origin(ctx.size()).to_vec2();
+        // The layout holds the displayed text, with the preedit text.
+        self.committed_offset(offset_for_point(text_layout, text_pos))
+            .min(self.text.len())
+    }
+
+    fn set_selection(&mut self, ctx: &mut EventCtx, selection: Selection) {
+        if selection != self.selection {
+            self.selection = selection;
+            // Layout scrolls the caret into view and moves the IME area.
+            ctx.request_layout();
+        }
+    }
+
+    /// Replace the selected text with `new_text`, and put the caret after it.
+    fn insert(&mut self, ctx: &mut EventCtx, new_text: &str) {
+        // This is a single-line text box; newlines and other control characters are dropped.
+        let new_text: String = new_text.chars().filter(|c| !c.is_control()).collect();
+        if new_text.is_empty() && self.selection.is_caret() {
+            return;
+        }
+        let range = self.selection.range();
+        self.text.replace_range(range.clone(), &new_text);
+        self.selection = Selection::caret(range.start + new_text.len());
+        self.text_changed(ctx);
+    }
+
+    /// Delete the selected text, or if the selection is empty, the text between the caret and `offset`.
+    fn delete_towards(&mut self, ctx: &mut EventCtx, offset: usize) {
+        let range = if self.selection.is_caret() {
+            let caret = self.selection.active;
+            caret.min(offset)..caret.max(offset)
+        } else {
+            self.selection.range()
+        };
+        if range.is_empty() {
+            return;
+        }
+        self.text.replace_range(range.clone(), "");
+        self.selection = Selection::caret(range.start);
+        self.text_changed(ctx);
+    }
+
+    fn text_changed(&mut self, ctx: &mut EventCtx) {
+        trace!(
+            "TextBox {:?} text changed to {:?}",
+            ctx.widget_id(),
+            self.text
+        );
+        self.text_layout = None;
+        ctx.submit_action(Action::TextChanged(self.text.clone()));
+        ctx.request_layout();
+        ctx.request_accessibility_update();
+    }
+}
+
+impl TextBoxMut<'_> {
+    /// Set the text, and put the caret at its end.
+    ///
+    /// This doesn't emit [`Action::TextChanged`].
+    pub fn set_text(&mut self, new_text: impl Into<String>) {
+        self.widget.text = new_text.into();
+        self.widget.selection = Selection::caret(self.widget.text.len());
+        self.widget.preedit = None;
+        self.widget.text_layout = None;
+        self.ctx.invalidate_text_input(ImeChangeSignal::Reset);
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the text shown while the text box is empty.
+    pub fn set_placeholder(&mut self, placeholder: impl Into<ArcStr>) {
+        self.widget.placeholder = placeholder.into();
+        self.widget.placeholder_layout = None;
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the selection.
+    ///
+    /// The offsets are clamped to the text, and to `char` boundaries.
+    pub fn set_selection(&mut self, selection: Selection) {
+        self.widget.selection = selection.constrained(&self.widget.text);
+        self.ctx
+            .invalidate_text_input(ImeChangeSignal::SelectionChanged);
+        self.ctx.request_layout();
+    }
+
+    /// Set the text size.
+    pub fn set_text_size(&mut self, size: impl Into<f32>) {
+        self.widget.text_size = size.into();
+        self.widget.text_layout = None;
+        self.widget.placeholder_layout = None;
+        self.ctx.request_layout();
+    }
+
+    /// Set the font.
+    pub fn set_font_family(&mut self, font_family: impl Into<FontFamily<'static>>) {
+        self.widget.font_family = font_family.into();
+        self.widget.text_layout = None;
+        self.widget.placeholder_layout = None;
+        self.ctx.request_layout();
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl ImeTextField for TextBox {
+    fn committed_text(&self) -> &str {
+        &self.text
+    }
+
+    fn caret(&self) -> usize {
+        self.selection.active
+    }
+
+    fn preedit(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    fn preedit_mut(&mut self) -> &mut Option<Preedit> {
+        &mut self.preedit
+    }
+
+    fn delete_selection(&mut self, ctx: &mut EventCtx) {
+        self.delete_towards(ctx, self.selection.active);
+    }
+
+    fn insert_committed(&mut self, ctx: &mut EventCtx, text: &str) {
+        self.insert(ctx, text);
+    }
+
+    fn preedit_changed(&mut self, ctx: &mut EventCtx) {
+        self.text_layout = None;
+        ctx.request_layout();
+    }
+}
+
+impl Widget for TextBox {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(_, state) => {
+                if ctx.is_disabled() {
+                    return;
+                }
+                ctx.request_focus();
+                ctx.set_active(true);
+                // This needs the text layout, which is dropped when the preedit text is.
+                let offset = self.offset_for_position(ctx, state.position);
+                // Clicking abandons the current composition.
+                if self.preedit.is_some() {
+                    self.set_preedit(ctx, None);
+                    ctx.invalidate_text_input(ImeChangeSignal::Reset);
+                }
+
+                let selection = match state.count {
+                    2 => {
+                        let word = word_range(&self.text, offset);
+                        Selection::new(word.start, word.end)
+                    }
+                    3.. => Selection::new(0, self.text.len()),
+                    _ => self
+                        .selection
+                        .move_to(offset, state.mods.state().shift_key()),
+                };
+                self.set_selection(ctx, selection);
+            }
+            PointerEvent::PointerMove(state) => {
+                if ctx.is_active() {
+                    let offset = self.offset_for_position(ctx, state.position);
+                    self.set_selection(ctx, self.selection.move_to(offset, true));
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                ctx.set_active(false);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if ctx.is_disabled() {
+            return;
+        }
+        match event {
+            TextEvent::KeyboardKey(key_event, mods) if key_event.state == ElementState::Pressed => {
+                let Some(command) = edit_command(key_event, *mods) else {
+                    return;
+                };
+                match command {
+                    EditCommand::Move(movement, extend) => {
+                        // The only line starts and ends with the text.
+                        let movement = match movement {
+                            Movement::LineStart => Movement::TextStart,
+                            Movement::LineEnd => Movement::TextEnd,
+                            movement => movement,
+                        };
+                        let Some(selection) =
+                            move_selection(&self.text, self.selection, movement, extend)
+                        else {
+                            return;
+                        };
+                        self.set_selection(ctx, selection);
+                    }
+                    EditCommand::Delete(movement) => {
+                        let Some(offset) =
+                            movement_offset(&self.text, self.selection.active, movement)
+                        else {
+                            return;
+                        };
+                        self.delete_towards(ctx, offset);
+                    }
+                    EditCommand::SelectAll => {
+                        self.set_selection(ctx, Selection::new(0, self.text.len()));
+                    }
+                    EditCommand::Enter => {
+                        ctx.submit_action(Action::TextEntered(self.text.clone()));
+                    }
+                    EditCommand::Insert(new_text) => {
+                        self.insert(ctx, new_text);
+                    }
+                    EditCommand::Undo | EditCommand::Redo => return,
+                }
+                ctx.set_handled();
+            }
+            TextEvent::Ime(ime) => {
+                if self.handle_ime(ctx, ime) {
+                    ctx.set_handled();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id()
+            && event.action == accesskit::Action::SetValue
+            && !ctx.is_disabled()
+        {
+            if let Some(ActionData::Value(value)) = &event.data {
+                self.text = value.to_string();
+                self.selection = Selection::caret(self.text.len());
+                self.preedit = None;
+                ctx.invalidate_text_input(ImeChangeSignal::Reset);
+                self.text_changed(ctx);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::FocusChanged(false) = event {
+            // The IME session ends with the focus.
+            if self.preedit.take().is_some() {
+                self.text_layout = None;
+                ctx.request_layout();
+            }
+        }
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_as_text_input();
+            }
+            LifeCycle::BuildFocusChain => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::DisabledChanged(disabled) => {
+                self.disabled = *disabled;
+                self.text_layout = None;
+                ctx.request_layout();
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        self.rebuild_layouts(ctx.font_ctx());
+        let text_layout = self.text_layout.as_ref().unwrap();
+        let text_width = text_layout.width() as f64;
+        self.line_height = text_layout.height() as f64;
+
+        let insets = theme::TEXTBOX_INSETS;
+        let size = bc.constrain(Size::new(
+            theme::WIDE_WIDGET_WIDTH,
+            (self.line_height + insets.y_value()).max(theme::BORDERED_WIDGET_HEIGHT),
+        ));
+
+        // Scroll the caret into view.
+        let inner_width = (size.width - insets.x_value()).max(0.0);
+        let caret_x = caret_x(text_layout, self.display_caret());
+        if caret_x < self.scroll_offset {
+            self.scroll_offset = caret_x;
+        } else if caret_x + CARET_WIDTH > self.scroll_offset + inner_width {
+            self.scroll_offset = caret_x + CARET_WIDTH - inner_width;
+        }
+        let max_scroll = (text_width + CARET_WIDTH - inner_width).max(0.0);
+        self.scroll_offset = self.scroll_offset.clamp(0.0, max_scroll);
+
+        let origin = self.text_origin(size);
+        ctx.set_ime_area(Rect::new(
+            origin.x + caret_x,
+            origin.y,
+            origin.x + caret_x + CARET_WIDTH,
+            origin.y + self.line_height,
+        ));
+
+        trace!(
+            "Computed layout: size={}, scroll={}",
+            size,
+            self.scroll_offset
+        );
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let border_width = theme::TEXTBOX_BORDER_WIDTH;
+        let is_focused = ctx.is_focused() && !ctx.is_disabled();
+
+        let background_rect = size
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(theme::TEXTBOX_BORDER_RADIUS);
+        let border_color = if is_focused {
+            theme::PRIMARY_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            theme::BACKGROUND_LIGHT,
+            None,
+            &background_rect,
+        );
+        stroke(scene, &background_rect, border_color, border_width);
+
+        let (Some(text_layout), Some(placeholder_layout)) =
+            (&self.text_layout, &self.placeholder_layout)
+        else {
+            return;
+        };
+
+        let clip_rect = size.to_rect().inset(-border_width);
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+
+        let transform = Affine::translate(self.text_origin(size).to_vec2());
+        if self.text.is_empty() && self.preedit.is_none() {
+            crate::text_helpers::render_text(scene, transform, placeholder_layout);
+        } else {
+            if !self.selection.is_caret() {
+                let x0 = caret_x(text_layout, self.display_offset(self.selection.min()));
+                let x1 = caret_x(text_layout, self.display_offset(self.selection.max()));
+                let selection_color = if is_focused {
+                    theme::SELECTED_TEXT_BACKGROUND_COLOR
+                } else {
+                    theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR
+                };
+                let selection_rect = Rect::new(x0, 0.0, x1, self.line_height);
+                scene.fill(
+                    Fill::NonZero,
+                    transform,
+                    selection_color,
+                    None,
+                    &selection_rect,
+                );
+            }
+
+            crate::text_helpers::render_text(scene, transform, text_layout);
+
+            if let Some((preedit, _)) = &self.preedit {
+                let start = self.selection.active;
+                let x0 = caret_x(text_layout, start);
+                let x1 = caret_x(text_layout, start + preedit.len());
+                let underline = Line::new((x0, self.line_height), (x1, self.line_height));
+                scene.stroke(
+                    &Stroke::new(1.0),
+                    transform,
+                    theme::TEXT_COLOR,
+                    None,
+                    &underline,
+                );
+            }
+        }
+
+        let hide_caret = matches!(self.preedit, Some((_, None)));
+        if is_focused && !hide_caret {
+            let x = caret_x(text_layout, self.display_caret()) + CARET_WIDTH / 2.0;
+            let caret = Line::new((x, 0.0), (x, self.line_height));
+            scene.stroke(
+                &Stroke::new(CARET_WIDTH),
+                transform,
+                theme::CURSOR_COLOR,
+                None,
+                &caret,
+            );
+        }
+
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TextInput
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_value(self.text.clone());
+        if !self.placeholder.is_empty() {
+            node.set_placeholder(self.placeholder.to_string());
+        }
+        node.add_action(accesskit::Action::SetValue);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TextBox")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.text.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::{Ime, MouseButton};
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+
+    fn text_of(harness: &TestHarness, id: crate::WidgetId) -> String {
+        harness
+            .get_widget(id)
+            .downcast::<TextBox>()
+            .unwrap()
+            .text()
+            .to_string()
+    }
+
+    #[test]
+    fn type_text() {
+        let [textbox_id] = widget_ids();
+        let widget = TextBox::empty().with_id(textbox_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(textbox_id);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(textbox_id));
+
+        harness.keyboard_type_chars("hi");
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("h".to_string()), textbox_id))
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("hi".to_string()), textbox_id))
+        );
+        assert_eq!(text_of(&harness, textbox_id), "hi");
+        assert_eq!(
+            harness
+                .get_widget(textbox_id)
+                .downcast::<TextBox>()
+                .unwrap()
+                .selection(),
+            Selection::caret(2)
+        );
+    }
+
+    #[test]
+    fn ime_composition() {
+        let [textbox_id] = widget_ids();
+        let widget = TextBox::new("a").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(textbox_id);
+
+        // Preedit text is displayed, but isn't part of the text until it's committed.
+        harness.process_text_event(TextEvent::Ime(Ime::Preedit("ni".to_string(), Some((2, 2)))));
+        assert_eq!(harness.pop_action(), None);
+        assert_eq!(text_of(&harness, textbox_id), "a");
+
+        harness.process_text_event(TextEvent::Ime(Ime::Commit("你".to_string())));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("a你".to_string()), textbox_id))
+        );
+        assert_eq!(text_of(&harness, textbox_id), "a你");
+    }
+
+    #[test]
+    fn click_during_composition() {
+        let widget = TextBox::new("Hello");
+
+        let mut harness = TestHarness::create(widget);
+        let textbox_id = harness.root_widget().id();
+        let rect = harness.root_widget().state().window_layout_rect();
+        let click = |harness: &mut TestHarness, x: f64| {
+            harness.mouse_move(Point::new(x, rect.center().y));
+            harness.mouse_button_press(MouseButton::Left);
+            harness.mouse_button_release(MouseButton::Left);
+        };
+        let textbox_of = |harness: &TestHarness| {
+            let textbox = harness.get_widget(textbox_id);
+            let textbox = textbox.downcast::<TextBox>().unwrap();
+            (textbox.selection(), textbox.preedit.clone())
+        };
+
+        click(&mut harness, rect.x1 - 1.0);
+        assert_eq!(textbox_of(&harness), (Selection::caret(5), None));
+
+        // The click moves the caret, and abandons the composition.
+        harness.process_text_event(TextEvent::Ime(Ime::Preedit("ni".to_string(), Some((2, 2)))));
+        click(&mut harness, rect.x0 + 1.0);
+        assert_eq!(textbox_of(&harness), (Selection::caret(0), None));
+        assert_eq!(text_of(&harness, textbox_id), "Hello");
+    }
+
+    #[test]
+    fn set_value_from_assistive_technology() {
+        let [textbox_id] = widget_ids();
+        let widget = TextBox::new("Hello").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.process_access_event(AccessEvent {
+            target: textbox_id,
+            action: accesskit::Action::SetValue,
+            data: Some(ActionData::Value("Goodbye".into())),
+        });
+
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("Goodbye".to_string()), textbox_id))
+        );
+        assert_eq!(text_of(&harness, textbox_id), "Goodbye");
+    }
+
+    #[test]
+    fn edit_textbox() {
+        let widget = TextBox::new("Hello").with_placeholder("Name");
+
+        let mut harness = TestHarness::create(widget);
+        let textbox_id = harness.root_widget().id();
+        harness.edit_root_widget(|mut root| {
+            let mut textbox = root.downcast::<TextBox>().unwrap();
+            textbox.set_text("Goodbye");
+            textbox.set_placeholder("Farewell");
+            textbox.set_selection(Selection::new(0, 100));
+        });
+
+        // Setting the text programmatically doesn't emit an action.
+        assert_eq!(harness.pop_action(), None);
+
+        let textbox = harness.get_widget(textbox_id);
+        let textbox = textbox.downcast::<TextBox>().unwrap();
+        assert_eq!(textbox.text(), "Goodbye");
+        assert_eq!(&*textbox.placeholder(), "Farewell");
+        assert_eq!(textbox.selection(), Selection::new(0, 7));
+    }
+}
@@ -0,0 +1,440 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that shows a tooltip when its child is hovered.
+
+use std::time::Duration;
+
+use accesskit::Role;
+use kurbo::RoundedRect;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Label, LineBreaking, WidgetMut, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, TimerToken,
+    Widget, WidgetId, WidgetPod,
+};
+
+/// How long the pointer must rest on the child before the tooltip appears.
+const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+/// The maximum width of the tooltip text, past which it wraps.
+const TOOLTIP_MAX_WIDTH: f64 = 300.0;
+/// The space between the edges of the tooltip and its text.
+const TOOLTIP_PADDING: f64 = theme::WIDGET_CONTROL_COMPONENT_PADDING;
+/// How far below the pointer the tooltip appears, so the cursor doesn't cover it.
+const TOOLTIP_POINTER_OFFSET: f64 = 20.0;
+const TOOLTIP_BORDER_RADIUS: f64 = 3.0;
+
+/// A widget that shows a tooltip when its child is hovered.
+///
+/// The tooltip appears once the pointer has rested on the child for a short delay.
+/// It's placed below the pointer, or above it near the bottom of the window, and
+/// is kept inside the window. It disappears when the pointer leaves the child, when
+/// a pointer button is pressed, or when the child is stashed.
+///
+/// The tooltip is opened with [`EventCtx::open_tooltip`], so it's painted above all
+/// other widgets, and isn't clipped by the child's ancestors. It doesn't receive
+/// pointer events.
+pub struct Tooltip {
+    child: WidgetPod<Box<dyn Widget>>,
+    text: ArcStr,
+    /// The last pointer position over the child, in window coordinates.
+    pointer_pos: Point,
+    /// The timer which shows the tooltip, restarted every time the pointer moves.
+    delay_timer: Option<TimerToken>,
+    /// The id of the tooltip's popup, while it's shown.
+    popup: Option<WidgetId>,
+}
+
+crate::declare_widget!(TooltipMut, Tooltip);
+
+/// The content of the tooltip's popup: the text on a dark background.
+struct TooltipBubble {
+    label: WidgetPod<Label>,
+}
+
+// --- METHODS ---
+
+impl Tooltip {
+    /// Create a new widget showing `text` when `child` is hovered.
+    pub fn new(child: impl Widget, text: impl Into<ArcStr>) -> Self {
+        Tooltip {
+            child: WidgetPod::new(child).boxed(),
+            text: text.into(),
+            pointer_pos: Point::ORIGIN,
+            delay_timer: None,
+            popup: None,
+        }
+    }
+
+    /// Return the text of the tooltip.
+    pub fn text(&self) -> ArcStr {
+        self.text.clone()
+    }
+
+    /// Return `true` if the tooltip is currently shown.
+    pub fn is_shown(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    /// The rect the popup is placed next to: a line going down from the pointer.
+    fn anchor(&self) -> Rect {
+        Rect::from_origin_size(self.pointer_pos, Size::new(0.0, TOOLTIP_POINTER_OFFSET))
+    }
+
+    /// Cancel the pending timer, and return the popup to close if the tooltip is shown.
+    fn hide(&mut self) -> Option<WidgetId> {
+        self.delay_timer = None;
+        self.popup.take()
+    }
+}
+
+impl TooltipMut<'_> {
+    /// Set the text of the tooltip.
+    pub fn set_text(&mut self, text: impl Into<ArcStr>) {
+        self.widget.text = text.into();
+        // A shown tooltip is replaced by one with the new text.
+        if let Some(popup) = self.widget.popup {
+            self.ctx.close_popup(popup);
+            let bubble = TooltipBubble::new(self.widget.text.clone());
+            let anchor = self.widget.anchor();
+            self.widget.popup = Some(self.ctx.open_tooltip(bubble, anchor));
+        }
+        self.ctx.request_accessibility_update();
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl TooltipBubble {
+    fn new(text: ArcStr) -> Self {
+        TooltipBubble {
+            label: WidgetPod::new(Label::new(text).with_line_break_mode(LineBreaking::WordWrap)),
+        }
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Tooltip {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+
+        match event {
+            PointerEvent::PointerMove(state) => {
+                // The tooltip stays where it appeared until the pointer leaves.
+                if ctx.is_hot() && !self.is_shown() {
+                    self.pointer_pos = Point::new(state.position.x, state.position.y);
+                    self.delay_timer = Some(ctx.request_timer(TOOLTIP_DELAY));
+                }
+            }
+            PointerEvent::PointerDown(_, _) | PointerEvent::PointerLeave(_) => {
+                if let Some(popup) = self.hide() {
+                    ctx.close_popup(popup);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::HotChanged(false) = event {
+            if let Some(popup) = self.hide() {
+                ctx.close_popup(popup);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::Timer(token) if self.delay_timer == Some(*token) => {
+                self.delay_timer = None;
+                if ctx.is_hot() && !ctx.is_disabled() {
+                    trace!("Showing tooltip of {:?}", ctx.widget_id());
+                    let bubble = TooltipBubble::new(self.text.clone());
+                    self.popup = Some(ctx.open_tooltip(bubble, self.anchor()));
+                }
+            }
+            // The tooltip was closed because the child was stashed.
+            LifeCycle::PopupClosed(popup_id, _) if self.popup == Some(*popup_id) => {
+                trace!("Hiding tooltip of {:?}", ctx.widget_id());
+                self.popup = None;
+            }
+            _ => {}
+        }
+
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        // Screen readers get the tooltip as a description, whether or not it's shown.
+        ctx.current_node().set_description(self.text.to_string());
+        self.child.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Tooltip")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.text.to_string())
+    }
+}
+
+impl Widget for TooltipBubble {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, _bc: &BoxConstraints) -> Size {
+        let label_bc = BoxConstraints::new(Size::ZERO, Size::new(TOOLTIP_MAX_WIDTH, f64::INFINITY));
+        let label_size = self.label.layout(ctx, &label_bc);
+        ctx.place_child(
+            &mut self.label,
+            Point::new(TOOLTIP_PADDING, TOOLTIP_PADDING),
+        );
+
+        let size = Size::new(
+            label_size.width + 2.0 * TOOLTIP_PADDING,
+            label_size.height + 2.0 * TOOLTIP_PADDING,
+        );
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let border_width = 1.0;
+        let rect = RoundedRect::from_rect(
+            ctx.size().to_rect().inset(-border_width / 2.0),
+            TOOLTIP_BORDER_RADIUS,
+        );
+        fill_color(scene, &rect, theme::BACKGROUND_DARK);
+        stroke(scene, &rect, theme::BORDER_LIGHT, border_width);
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Tooltip
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.label.accessibility(ctx);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TooltipBubble")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Align, Button, Flex, SizedBox, Tabs};
+
+    fn tooltip_of(harness: &TestHarness) -> WidgetRef<'_, Tooltip> {
+        harness.root_widget().downcast::<Tooltip>().unwrap()
+    }
+
+    /// The rect of the shown tooltip, in window coordinates.
+    fn tooltip_rect(harness: &TestHarness) -> Rect {
+        let popups = harness.popups();
+        assert_eq!(popups.len(), 1);
+        popups[0].state().window_layout_rect()
+    }
+
+    #[test]
+    fn shown_after_delay() {
+        let [button_id] = widget_ids();
+        let widget = Tooltip::new(Button::new("Save").with_id(button_id), "Save the file");
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_move_to(button_id);
+        assert!(!tooltip_of(&harness).is_shown());
+
+        harness.move_timers_forward(TOOLTIP_DELAY / 2);
+        assert!(!tooltip_of(&harness).is_shown());
+        harness.move_timers_forward(TOOLTIP_DELAY);
+        assert!(tooltip_of(&harness).is_shown());
+
+        // The tooltip appears below the pointer.
+        let pointer_pos = harness
+            .get_widget(button_id)
+            .state()
+            .window_layout_rect()
+            .center();
+        let tooltip_rect = tooltip_rect(&harness);
+        assert_eq!(tooltip_rect.x0, pointer_pos.x);
+        assert_eq!(tooltip_rect.y0, pointer_pos.y + TOOLTIP_POINTER_OFFSET);
+
+        // The tooltip doesn't take the pointer away from the child.
+        harness.mouse_click_on(button_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((crate::Action::ButtonPressed, button_id))
+        );
+        assert!(!tooltip_of(&harness).is_shown());
+        assert!(harness.popups().is_empty());
+    }
+
+    #[test]
+    fn hidden_on_pointer_leave() {
+        let [tooltip_id, other_id] = widget_ids();
+        let widget = Flex::row()
+            .with_child(Tooltip::new(Button::new("Save"), "Save the file").with_id(tooltip_id))
+            .with_child(Button::new("Open").with_id(other_id));
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_move_to(tooltip_id);
+        harness.move_timers_forward(TOOLTIP_DELAY);
+        let tooltip = harness
+            .get_widget(tooltip_id)
+            .downcast::<Tooltip>()
+            .unwrap();
+        assert!(tooltip.is_shown());
+
+        harness.mouse_move_to(other_id);
+        let tooltip = harness
+            .get_widget(tooltip_id)
+            .downcast::<Tooltip>()
+            .unwrap();
+        assert!(!tooltip.is_shown());
+
+        // Leaving before the delay cancels the tooltip.
+        harness.mouse_move_to(tooltip_id);
+        harness.mouse_move_to(other_id);
+        harness.move_timers_forward(TOOLTIP_DELAY);
+        let tooltip = harness
+            .get_widget(tooltip_id)
+            .downcast::<Tooltip>()
+            .unwrap();
+        assert!(!tooltip.is_shown());
+    }
+
+    #[test]
+    fn kept_inside_window() {
+        let [tooltip_id] = widget_ids();
+        let tooltip = Tooltip::new(
+            SizedBox::empty().width(40.0).height(40.0),
+            "A tooltip long enough to overflow the right edge of the window",
+        )
+        .with_id(tooltip_id);
+        let widget = Align::new(crate::paint_scene_helpers::UnitPoint::BOTTOM_RIGHT, tooltip);
+
+        let window_size = Size::new(400.0, 400.0);
+        let mut harness = TestHarness::create_with_size(widget, window_size);
+        harness.mouse_move((395.0, 395.0));
+        harness.move_timers_forward(TOOLTIP_DELAY);
+
+        let tooltip = harness
+            .get_widget(tooltip_id)
+            .downcast::<Tooltip>()
+            .unwrap();
+        assert!(tooltip.is_shown());
+        let tooltip_rect = tooltip_rect(&harness);
+        assert!(window_size.to_rect().contains_rect(tooltip_rect));
+        // There's no room below the pointer, so the tooltip is above it.
+        assert_eq!(tooltip_rect.y1, 395.0);
+    }
+
+    #[test]
+    fn hidden_on_press() {
+        let widget = Tooltip::new(SizedBox::empty().width(40.0).height(40.0), "Tip");
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(40.0, 40.0));
+        harness.mouse_move((20.0, 20.0));
+        harness.move_timers_forward(TOOLTIP_DELAY);
+        assert!(tooltip_of(&harness).is_shown());
+
+        harness.mouse_button_press(MouseButton::Left);
+        assert!(!tooltip_of(&harness).is_shown());
+        harness.mouse_button_release(MouseButton::Left);
+
+        harness.edit_root_widget(|mut root| {
+            let mut tooltip = root.downcast::<Tooltip>().unwrap();
+            tooltip.set_text("New tip");
+        });
+        assert_eq!(&*tooltip_of(&harness).text(), "New tip");
+    }
+
+    #[test]
+    fn hidden_when_stashed() {
+        let [tooltip_id] = widget_ids();
+        let tooltip = Tooltip::new(SizedBox::empty().width(40.0).height(40.0), "Tip");
+        let widget = Tabs::new()
+            .with_tab("First", tooltip.with_id(tooltip_id))
+            .with_tab("Second", Button::new("Other"));
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_move_to(tooltip_id);
+        harness.move_timers_forward(TOOLTIP_DELAY);
+        assert_eq!(harness.popups().len(), 1);
+
+        harness.edit_root_widget(|mut root| {
+            let mut tabs = root.downcast::<Tabs>().unwrap();
+            tabs.set_selected(1);
+        });
+        assert!(harness.popups().is_empty());
+        let tooltip = harness
+            .get_widget(tooltip_id)
+            .downcast::<Tooltip>()
+            .unwrap();
+        assert!(!tooltip.is_shown());
+    }
+}
@@ -0,0 +1,777 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A scrolling list which only creates widgets for the rows it shows.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use accesskit::{ActionData, Role};
+use kurbo::Affine;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, warn, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::{Axis, ScrollBar, StoreInWidgetMut, WidgetMut, WidgetRef};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    PointerEvent, StatusChange, TextEvent, Widget, WidgetCtx, WidgetPod,
+};
+
+/// The default distance beyond the viewport, above and below, in which rows are kept alive.
+const DEFAULT_OVERSCAN: f64 = 200.0;
+
+/// A vertically scrolling list of rows, built on demand.
+///
+/// Only the rows inside the viewport, plus a buffer above and below it, exist as
+/// widgets. They are built by a callback when they scroll into view, and dropped when
+/// they scroll out of it. With [`with_recycling`](Self::with_recycling), rows which
+/// scroll out of view are instead kept, and reused for the next rows that need one.
+///
+/// Rows can have different heights. Until a row has been laid out, its height is
+/// assumed to be the estimate given to [`new`](Self::new).
+///
+/// Rows are laid out with the width of the list.
+pub struct VirtualList<W: Widget + StoreInWidgetMut> {
+    row_count: usize,
+    estimated_row_height: f64,
+    heights: RowHeights,
+    build_row: Box<dyn FnMut(usize) -> W>,
+    update_row: Option<Box<dyn FnMut(usize, WidgetMut<'_, W>)>>,
+    /// The index of the first row in `rows`.
+    first_row: usize,
+    /// The rows which currently exist as widgets, which are contiguous.
+    rows: VecDeque<WidgetPod<W>>,
+    /// Rows which scrolled out of view, kept to be recycled.
+    pool: Vec<WidgetPod<W>>,
+    viewport_pos: f64,
+    viewport_height: f64,
+    overscan: f64,
+    scrollbar: WidgetPod<ScrollBar>,
+    scrollbar_visible: bool,
+}
+
+crate::declare_widget!(VirtualListMut, VirtualList<W: (Widget + StoreInWidgetMut)>);
+
+/// The heights of all rows, measured or estimated.
+///
+/// They're stored in a Fenwick tree, so that the offset of any row can be computed in
+/// logarithmic time, even for very long lists.
+struct RowHeights {
+    heights: Vec<f64>,
+    /// One-based: `tree[i]` is the sum of the heights of a range of rows ending at `i - 1`.
+    tree: Vec<f64>,
+}
+
+/// The contexts in which rows can be created or recycled.
+trait RowCtx {
+    fn row_mut<'c, W: Widget + StoreInWidgetMut>(
+        &'c mut self,
+        row: &'c mut WidgetPod<W>,
+    ) -> WidgetMut<'c, W>;
+
+    fn rows_changed(&mut self);
+}
+
+macro_rules! impl_row_ctx {
+    ($($ctx:ty),*) => {
+        $(
+            impl RowCtx for $ctx {
+                fn row_mut<'c, W: Widget + StoreInWidgetMut>(
+                    &'c mut self,
+                    row: &'c mut WidgetPod<W>,
+                ) -> WidgetMut<'c, W> {
+                    self.get_mut(row)
+                }
+
+                fn rows_changed(&mut self) {
+                    self.children_changed();
+                    self.request_layout();
+                }
+            }
+        )*
+    };
+}
+
+impl_row_ctx!(EventCtx<'_>, LifeCycleCtx<'_>, WidgetCtx<'_>);
+
+// --- METHODS ---
+
+impl<W: Widget + StoreInWidgetMut> VirtualList<W> {
+    /// Create a new list of `row_count` rows.
+    ///
+    /// `build_row` is called with the index of a row when that row needs a widget.
+    pub fn new(
+        row_count: usize,
+        estimated_row_height: f64,
+        build_row: impl FnMut(usize) -> W + 'static,
+    ) -> Self {
+        VirtualList {
+            row_count,
+            estimated_row_height,
+            heights: RowHeights::new(vec![estimated_row_height; row_count]),
+            build_row: Box::new(build_row),
+            update_row: None,
+            first_row: 0,
+            rows: VecDeque::new(),
+            pool: Vec::new(),
+            viewport_pos: 0.0,
+            viewport_height: 0.0,
+            overscan: DEFAULT_OVERSCAN,
+            scrollbar: WidgetPod::new(ScrollBar::new(Axis::Vertical, 1.0, 1.0)),
+            scrollbar_visible: false,
+        }
+    }
+
+    /// Builder-style method to set how far beyond the viewport rows are kept alive.
+    ///
+    /// A larger buffer means fewer rows are created while scrolling, but more rows
+    /// are laid out on each frame. The default is 200 pixels.
+    pub fn with_overscan(mut self, overscan: f64) -> Self {
+        self.overscan = overscan.max(0.0);
+        self
+    }
+
+    /// Builder-style method to reuse the widgets of rows which scroll out of view.
+    ///
+    /// When a row needs a widget and one is available, `update_row` is called with the
+    /// index of the row and the widget, instead of building a new one.
+    pub fn with_recycling(
+        mut self,
+        update_row: impl FnMut(usize, WidgetMut<'_, W>) + 'static,
+    ) -> Self {
+        self.update_row = Some(Box::new(update_row));
+        self
+    }
+
+    /// Return the number of rows.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Return the vertical scroll offset.
+    pub fn viewport_pos(&self) -> f64 {
+        self.viewport_pos
+    }
+
+    /// Return the indices of the rows which currently exist as widgets.
+    pub fn live_rows(&self) -> Range<usize> {
+        self.first_row..self.first_row + self.rows.len()
+    }
+
+    /// Return the widget of row `idx`, if it exists.
+    pub fn row(&self, idx: usize) -> Option<WidgetRef<'_, W>> {
+        let slot = idx.checked_sub(self.first_row)?;
+        self.rows.get(slot).map(|row| row.as_ref())
+    }
+
+    /// The rows which intersect the viewport and the buffer around it.
+    fn needed_rows(&self) -> Range<usize> {
+        if self.row_count == 0 {
+            return 0..0;
+        }
+        let top = (self.viewport_pos - self.overscan).max(0.0);
+        let bottom = self.viewport_pos + self.viewport_height + self.overscan;
+        let start = self.heights.row_at(top);
+        let end = self.heights.row_at(bottom) + 1;
+        start..end.min(self.row_count)
+    }
+
+    fn max_viewport_pos(&self) -> f64 {
+        (self.heights.total() - self.viewport_height).max(0.0)
+    }
+
+    fn set_viewport_pos_raw(&mut self, pos: f64) -> bool {
+        let pos = pos.clamp(0.0, self.max_viewport_pos());
+        if (pos - self.viewport_pos).abs() > 1e-6 {
+            self.viewport_pos = pos;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Create and drop row widgets so that exactly the needed rows exist.
+    fn sync_rows(&mut self, ctx: &mut impl RowCtx) {
+        let needed = self.needed_rows();
+        let live = self.live_rows();
+        let mut changed = false;
+
+        if needed.start >= live.end || needed.end <= live.start {
+            while let Some(row) = self.rows.pop_back() {
+                self.retire_row(row);
+                changed = true;
+            }
+            self.first_row = needed.start;
+        } else {
+            while self.first_row < needed.start {
+                let row = self.rows.pop_front().unwrap();
+                self.retire_row(row);
+                self.first_row += 1;
+                changed = true;
+            }
+            while self.first_row + self.rows.len() > needed.end {
+                let row = self.rows.pop_back().unwrap();
+                self.retire_row(row);
+                changed = true;
+            }
+        }
+
+        while self.first_row > needed.start {
+            self.first_row -= 1;
+            let row = self.make_row(ctx, self.first_row);
+            self.rows.push_front(row);
+            changed = true;
+        }
+        while self.first_row + self.rows.len() < needed.end {
+            let row = self.make_row(ctx, self.first_row + self.rows.len());
+            self.rows.push_back(row);
+            changed = true;
+        }
+
+        if changed {
+            trace!("VirtualList rows {:?} are live", self.live_rows());
+            ctx.rows_changed();
+        }
+    }
+
+    fn retire_row(&mut self, row: WidgetPod<W>) {
+        if self.update_row.is_some() {
+            self.pool.push(row);
+        }
+    }
+
+    fn make_row(&mut self, ctx: &mut impl RowCtx, idx: usize) -> WidgetPod<W> {
+        if let Some(update_row) = &mut self.update_row {
+            if let Some(mut row) = self.pool.pop() {
+                update_row(idx, ctx.row_mut(&mut row));
+                return row;
+            }
+        }
+        WidgetPod::new((self.build_row)(idx))
+    }
+
+    /// Scroll to `pos`, and create the rows which come into view.
+    fn scroll_to(&mut self, ctx: &mut impl RowCtx, pos: f64) -> bool {
+        let moved = self.set_viewport_pos_raw(pos);
+        if moved {
+            self.sync_rows(ctx);
+            ctx.rows_changed();
+        }
+        moved
+    }
+}
+
+impl<W: Widget + StoreInWidgetMut> VirtualListMut<'_, W> {
+    /// Set the number of rows.
+    ///
+    /// Rows which already exist keep their widgets.
+    pub fn set_row_count(&mut self, row_count: usize) {
+        let list = &mut *self.widget;
+        list.row_count = row_count;
+        list.heights.resize(row_count, list.estimated_row_height);
+        while list.first_row + list.rows.len() > row_count {
+            let row = list.rows.pop_back().unwrap();
+            list.retire_row(row);
+        }
+        list.set_viewport_pos_raw(list.viewport_pos);
+        list.sync_rows(&mut self.ctx);
+        self.ctx.rows_changed();
+    }
+
+    /// Build new widgets for all the rows, eg because the data they show has changed.
+    ///
+    /// If recycling is enabled, the existing widgets are updated instead.
+    pub fn refresh_rows(&mut self) {
+        let list = &mut *self.widget;
+        while let Some(row) = list.rows.pop_back() {
+            list.retire_row(row);
+        }
+        list.first_row = list.needed_rows().start;
+        list.sync_rows(&mut self.ctx);
+        self.ctx.rows_changed();
+    }
+
+    /// Scroll so that row `idx` is at the top of the viewport, or as close as possible.
+    pub fn scroll_to_row(&mut self, idx: usize) {
+        let pos = self.widget.heights.offset(idx.min(self.widget.row_count));
+        self.widget.scroll_to(&mut self.ctx, pos);
+    }
+
+    /// Set the vertical scroll offset.
+    ///
+    /// Return `true` if the viewport moved.
+    pub fn set_viewport_pos(&mut self, pos: f64) -> bool {
+        self.widget.scroll_to(&mut self.ctx, pos)
+    }
+
+    /// Return a [`WidgetMut`] to row `idx`, if it exists.
+    pub fn row_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, W>> {
+        let slot = idx.checked_sub(self.widget.first_row)?;
+        let row = self.widget.rows.get_mut(slot)?;
+        Some(self.ctx.get_mut(row))
+    }
+}
+
+impl RowHeights {
+    fn new(heights: Vec<f64>) -> Self {
+        let len = heights.len();
+        let mut tree = vec![0.0; len + 1];
+        for i in 1..=len {
+            tree[i] += heights[i - 1];
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= len {
+                tree[parent] += tree[i];
+            }
+        }
+        RowHeights { heights, tree }
+    }
+
+    fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    fn resize(&mut self, len: usize, height: f64) {
+        let mut heights = std::mem::take(&mut self.heights);
+        heights.resize(len, height);
+        *self = RowHeights::new(heights);
+    }
+
+    fn set(&mut self, row: usize, height: f64) {
+        let delta = height - self.heights[row];
+        if delta == 0.0 {
+            return;
+        }
+        self.heights[row] = height;
+        let mut i = row + 1;
+        while i <= self.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The sum of the heights of the rows before `row`.
+    fn offset(&self, row: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut i = row;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    fn total(&self) -> f64 {
+        self.offset(self.len())
+    }
+
+    /// The row which contains `y`, or the last row if `y` is past the end.
+    fn row_at(&self, y: f64) -> usize {
+        let len = self.len();
+        let mut row = 0;
+        let mut remaining = y;
+        let mut step = len.next_power_of_two();
+        while step > 0 {
+            if row + step <= len && self.tree[row + step] <= remaining {
+                row += step;
+                remaining -= self.tree[row];
+            }
+            step /= 2;
+        }
+        row.min(len.saturating_sub(1))
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl<W: Widget + StoreInWidgetMut> Widget for VirtualList<W> {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // Rows are only created after the event is dispatched to the existing ones,
+        // since new rows don't receive events until they're added to the tree.
+        for row in &mut self.rows {
+            row.on_pointer_event(ctx, event);
+        }
+        self.scrollbar.on_pointer_event(ctx, event);
+
+        if let PointerEvent::MouseWheel(delta, _) = event {
+            self.scroll_to(ctx, self.viewport_pos + delta.y);
+        }
+        if self.scrollbar.widget().moved {
+            self.scrollbar.widget_mut().moved = false;
+            let progress = self.scrollbar.widget().cursor_progress;
+            self.scroll_to(ctx, progress * self.max_viewport_pos());
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for row in &mut self.rows {
+            row.on_text_event(ctx, event);
+        }
+        self.scrollbar.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for row in &mut self.rows {
+            row.on_access_event(ctx, event);
+        }
+        self.scrollbar.on_access_event(ctx, event);
+
+        if event.target == ctx.widget_id() {
+            // Scroll by one page at a time.
+            let new_pos = match (event.action, &event.data) {
+                (accesskit::Action::ScrollUp, _) => Some(self.viewport_pos - self.viewport_height),
+                (accesskit::Action::ScrollDown, _) => {
+                    Some(self.viewport_pos + self.viewport_height)
+                }
+                (accesskit::Action::SetScrollOffset, Some(ActionData::SetScrollOffset(offset))) => {
+                    Some(offset.y)
+                }
+                _ => None,
+            };
+            if let Some(new_pos) = new_pos {
+                self.scroll_to(ctx, new_pos);
+                ctx.request_accessibility_update();
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_as_portal();
+                // We don't know our height yet, so this only creates the rows in the buffer.
+                self.sync_rows(ctx);
+            }
+            LifeCycle::PostLayout => {
+                self.sync_rows(ctx);
+                // The new rows haven't received WidgetAdded yet.
+                return;
+            }
+            LifeCycle::RequestPanToChild(target_rect) => {
+                // The rect is in our coordinate space, which is offset by the scroll position.
+                let viewport = self.viewport_pos..self.viewport_pos + self.viewport_height;
+                let target = target_rect.min_y() + self.viewport_pos
+                    ..target_rect.max_y() + self.viewport_pos;
+                let new_pos = if target.start < viewport.start {
+                    target.start
+                } else if target.end > viewport.end {
+                    (target.end - self.viewport_height).min(target.start)
+                } else {
+                    viewport.start
+                };
+                self.scroll_to(ctx, new_pos);
+                return;
+            }
+            _ => {}
+        }
+
+        for row in &mut self.rows {
+            row.lifecycle(ctx, event);
+        }
+        self.scrollbar.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let row_bc = if bc.is_width_bounded() {
+            let width = bc.max().width;
+            BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY))
+        } else {
+            BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, f64::INFINITY))
+        };
+
+        // Rows which get measured may not be as tall as estimated. We keep the row
+        // at the top of the viewport in place, so the content doesn't jump around.
+        let anchor_row = self.heights.row_at(self.viewport_pos);
+        let anchor_delta = self.viewport_pos - self.heights.offset(anchor_row);
+
+        let mut max_width: f64 = 0.0;
+        for (slot, row) in self.rows.iter_mut().enumerate() {
+            let row_size = row.layout(ctx, &row_bc);
+            if !row_size.height.is_finite() {
+                warn!("A VirtualList row has an infinite height.");
+                continue;
+            }
+            self.heights.set(self.first_row + slot, row_size.height);
+            max_width = max_width.max(row_size.width);
+        }
+
+        let content_height = self.heights.total();
+        // Without a bound, the list would be as tall as all its rows, and create them all.
+        let max_height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            debug_panic!("A VirtualList must have a bounded height.");
+            ctx.window_size().height
+        };
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            max_width
+        };
+        let size = bc.constrain(Size::new(width, content_height.min(max_height)));
+        self.viewport_height = size.height;
+
+        if self.row_count > 0 {
+            self.viewport_pos = self.heights.offset(anchor_row) + anchor_delta;
+        }
+        self.viewport_pos = self.viewport_pos.clamp(0.0, self.max_viewport_pos());
+
+        let mut y = self.heights.offset(self.first_row) - self.viewport_pos;
+        for row in &mut self.rows {
+            ctx.place_child(row, Point::new(0.0, y));
+            y += row.layout_rect().height();
+        }
+
+        self.scrollbar_visible = content_height > size.height;
+        if self.scrollbar_visible {
+            let scrollbar = self.scrollbar.widget_mut();
+            scrollbar.portal_size = size.height;
+            scrollbar.content_size = content_height;
+            scrollbar.cursor_progress = self.viewport_pos / self.max_viewport_pos();
+            let scrollbar_size = self.scrollbar.layout(ctx, bc);
+            ctx.place_child(
+                &mut self.scrollbar,
+                Point::new(size.width - scrollbar_size.width, 0.0),
+            );
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+
+        // Widgets can't be added during layout, so if the viewport now shows rows
+        // which don't exist, we create them right after, before the list is painted.
+        let needed = self.needed_rows();
+        let live = self.live_rows();
+        if needed.start < live.start || needed.end > live.end {
+            ctx.request_post_layout();
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let clip_rect = ctx.size().to_rect();
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        for row in &mut self.rows {
+            row.paint(ctx, scene);
+        }
+        scene.pop_layer();
+
+        if self.scrollbar_visible {
+            self.scrollbar.paint(ctx, scene);
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::List
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let scale_factor = ctx.scale_factor();
+
+        let node = ctx.current_node();
+        node.set_clips_children();
+        node.set_scroll_y(self.viewport_pos * scale_factor);
+        node.set_scroll_y_min(0.0);
+        node.set_scroll_y_max(self.max_viewport_pos() * scale_factor);
+        node.add_action(accesskit::Action::ScrollUp);
+        node.add_action(accesskit::Action::ScrollDown);
+        node.add_action(accesskit::Action::SetScrollOffset);
+
+        for row in &mut self.rows {
+            row.accessibility(ctx);
+        }
+
+        if self.scrollbar_visible {
+            ctx.add_extra_child(&mut self.scrollbar);
+        } else {
+            ctx.skip_child(&mut self.scrollbar);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.rows.iter().map(|row| row.as_dyn()).collect()
+    }
+
+    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
+        // The scrollbar is painted over the rows, so it takes the pointer first.
+        if self.scrollbar_visible && self.scrollbar.layout_rect().contains(pos) {
+            return Some(self.scrollbar.as_dyn());
+        }
+        self.rows
+            .iter()
+            .find(|row| row.layout_rect().contains(pos))
+            .map(|row| row.as_dyn())
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("VirtualList")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!(
+            "{} rows, {:?} live",
+            self.row_count,
+            self.live_rows()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::kurbo::Vec2;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Label, SizedBox};
+
+    fn row(idx: usize, height: f64) -> SizedBox {
+        SizedBox::new(Label::new(format!("Row {idx}"))).height(height)
+    }
+
+    fn list_of(harness: &TestHarness) -> WidgetRef<'_, VirtualList<SizedBox>> {
+        harness
+            .root_widget()
+            .downcast::<VirtualList<SizedBox>>()
+            .unwrap()
+    }
+
+    /// Check that the live rows are stacked without gaps or overlaps.
+    fn assert_rows_contiguous(harness: &TestHarness) {
+        let list = list_of(harness);
+        let mut rects = list.live_rows().map(|idx| {
+            let row = list.row(idx).unwrap();
+            row.state().layout_rect()
+        });
+        let mut previous = rects.next().unwrap();
+        for rect in rects {
+            assert_eq!(rect.min_y(), previous.max_y());
+            previous = rect;
+        }
+    }
+
+    #[test]
+    fn huge_list() {
+        let builds = Rc::new(Cell::new(0));
+        let widget = VirtualList::new(1_000_000, 20.0, {
+            let builds = builds.clone();
+            move |idx| {
+                builds.set(builds.get() + 1);
+                row(idx, 20.0)
+            }
+        });
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+        // The rows missing after the first layout are created before painting.
+
+        // 400px of viewport and 200px of buffer below it, up to and including the row
+        // which starts right at the edge of the buffer.
+        assert_eq!(list_of(&harness).live_rows(), 0..31);
+        assert_eq!(builds.get(), 31);
+        assert_rows_contiguous(&harness);
+    }
+
+    #[test]
+    fn scroll_recycles_rows() {
+        let builds = Rc::new(Cell::new(0));
+        let [list_id] = widget_ids();
+        let widget = VirtualList::new(1000, 20.0, {
+            let builds = builds.clone();
+            move |idx| {
+                builds.set(builds.get() + 1);
+                row(idx, 20.0)
+            }
+        })
+        .with_recycling(|idx, mut row: WidgetMut<'_, SizedBox>| {
+            let mut label = row.child_mut().unwrap();
+            let mut label = label.downcast::<Label>().unwrap();
+            label.set_text(format!("Row {idx}"));
+        })
+        .with_id(list_id);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+        let initial_builds = builds.get();
+
+        harness.mouse_move_to(list_id);
+        harness.mouse_wheel(Vec2::new(0.0, 1000.0));
+
+        let list = harness.get_widget(list_id);
+        let list = list.downcast::<VirtualList<SizedBox>>().unwrap();
+        assert_eq!(list.viewport_pos(), 1000.0);
+        // Rows 40 to 80 are in the viewport or its buffer.
+        assert_eq!(list.live_rows(), 40..81);
+        // Every row which scrolled out of view was reused.
+        assert_eq!(builds.get(), initial_builds + 10);
+        let label = list.row(50).unwrap().children()[0];
+        assert_eq!(label.downcast::<Label>().unwrap().text().as_ref(), "Row 50");
+    }
+
+    #[test]
+    fn variable_heights() {
+        let widget = VirtualList::new(100, 30.0, |idx| {
+            row(idx, if idx % 2 == 0 { 40.0 } else { 20.0 })
+        });
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+        assert_rows_contiguous(&harness);
+
+        harness.edit_root_widget(|mut root| {
+            let mut list = root.downcast::<VirtualList<SizedBox>>().unwrap();
+            list.scroll_to_row(50);
+        });
+
+        let list = list_of(&harness);
+        assert!(list.live_rows().contains(&50));
+        // The row we scrolled to stays at the top once its neighbours are measured.
+        let row_50 = list.row(50).unwrap();
+        assert_eq!(row_50.state().layout_rect().min_y(), 0.0);
+        assert_rows_contiguous(&harness);
+    }
+
+    #[test]
+    fn scroll_past_overscan() {
+        // Rows are shorter than estimated, so the rows created for the new scroll
+        // position don't fill the viewport once they're measured.
+        let widget = VirtualList::new(1000, 30.0, |idx| row(idx, 10.0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+        harness.mouse_move((100.0, 200.0));
+        harness.mouse_wheel(Vec2::new(0.0, 5000.0));
+
+        // The missing rows are created before the list is painted.
+        let list = list_of(&harness);
+        let live = list.live_rows();
+        let first_row = list.row(live.start).unwrap().state().layout_rect();
+        let last_row = list.row(live.end - 1).unwrap().state().layout_rect();
+        assert!(first_row.min_y() <= 0.0);
+        assert!(last_row.max_y() >= 400.0);
+        assert_rows_contiguous(&harness);
+    }
+
+    #[test]
+    fn edit_row_count() {
+        let widget = VirtualList::new(100, 20.0, |idx| row(idx, 20.0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+
+        harness.edit_root_widget(|mut root| {
+            let mut list = root.downcast::<VirtualList<SizedBox>>().unwrap();
+            list.set_row_count(5);
+        });
+        let list = list_of(&harness);
+        assert_eq!(list.row_count(), 5);
+        assert_eq!(list.live_rows(), 0..5);
+        assert_eq!(list.viewport_pos(), 0.0);
+    }
+}
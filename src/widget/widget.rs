@@ -7,15 +7,17 @@ use std::num::NonZeroU64;
 use std::ops::{Deref, DerefMut};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use accesskit::{NodeId, Role};
 use smallvec::SmallVec;
 use tracing::{trace_span, Span};
 use vello::Scene;
 
 use crate::event::StatusChange;
-use crate::event::{PointerEvent, TextEvent};
+use crate::event::{AccessEvent, PointerEvent, TextEvent};
 use crate::widget::WidgetRef;
 use crate::{
-    AsAny, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point, Size,
+    AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, Size,
 };
 
 /// A unique identifier for a single [`Widget`].
@@ -73,6 +75,12 @@ pub trait Widget: AsAny {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent);
     fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent);
 
+    /// Handle a request from an assistive technology, eg a screen reader.
+    ///
+    /// The event is sent to the target widget and all its ancestors. Container
+    /// widgets must forward it to their children.
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent);
+
     #[allow(missing_docs)]
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange);
 
@@ -110,6 +118,19 @@ pub trait Widget: AsAny {
     /// the render context, which is especially useful for scrolling.
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene);
 
+    /// The role of this widget for assistive technologies.
+    fn accessibility_role(&self) -> Role;
+
+    /// Describe the widget to assistive technologies.
+    ///
+    /// The widget can add properties to the node returned by
+    /// [`AccessCtx::current_node`]. Container widgets must then call
+    /// [`WidgetPod::accessibility`](crate::WidgetPod::accessibility) on their children.
+    ///
+    /// This is only called after layout, and only on widgets which requested
+    /// it or which have a descendant that requested it.
+    fn accessibility(&mut self, ctx: &mut AccessCtx);
+
     /// Return references to this widget's children.
     ///
     /// Leaf widgets return an empty array. Container widgets return references to
@@ -148,13 +169,27 @@ pub trait Widget: AsAny {
     /// The child return is a direct child, not eg a grand-child. The position is in
     /// relative cordinates. (Eg `(0,0)` is the top-left corner of `self`).
     ///
+    /// If several children overlap, the topmost one is returned. Children are assumed
+    /// to be painted in the order [`children`](Self::children) returns them, so the last
+    /// matching child is the topmost.
+    ///
+    /// Pointer events use this method to decide which child is hot; the other children
+    /// under the pointer are considered occluded. This means every child that can
+    /// receive pointer events must be found by this method, with an up-to-date layout
+    /// rect. Widgets whose pointer-interactive children aren't all returned by
+    /// [`children`](Self::children) (eg scrollbars) must override it.
+    ///
+    /// Stashed children are never returned: their layout rect is left over from when
+    /// they were last laid out.
+    ///
     /// Has a default implementation, that can be overriden to search children more
     /// efficiently.
     fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
         // layout_rect() is in parent coordinate space
         self.children()
             .into_iter()
-            .find(|child| child.state().layout_rect().contains(pos))
+            .rev()
+            .find(|child| !child.state().is_stashed && child.state().layout_rect().contains(pos))
     }
 
     /// Get the (verbose) type name of the widget for debugging purposes.
@@ -342,6 +377,19 @@ impl WidgetId {
     pub(crate) fn to_raw(self) -> u64 {
         self.0.into()
     }
+
+    /// Return the `WidgetId` matching an accessibility node id.
+    ///
+    /// Returns `None` if the node wasn't created by Masonry.
+    pub(crate) fn from_node_id(node_id: NodeId) -> Option<WidgetId> {
+        NonZeroU64::new(node_id.0).map(WidgetId)
+    }
+}
+
+impl From<WidgetId> for NodeId {
+    fn from(id: WidgetId) -> NodeId {
+        NodeId(id.to_raw())
+    }
 }
 
 // TODO - remove
@@ -354,6 +402,10 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().on_text_event(ctx, event)
     }
 
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.deref_mut().on_access_event(ctx, event)
+    }
+
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
         self.deref_mut().on_status_change(ctx, event)
     }
@@ -370,6 +422,14 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().paint(ctx, scene);
     }
 
+    fn accessibility_role(&self) -> Role {
+        self.deref().accessibility_role()
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.deref_mut().accessibility(ctx);
+    }
+
     fn type_name(&self) -> &'static str {
         self.deref().type_name()
     }
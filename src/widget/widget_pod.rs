@@ -2,19 +2,20 @@
 // "as-is" basis without warranties of any kind. See the LICENSE file for
 // details.
 
+use accesskit::{NodeBuilder, NodeId};
 use tracing::{info_span, trace, warn};
 use vello::Scene;
-use winit::dpi::PhysicalPosition;
+use winit::dpi::LogicalPosition;
 
-use crate::event::{PointerEvent, TextEvent};
+use crate::event::{AccessEvent, PointerEvent, TextEvent};
 use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size};
 use crate::paint_scene_helpers::stroke;
 use crate::render_root::RenderRootState;
 use crate::theme::get_debug_color;
 use crate::widget::{FocusChange, WidgetRef, WidgetState};
 use crate::{
-    BoxConstraints, EventCtx, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    StatusChange, Widget, WidgetId,
+    AccessCtx, BoxConstraints, EventCtx, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, StatusChange, Widget, WidgetId,
 };
 
 // TODO - rewrite links in doc
@@ -50,6 +51,7 @@ impl<W: Widget> WidgetPod<W> {
         let mut state = WidgetState::new(id, None, inner.short_type_name());
         state.children_changed = true;
         state.needs_layout = true;
+        state.needs_accessibility = true;
         WidgetPod {
             state,
             inner,
@@ -232,7 +234,7 @@ impl<W: Widget> WidgetPod<W> {
         inner: &mut W,
         inner_state: &mut WidgetState,
         global_state: &mut RenderRootState,
-        mouse_pos: Option<PhysicalPosition<f64>>,
+        mouse_pos: Option<LogicalPosition<f64>>,
     ) -> bool {
         let rect = inner_state.layout_rect() + inner_state.parent_window_origin.to_vec2();
         let had_hot = inner_state.is_hot;
@@ -382,6 +384,11 @@ impl<W: Widget> WidgetPod<W> {
             PointerEvent::DropFile(_, _) => None,
             PointerEvent::HoverFileCancel(_) => None,
         };
+        // A sibling painted over this widget takes the pointer, even if our rect contains it.
+        let is_occluded = parent_ctx
+            .hot_child
+            .map_or(false, |hot_child| hot_child != self.state.id);
+        let hot_pos = if is_occluded { None } else { hot_pos };
         let hot_changed = WidgetPod::update_hot_state(
             &mut self.inner,
             &mut self.state,
@@ -394,11 +401,23 @@ impl<W: Widget> WidgetPod<W> {
         if call_inner {
             self.call_widget_method_with_checks("on_pointer_event", |widget_pod| {
                 // widget_pod is a reborrow of `self`
+                let hot_child = match hot_pos {
+                    Some(pos) if widget_pod.state.is_hot => {
+                        let local_pos =
+                            Point::new(pos.x, pos.y) - widget_pod.state.window_origin().to_vec2();
+                        widget_pod
+                            .inner
+                            .get_child_at_pos(local_pos)
+                            .map(|child| child.id())
+                    }
+                    _ => None,
+                };
                 let mut inner_ctx = EventCtx {
                     global_state: parent_ctx.global_state,
                     widget_state: &mut widget_pod.state,
                     is_handled: false,
                     request_pan_to_child: None,
+                    hot_child,
                 };
                 inner_ctx.widget_state.has_active = false;
 
@@ -407,11 +426,11 @@ impl<W: Widget> WidgetPod<W> {
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
-                // TODO - there's some dubious logic here
+                // The target rect is in this widget's coordinate space; the parent
+                // gets it in its own.
                 if let Some(target_rect) = inner_ctx.request_pan_to_child {
                     widget_pod.pan_to_child(parent_ctx, target_rect);
-                    let new_rect = target_rect
-                        .with_origin(target_rect.origin() + widget_pod.state.origin.to_vec2());
+                    let new_rect = target_rect + widget_pod.state.origin.to_vec2();
                     parent_ctx.request_pan_to_child = Some(new_rect);
                 }
             });
@@ -459,6 +478,7 @@ impl<W: Widget> WidgetPod<W> {
                     widget_state: &mut widget_pod.state,
                     is_handled: false,
                     request_pan_to_child: None,
+                    hot_child: None,
                 };
 
                 widget_pod.inner.on_text_event(&mut inner_ctx, event);
@@ -466,11 +486,82 @@ impl<W: Widget> WidgetPod<W> {
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
-                // TODO - there's some dubious logic here
+                // The target rect is in this widget's coordinate space; the parent
+                // gets it in its own.
+                if let Some(target_rect) = inner_ctx.request_pan_to_child {
+                    widget_pod.pan_to_child(parent_ctx, target_rect);
+                    let new_rect = target_rect + widget_pod.state.origin.to_vec2();
+                    parent_ctx.request_pan_to_child = Some(new_rect);
+                }
+            });
+        }
+
+        // Always merge even if not needed, because merging is idempotent and gives us simpler code.
+        // Doing this conditionally only makes sense when there's a measurable performance boost.
+        parent_ctx.widget_state.merge_up(&mut self.state);
+
+        parent_ctx
+            .global_state
+            .debug_logger
+            .update_widget_state(self.as_dyn());
+        parent_ctx
+            .global_state
+            .debug_logger
+            .push_log(false, "updated state");
+
+        parent_ctx.global_state.debug_logger.pop_span();
+    }
+
+    pub fn on_access_event(&mut self, parent_ctx: &mut EventCtx, event: &AccessEvent) {
+        let _span = self.inner.make_trace_span().entered();
+        // TODO #11
+        parent_ctx
+            .global_state
+            .debug_logger
+            .push_span(self.inner.short_type_name());
+
+        // TODO - explain this
+        self.mark_as_visited();
+        self.check_initialized("on_access_event");
+
+        if parent_ctx.is_handled {
+            parent_ctx.global_state.debug_logger.pop_span();
+            // If the event was already handled, we quit early.
+            return;
+        }
+
+        // The bloom filter we're checking can return false positives.
+        let is_target = event.target == self.state.id;
+        if (is_target || self.state.children.may_contain(&event.target)) && !self.state.is_stashed {
+            self.call_widget_method_with_checks("on_access_event", |widget_pod| {
+                // widget_pod is a reborrow of `self`
+                let mut inner_ctx = EventCtx {
+                    global_state: parent_ctx.global_state,
+                    widget_state: &mut widget_pod.state,
+                    is_handled: false,
+                    request_pan_to_child: None,
+                    hot_child: None,
+                };
+
+                // Focus is handled the same way for every widget, so that individual
+                // widgets don't have to.
+                if is_target
+                    && event.action == accesskit::Action::Focus
+                    && inner_ctx.widget_state.focus_chain.contains(&event.target)
+                {
+                    inner_ctx.request_focus();
+                }
+
+                widget_pod.inner.on_access_event(&mut inner_ctx, event);
+
+                inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
+                parent_ctx.is_handled |= inner_ctx.is_handled;
+
+                // The target rect is in this widget's coordinate space; the parent
+                // gets it in its own.
                 if let Some(target_rect) = inner_ctx.request_pan_to_child {
                     widget_pod.pan_to_child(parent_ctx, target_rect);
-                    let new_rect = target_rect
-                        .with_origin(target_rect.origin() + widget_pod.state.origin.to_vec2());
+                    let new_rect = target_rect + widget_pod.state.origin.to_vec2();
                     parent_ctx.request_pan_to_child = Some(new_rect);
                 }
             });
@@ -563,6 +654,7 @@ impl<W: Widget> WidgetPod<W> {
                     self.state.is_explicitly_disabled = self.state.is_explicitly_disabled_new;
 
                     if was_disabled != self.state.is_disabled() {
+                        self.state.needs_accessibility = true;
                         // TODO
                         let disabled = self.state.is_disabled();
                         self.call_widget_method_with_checks("lifecycle", |widget_pod| {
@@ -608,9 +700,53 @@ impl<W: Widget> WidgetPod<W> {
                 InternalLifeCycle::ParentWindowOrigin => {
                     self.state.parent_window_origin = parent_ctx.widget_state.window_origin();
                     self.state.needs_window_origin = false;
+                    // Accessibility bounds are in window coordinates.
+                    self.state.needs_accessibility = true;
                     // TODO - self.state.is_hidden
                     true
                 }
+                InternalLifeCycle::RouteTimer { token, widget_id } => {
+                    if *widget_id == self.state.id {
+                        self.call_routed_lifecycle(parent_ctx, &LifeCycle::Timer(*token));
+                        false
+                    } else {
+                        // The bloom filter we're checking can return false positives.
+                        self.state.children.may_contain(widget_id)
+                    }
+                }
+                InternalLifeCycle::RoutePromiseResult { result, widget_id } => {
+                    if *widget_id == self.state.id {
+                        let event = LifeCycle::PromiseResult(result.clone());
+                        self.call_routed_lifecycle(parent_ctx, &event);
+                        false
+                    } else {
+                        // The bloom filter we're checking can return false positives.
+                        self.state.children.may_contain(widget_id)
+                    }
+                }
+                InternalLifeCycle::RoutePopupClosed {
+                    popup_id,
+                    result,
+                    widget_id,
+                } => {
+                    if *widget_id == self.state.id {
+                        let event = LifeCycle::PopupClosed(*popup_id, result.clone());
+                        self.call_routed_lifecycle(parent_ctx, &event);
+                        false
+                    } else {
+                        // The bloom filter we're checking can return false positives.
+                        self.state.children.may_contain(widget_id)
+                    }
+                }
+                InternalLifeCycle::RoutePostLayout { widget_id } => {
+                    if *widget_id == self.state.id {
+                        self.call_routed_lifecycle(parent_ctx, &LifeCycle::PostLayout);
+                        false
+                    } else {
+                        // The bloom filter we're checking can return false positives.
+                        self.state.children.may_contain(widget_id)
+                    }
+                }
             },
             LifeCycle::WidgetAdded => {
                 if !self.state.is_new {
@@ -646,6 +782,7 @@ impl<W: Widget> WidgetPod<W> {
 
                 self.state.is_explicitly_disabled = self.state.is_explicitly_disabled_new;
                 self.state.ancestor_disabled = *ancestors_disabled;
+                self.state.needs_accessibility = true;
 
                 // the change direction (true -> false or false -> true) of our parent and ourself
                 // is always the same, or we dont change at all, because we stay disabled if either
@@ -666,6 +803,12 @@ impl<W: Widget> WidgetPod<W> {
             }
             // This is called by children when going up the widget tree.
             LifeCycle::RequestPanToChild(_) => false,
+            // Timers, promises, popups and post-layout events are only meaningful to the
+            // widget that requested them.
+            LifeCycle::Timer(_)
+            | LifeCycle::PromiseResult(_)
+            | LifeCycle::PopupClosed(..)
+            | LifeCycle::PostLayout => false,
         };
 
         // widget_pod is a reborrow of `self`
@@ -753,6 +896,18 @@ impl<W: Widget> WidgetPod<W> {
         parent_ctx.global_state.debug_logger.pop_span();
     }
 
+    /// Call the inner widget's `lifecycle` method with an event that was routed to it.
+    fn call_routed_lifecycle(&mut self, parent_ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.call_widget_method_with_checks("lifecycle", |widget_pod| {
+            let mut inner_ctx = LifeCycleCtx {
+                global_state: parent_ctx.global_state,
+                widget_state: &mut widget_pod.state,
+            };
+
+            widget_pod.inner.lifecycle(&mut inner_ctx, event);
+        });
+    }
+
     // --- LAYOUT ---
 
     /// Compute layout of a widget.
@@ -788,6 +943,7 @@ impl<W: Widget> WidgetPod<W> {
         self.state.is_expecting_place_child_call = true;
         // TODO - Not everything that has been re-laid out needs to be repainted.
         self.state.needs_paint = true;
+        self.state.needs_accessibility = true;
 
         bc.debug_check(self.inner.short_type_name());
 
@@ -934,6 +1090,78 @@ impl<W: Widget> WidgetPod<W> {
         scene.append(&self.fragment, Some(transform));
     }
 
+    // --- ACCESSIBILITY ---
+
+    /// Add the widget's accessibility node to the tree update, if it changed.
+    ///
+    /// Container widgets must call this on each of their children in their
+    /// [`accessibility`](Widget::accessibility) method.
+    pub fn accessibility(&mut self, parent_ctx: &mut AccessCtx) {
+        let _span = self.inner.make_trace_span().entered();
+
+        // TODO - explain this
+        self.mark_as_visited();
+        if self.state.is_stashed {
+            return;
+        }
+        self.check_initialized("accessibility");
+
+        if !parent_ctx.rebuild_all && !self.state.needs_accessibility {
+            return;
+        }
+        self.state.needs_accessibility = false;
+
+        trace!(
+            "Building accessibility node for widget '{}' #{}",
+            self.inner.short_type_name(),
+            self.state.id.to_raw()
+        );
+
+        let mut node = NodeBuilder::new(self.inner.accessibility_role());
+        let bounds = self.state.window_layout_rect();
+        node.set_bounds(accesskit::Rect {
+            x0: bounds.x0 * parent_ctx.scale_factor,
+            y0: bounds.y0 * parent_ctx.scale_factor,
+            x1: bounds.x1 * parent_ctx.scale_factor,
+            y1: bounds.y1 * parent_ctx.scale_factor,
+        });
+        node.set_children(
+            self.inner
+                .children()
+                .iter()
+                .filter(|child| !child.state().is_stashed)
+                .map(|child| NodeId::from(child.state().id))
+                .collect::<Vec<_>>(),
+        );
+        if self.state.is_disabled() {
+            node.set_disabled();
+        }
+        if self.state.focus_chain.contains(&self.state.id) {
+            node.add_action(accesskit::Action::Focus);
+        }
+
+        let node = self.call_widget_method_with_checks("accessibility", |widget_pod| {
+            // widget_pod is a reborrow of `self`
+            let mut inner_ctx = AccessCtx {
+                global_state: parent_ctx.global_state,
+                widget_state: &widget_pod.state,
+                tree_update: parent_ctx.tree_update,
+                current_node: node,
+                rebuild_all: parent_ctx.rebuild_all,
+                scale_factor: parent_ctx.scale_factor,
+            };
+
+            widget_pod.inner.accessibility(&mut inner_ctx);
+            inner_ctx.current_node
+        });
+
+        let node = node.build(&mut parent_ctx.global_state.access_node_classes);
+        parent_ctx
+            .tree_update
+            .nodes
+            .push((NodeId::from(self.state.id), node));
+    }
+
     fn debug_paint_layout_bounds(&mut self, size: Size) {
         const BORDER_WIDTH: f64 = 1.0;
         let rect = size.to_rect().inset(BORDER_WIDTH / -2.0);
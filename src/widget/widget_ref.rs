@@ -135,8 +135,10 @@ impl<'w> WidgetRef<'w, dyn Widget> {
 
     /// Recursively find innermost widget at given position.
     ///
-    /// **pos** - the position in local coordinates (zero being the top-left of the
-    /// inner widget).
+    /// **pos** - the position in the coordinate space of the widget's parent (for the
+    /// root widget, that's window coordinates).
+    ///
+    /// Where children overlap, the topmost one is picked, see [`Widget::get_child_at_pos`].
     pub fn find_widget_at_pos(&self, pos: Point) -> Option<WidgetRef<'w, dyn Widget>> {
         let mut pos = pos;
         let mut innermost_widget: WidgetRef<'w, dyn Widget> = *self;
@@ -146,8 +148,10 @@ impl<'w> WidgetRef<'w, dyn Widget> {
         }
 
         loop {
+            // The child's position is relative to its parent, and the position we pass
+            // it must be too.
+            pos -= innermost_widget.state().layout_rect().origin().to_vec2();
             if let Some(child) = innermost_widget.deref().get_child_at_pos(pos) {
-                pos -= innermost_widget.state().layout_rect().origin().to_vec2();
                 innermost_widget = child;
             } else {
                 return Some(innermost_widget);
@@ -84,6 +84,8 @@ pub struct WidgetState {
 
     pub(crate) needs_layout: bool,
     pub(crate) needs_paint: bool,
+    /// This widget or a descendant needs its accessibility node rebuilt.
+    pub(crate) needs_accessibility: bool,
 
     /// Because of some scrolling or something, `parent_window_origin` needs to be updated.
     pub(crate) needs_window_origin: bool,
@@ -106,6 +108,8 @@ pub struct WidgetState {
     pub(crate) cursor: Option<CursorIcon>,
 
     pub(crate) text_registrations: Vec<TextFieldRegistration>,
+    /// The area the IME candidate window should avoid, in local coordinates.
+    pub(crate) ime_area: Option<Rect>,
 
     // --- STATUS ---
     // `true` if one of our ancestors is disabled (meaning we are also disabled).
@@ -163,6 +167,7 @@ impl WidgetState {
             is_hot: false,
             needs_layout: false,
             needs_paint: false,
+            needs_accessibility: false,
             needs_window_origin: false,
             is_active: false,
             has_active: false,
@@ -176,6 +181,7 @@ impl WidgetState {
             cursor: None,
             is_explicitly_disabled_new: false,
             text_registrations: Vec::new(),
+            ime_area: None,
             update_focus_chain: false,
             is_stashed: false,
             #[cfg(debug_assertions)]
@@ -215,6 +221,7 @@ impl WidgetState {
     pub(crate) fn merge_up(&mut self, child_state: &mut WidgetState) {
         self.needs_layout |= child_state.needs_layout;
         self.needs_paint |= child_state.needs_paint;
+        self.needs_accessibility |= child_state.needs_accessibility;
         self.needs_window_origin |= child_state.needs_window_origin;
         self.request_anim |= child_state.request_anim;
         self.children_disabled_changed |= child_state.children_disabled_changed;
@@ -0,0 +1,378 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that stacks its children on top of each other.
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::paint_scene_helpers::UnitPoint;
+use crate::widget::{WidgetMut, WidgetRef};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+/// A container that lays out its children in the same area, on top of each other.
+///
+/// Children are painted in the order they were added, so the last child is on top.
+/// Pointer events go to the topmost child under the pointer first.
+///
+/// The stack is as large as its largest child. Smaller children are placed according
+/// to their alignment, or the stack's alignment if they don't have one.
+pub struct ZStack {
+    alignment: UnitPoint,
+    children: Vec<Child>,
+}
+
+crate::declare_widget!(ZStackMut, ZStack);
+
+struct Child {
+    widget: WidgetPod<Box<dyn Widget>>,
+    alignment: Option<UnitPoint>,
+}
+
+// --- METHODS ---
+
+impl ZStack {
+    /// Create a new empty stack, with its children centered.
+    pub fn new() -> Self {
+        ZStack {
+            alignment: UnitPoint::CENTER,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the alignment of children that don't have their own.
+    pub fn with_alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Builder-style method to add a child on top of the others.
+    pub fn with_child(mut self, child: impl Widget) -> Self {
+        self.children.push(Child {
+            widget: WidgetPod::new(Box::new(child)),
+            alignment: None,
+        });
+        self
+    }
+
+    /// Builder-style method to add a child with a given id on top of the others.
+    pub fn with_child_id(mut self, child: impl Widget, id: WidgetId) -> Self {
+        self.children.push(Child {
+            widget: WidgetPod::new_with_id(Box::new(child), id),
+            alignment: None,
+        });
+        self
+    }
+
+    /// Builder-style method to add a child with its own alignment on top of the others.
+    pub fn with_aligned_child(mut self, child: impl Widget, alignment: UnitPoint) -> Self {
+        self.children.push(Child {
+            widget: WidgetPod::new(Box::new(child)),
+            alignment: Some(alignment),
+        });
+        self
+    }
+
+    /// Return the number of children.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Return `true` if the stack has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl Default for ZStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZStackMut<'_> {
+    /// Set the alignment of children that don't have their own.
+    pub fn set_alignment(&mut self, alignment: UnitPoint) {
+        self.widget.alignment = alignment;
+        self.ctx.request_layout();
+    }
+
+    /// Add a child on top of the others.
+    ///
+    /// See also [`with_child`](ZStack::with_child).
+    pub fn add_child(&mut self, child: impl Widget) {
+        self.insert_child_pod(
+            self.widget.children.len(),
+            WidgetPod::new(Box::new(child)),
+            None,
+        );
+    }
+
+    /// Add a child with a given id on top of the others.
+    pub fn add_child_id(&mut self, child: impl Widget, id: WidgetId) {
+        self.insert_child_pod(
+            self.widget.children.len(),
+            WidgetPod::new_with_id(Box::new(child), id),
+            None,
+        );
+    }
+
+    /// Add a child with its own alignment on top of the others.
+    pub fn add_aligned_child(&mut self, child: impl Widget, alignment: UnitPoint) {
+        self.insert_child_pod(
+            self.widget.children.len(),
+            WidgetPod::new(Box::new(child)),
+            Some(alignment),
+        );
+    }
+
+    /// Insert a child at `idx`, above the children before it and below the ones after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is greater than the number of children.
+    pub fn insert_child(&mut self, idx: usize, child: impl Widget) {
+        self.insert_child_pod(idx, WidgetPod::new(Box::new(child)), None);
+    }
+
+    fn insert_child_pod(
+        &mut self,
+        idx: usize,
+        widget: WidgetPod<Box<dyn Widget>>,
+        alignment: Option<UnitPoint>,
+    ) {
+        self.widget
+            .children
+            .insert(idx, Child { widget, alignment });
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    /// Set the alignment of the child at `idx`.
+    ///
+    /// If `alignment` is `None`, the child uses the stack's alignment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn set_child_alignment(&mut self, idx: usize, alignment: Option<UnitPoint>) {
+        self.widget.children[idx].alignment = alignment;
+        self.ctx.request_layout();
+    }
+
+    /// Remove the child at `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_child(&mut self, idx: usize) {
+        self.widget.children.remove(idx);
+        self.ctx.children_changed();
+        self.ctx.request_layout();
+    }
+
+    // FIXME - Remove Box
+    /// Get a mutable reference to the child at `idx`.
+    pub fn child_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let child = &mut self.widget.children.get_mut(idx)?.widget;
+        Some(self.ctx.get_mut(child))
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for ZStack {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // Topmost first, so that a child that handles the event hides it from the ones below.
+        for child in self.children.iter_mut().rev() {
+            child.widget.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for child in &mut self.children {
+            child.widget.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for child in &mut self.children {
+            child.widget.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let child_bc = bc.loosen();
+        let mut max_size = Size::ZERO;
+        let mut child_sizes = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            let child_size = child.widget.layout(ctx, &child_bc);
+            max_size = Size::new(
+                max_size.width.max(child_size.width),
+                max_size.height.max(child_size.height),
+            );
+            child_sizes.push(child_size);
+        }
+        let size = bc.constrain(max_size);
+
+        for (child, child_size) in self.children.iter_mut().zip(child_sizes) {
+            let alignment = child.alignment.unwrap_or(self.alignment);
+            let free_space = (size - child_size).to_rect();
+            let origin = Point::ORIGIN + alignment.resolve(free_space).to_vec2();
+            ctx.place_child(&mut child.widget, origin);
+        }
+
+        let paint_rect = self
+            .children
+            .iter()
+            .map(|child| child.widget.paint_rect())
+            .fold(Rect::ZERO.with_size(size), |acc, rect| acc.union(rect));
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for child in &mut self.children {
+            child.widget.accessibility(ctx);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.children
+            .iter()
+            .map(|child| child.widget.as_dyn())
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ZStack")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Align, Button, SizedBox};
+
+    fn child_rect(harness: &TestHarness, id: WidgetId) -> Rect {
+        harness.get_widget(id).state().layout_rect()
+    }
+
+    #[test]
+    fn child_alignment() {
+        let [big_id, centered_id, corner_id] = widget_ids();
+        let widget = ZStack::new()
+            .with_child_id(SizedBox::empty().width(100.0).height(50.0), big_id)
+            .with_child_id(SizedBox::empty().width(20.0).height(10.0), centered_id)
+            .with_aligned_child(
+                SizedBox::empty()
+                    .width(20.0)
+                    .height(10.0)
+                    .with_id(corner_id),
+                UnitPoint::BOTTOM_RIGHT,
+            );
+        let widget = Align::left(widget);
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 400.0));
+
+        assert_eq!(
+            child_rect(&harness, big_id),
+            Rect::new(0.0, 0.0, 100.0, 50.0)
+        );
+        assert_eq!(
+            child_rect(&harness, centered_id),
+            Rect::new(40.0, 20.0, 60.0, 30.0)
+        );
+        assert_eq!(
+            child_rect(&harness, corner_id),
+            Rect::new(80.0, 40.0, 100.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn topmost_child_gets_pointer() {
+        let [bottom_id, top_id] = widget_ids();
+        let widget = ZStack::new()
+            .with_child_id(Button::new("Bottom button"), bottom_id)
+            .with_child_id(Button::new("Top"), top_id);
+
+        let mut harness = TestHarness::create(widget);
+
+        harness.mouse_click_on(top_id);
+        assert_eq!(harness.pop_action(), Some((Action::ButtonPressed, top_id)));
+        assert_eq!(harness.pop_action(), None);
+        assert!(harness.get_widget(top_id).state().is_hot);
+        assert!(!harness.get_widget(bottom_id).state().is_hot);
+
+        let pointer_pos = harness
+            .get_widget(top_id)
+            .state()
+            .window_layout_rect()
+            .center();
+        let hit = harness
+            .root_widget()
+            .find_widget_at_pos(pointer_pos)
+            .unwrap();
+        // The innermost widget is the top button's label.
+        let top_label_id = harness.get_widget(top_id).children()[0].id();
+        assert_eq!(hit.id(), top_label_id);
+    }
+
+    #[test]
+    fn edit_zstack() {
+        let [bottom_id, top_id] = widget_ids();
+        let widget = ZStack::new().with_child_id(Button::new("Bottom button"), bottom_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.edit_root_widget(|mut root| {
+            let mut zstack = root.downcast::<ZStack>().unwrap();
+            zstack.add_child_id(Button::new("Top"), top_id);
+            zstack.set_alignment(UnitPoint::TOP_LEFT);
+        });
+
+        assert_eq!(child_rect(&harness, top_id).origin(), Point::ORIGIN);
+        harness.mouse_click_on(top_id);
+        assert_eq!(harness.pop_action(), Some((Action::ButtonPressed, top_id)));
+        assert_eq!(harness.pop_action(), None);
+
+        harness.edit_root_widget(|mut root| {
+            let mut zstack = root.downcast::<ZStack>().unwrap();
+            zstack.remove_child(1);
+        });
+        assert!(harness.try_get_widget(top_id).is_none());
+
+        harness.mouse_click_on(bottom_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, bottom_id))
+        );
+    }
+}
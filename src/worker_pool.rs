@@ -0,0 +1,152 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A small pool of threads running background tasks.
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use tracing::{error, warn};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A fixed set of threads that run the jobs they're given in FIFO order.
+///
+/// A job that panics is logged, and its thread goes on running the next jobs.
+///
+/// Dropping the pool blocks until every job already submitted has completed,
+/// including the ones that haven't started yet. Since the event loop drops its pool
+/// when it exits, a job that never returns keeps the app from exiting.
+pub(crate) struct WorkerPool {
+    sender: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Create a pool with one thread per available CPU core.
+    pub(crate) fn new() -> Self {
+        let thread_count = std::thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(4);
+        Self::with_thread_count(thread_count)
+    }
+
+    pub(crate) fn with_thread_count(thread_count: usize) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let threads = (0..thread_count.max(1))
+            .map(|idx| {
+                let receiver = receiver.clone();
+                std::thread::Builder::new()
+                    .name(format!("masonry-worker-{idx}"))
+                    .spawn(move || run_worker(&receiver))
+                    .expect("failed to spawn worker thread")
+            })
+            .collect();
+
+        WorkerPool {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    /// Run `job` on one of the pool's threads.
+    pub(crate) fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        let sender = self.sender.as_ref().unwrap();
+        if sender.send(Box::new(job)).is_err() {
+            warn!("Cannot spawn background task: all worker threads have stopped.");
+        }
+    }
+}
+
+fn run_worker(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is released as soon as a job is received, so other
+        // workers can pick up jobs while this one runs.
+        let job = receiver.lock().unwrap().recv();
+        match job {
+            Ok(job) => {
+                if let Err(payload) = catch_unwind(AssertUnwindSafe(job)) {
+                    error!("A background task panicked: {}", panic_message(&*payload));
+                }
+            }
+            // The pool was dropped.
+            Err(_) => return,
+        }
+    }
+}
+
+/// The message a panic was raised with, if it has one.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "<no message>"
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // Closing the channel makes every worker exit once the queue is empty.
+        drop(self.sender.take());
+        for thread in self.threads.drain(..) {
+            if thread.join().is_err() {
+                warn!("A worker thread panicked.");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn run_all_jobs() {
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let pool = WorkerPool::with_thread_count(3);
+        for _ in 0..20 {
+            let counter = counter.clone();
+            pool.spawn(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(pool);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 20);
+    }
+
+    #[test]
+    fn survive_panicking_job() {
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let pool = WorkerPool::with_thread_count(1);
+        pool.spawn(|| panic!("background task failed"));
+        for _ in 0..5 {
+            let counter = counter.clone();
+            pool.spawn(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(pool);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn panic_messages() {
+        assert_eq!(panic_message(&"static message"), "static message");
+        assert_eq!(panic_message(&"owned message".to_string()), "owned message");
+        assert_eq!(panic_message(&42), "<no message>");
+    }
+}